@@ -0,0 +1,123 @@
+//! Benchmarks for the per-cycle control loop: FK, the generic and closed-form URT
+//! Jacobians, pseudo-inverse, IK and a full `TaskSpacePidController` step on the
+//! 6-DOF URT arm.
+//!
+//! Run with `cargo bench -p dh_arm_model`, or with `--profile fast` to measure the
+//! LTO / single-codegen-unit build used on embedded-class hardware. Everything on
+//! the hot path is fixed-size (`SMatrix`), so no step below heap-allocates.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use nalgebra::SVector;
+
+use dh_arm_model::dh::{DHTable, FkCache, Pose};
+use dh_arm_model::inverse_kinematics_solvers::{IkSolver, UrtIkSolver};
+use dh_arm_model::joint::Joint;
+use dh_arm_model::task_space_pid_controller::TaskSpacePidController;
+use dh_arm_model::urt::{self, NUM_FRAMES, NUM_JOINTS, URT_IK_LINK_PARAMETERS};
+
+fn urt_table() -> DHTable<NUM_FRAMES, NUM_JOINTS> {
+    urt::urt_dh_table()
+}
+
+fn urt_joints(positions_deg: &[f64; NUM_JOINTS]) -> [Joint; NUM_JOINTS] {
+    let mut joints = urt::urt_joints();
+    for (joint, &pos) in joints.iter_mut().zip(positions_deg) {
+        joint.set_position(pos);
+    }
+    joints
+}
+
+/// Two nearby configurations to alternate between so caches are actually exercised.
+const Q_A: [f64; NUM_JOINTS] = [10.0, 20.0, 30.0, 40.0, 50.0, 60.0];
+const Q_B: [f64; NUM_JOINTS] = [10.5, 20.5, 30.5, 40.5, 50.5, 60.5];
+/// Only the wrist moves between these, the common case for fine teleop.
+const Q_WRIST: [f64; NUM_JOINTS] = [10.0, 20.0, 30.0, 40.0, 50.0, 60.5];
+
+fn bench_kinematics(c: &mut Criterion) {
+    let table = urt_table();
+    let joints = urt_joints(&Q_A);
+
+    c.bench_function("fk/all_poses", |b| b.iter(|| table.all_poses(black_box(&joints))));
+
+    c.bench_function("fk/cache_refresh_all_rows", |b| {
+        let joints_b = urt_joints(&Q_B);
+        let mut cache = FkCache::new();
+        let mut flip = false;
+        b.iter(|| {
+            flip = !flip;
+            let js = if flip { &joints } else { &joints_b };
+            table.refresh_fk_cache(&mut cache, black_box(js))
+        })
+    });
+
+    c.bench_function("fk/cache_refresh_wrist_only", |b| {
+        let joints_w = urt_joints(&Q_WRIST);
+        let mut cache = FkCache::new();
+        let mut flip = false;
+        b.iter(|| {
+            flip = !flip;
+            let js = if flip { &joints } else { &joints_w };
+            table.refresh_fk_cache(&mut cache, black_box(js))
+        })
+    });
+
+    c.bench_function("jacobian/compute", |b| b.iter(|| table.compute_jacobian(black_box(&joints))));
+
+    let q_rad: [f64; NUM_JOINTS] = core::array::from_fn(|i| joints[i].position);
+    c.bench_function("jacobian/urt_analytic", |b| b.iter(|| urt::urt_jacobian(black_box(&q_rad))));
+
+    c.bench_function("jacobian/urt_analytic_from_fk_cache", |b| {
+        let mut cache = FkCache::new();
+        table.refresh_fk_cache(&mut cache, &joints);
+        b.iter(|| urt::urt_jacobian_from_rows(black_box(cache.row_transforms())))
+    });
+
+    let jac = table.compute_jacobian(&joints);
+    c.bench_function("jacobian/pseudo_inverse", |b| {
+        b.iter(|| table.damped_moore_penrose_pseudo_inverse(&joints, Some(black_box(&jac)), Some(1e-4)))
+    });
+}
+
+fn bench_ik(c: &mut Criterion) {
+    let table = urt_table();
+    let target: Pose = table.all_poses(&urt_joints(&Q_A))[NUM_FRAMES - 1].clone();
+    let solver = UrtIkSolver;
+
+    c.bench_function("ik/urt_closed_form", |b| {
+        b.iter(|| {
+            solver.solve_ik(
+                black_box(target.position.x),
+                target.position.y,
+                target.position.z,
+                &target.rotation,
+                &URT_IK_LINK_PARAMETERS,
+            )
+        })
+    });
+}
+
+fn bench_controller(c: &mut Criterion) {
+    let mut arm = urt::urt_arm(None);
+    let mut controller = TaskSpacePidController::new(
+        SVector::<f64, 6>::from([1.0, 1.0, 1.0, 0.0, 0.0, 0.0]),
+        SVector::<f64, 6>::zeros(),
+        SVector::<f64, 6>::zeros(),
+    );
+    let task_vel = [1.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+    let joint_vel = [0.0; NUM_JOINTS];
+    let dt = 0.001;
+
+    c.bench_function("controller/task_space_pid_step", |b| {
+        let mut flip = false;
+        b.iter(|| {
+            flip = !flip;
+            let q = if flip { &Q_A } else { &Q_B };
+            controller.compute(&mut arm, black_box(&task_vel), q, &joint_vel, dt)
+        })
+    });
+}
+
+criterion_group!(benches, bench_kinematics, bench_ik, bench_controller);
+criterion_main!(benches);
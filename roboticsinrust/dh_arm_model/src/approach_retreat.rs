@@ -0,0 +1,128 @@
+use crate::dh::Pose;
+use crate::dh_arm_model::DHArmModel;
+use crate::inverse_kinematics_solvers::IkSolver;
+
+/// Stand-off distances used to approach and leave a target along the tool Z axis.
+///
+/// Distances are in the same linear unit as the DH table (cm for the URT arm)
+/// and are measured backwards along the tool Z axis, i.e. the approach pose
+/// sits "above" the target from the tool's point of view.
+#[derive(Debug, Clone, Copy)]
+pub struct ApproachRetreat {
+    pub approach_distance: f64,
+    pub retreat_distance: f64,
+}
+
+impl ApproachRetreat {
+    pub fn new(approach_distance: f64, retreat_distance: f64) -> Self {
+        Self { approach_distance, retreat_distance }
+    }
+
+    /// Same stand-off distance for approach and retreat.
+    pub fn symmetric(distance: f64) -> Self {
+        Self::new(distance, distance)
+    }
+
+    pub fn approach_pose(&self, target: &Pose) -> Pose {
+        target.offset_along_tool_z(-self.approach_distance)
+    }
+
+    pub fn retreat_pose(&self, target: &Pose) -> Pose {
+        target.offset_along_tool_z(-self.retreat_distance)
+    }
+}
+
+/// Role of a generated waypoint within an approach / target / retreat move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaypointKind {
+    Approach,
+    Target,
+    Retreat,
+}
+
+/// A single Cartesian waypoint produced from a [`MotionTarget`].
+#[derive(Debug, Clone)]
+pub struct Waypoint {
+    pub kind: WaypointKind,
+    pub pose: Pose,
+}
+
+/// A Cartesian goal with optional approach / retreat motions attached.
+#[derive(Debug, Clone)]
+pub struct MotionTarget {
+    pub pose: Pose,
+    pub approach_retreat: Option<ApproachRetreat>,
+}
+
+impl MotionTarget {
+    pub fn new(pose: Pose) -> Self {
+        Self { pose, approach_retreat: None }
+    }
+
+    pub fn with_approach_retreat(pose: Pose, approach_retreat: ApproachRetreat) -> Self {
+        Self { pose, approach_retreat: Some(approach_retreat) }
+    }
+
+    /// Expands the target into its ordered waypoints: approach, target, retreat.
+    ///
+    /// Zero distances are skipped so a target without stand-off yields a single waypoint.
+    pub fn waypoints(&self) -> Vec<Waypoint> {
+        let mut out = Vec::with_capacity(3);
+
+        match &self.approach_retreat {
+            Some(ar) => {
+                if ar.approach_distance != 0.0 {
+                    out.push(Waypoint { kind: WaypointKind::Approach, pose: ar.approach_pose(&self.pose) });
+                }
+                out.push(Waypoint { kind: WaypointKind::Target, pose: self.pose.clone() });
+                if ar.retreat_distance != 0.0 {
+                    out.push(Waypoint { kind: WaypointKind::Retreat, pose: ar.retreat_pose(&self.pose) });
+                }
+            }
+            None => out.push(Waypoint { kind: WaypointKind::Target, pose: self.pose.clone() }),
+        }
+
+        out
+    }
+}
+
+/// A pick-and-place action: approach, grasp and retreat at `pick`, then the same at `place`.
+#[derive(Debug, Clone)]
+pub struct PickPlace {
+    pub pick: MotionTarget,
+    pub place: MotionTarget,
+}
+
+impl PickPlace {
+    /// Builds a pick-and-place action using the same stand-off for both ends.
+    pub fn new(pick: Pose, place: Pose, approach_retreat: ApproachRetreat) -> Self {
+        Self {
+            pick: MotionTarget::with_approach_retreat(pick, approach_retreat),
+            place: MotionTarget::with_approach_retreat(place, approach_retreat),
+        }
+    }
+
+    /// All waypoints of the action in execution order.
+    pub fn waypoints(&self) -> Vec<Waypoint> {
+        let mut out = self.pick.waypoints();
+        out.extend(self.place.waypoints());
+        out
+    }
+}
+
+/// Solves IK for every waypoint, returning joint targets in execution order.
+///
+/// Fails on the first unreachable waypoint, naming its index and role.
+pub fn solve_waypoints<const F: usize, const J: usize, S: IkSolver<J>>(
+    arm: &DHArmModel<F, J, S>,
+    waypoints: &[Waypoint],
+) -> Result<Vec<[f64; J]>, String> {
+    waypoints
+        .iter()
+        .enumerate()
+        .map(|(i, wp)| {
+            arm.solve_ik_from_pose(&wp.pose)
+                .map_err(|e| format!("Waypoint {} ({:?}) unreachable: {}", i, wp.kind, e))
+        })
+        .collect()
+}
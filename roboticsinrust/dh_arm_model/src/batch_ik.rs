@@ -0,0 +1,220 @@
+use std::f64::consts::TAU;
+use std::fmt;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::dh::Pose;
+use crate::dh_arm_model::DHArmModel;
+use crate::inverse_kinematics_solvers::{IkError, IkResidual, IkSolver, IkTolerance};
+use crate::joint::{Joint, JointType};
+use crate::units::JointAngles;
+
+/// Why one target of a batch has no usable IK solution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BatchIkFailure {
+    /// The wrist centre is outside the reachable annulus (see [`IkError::Unreachable`]).
+    OutOfWorkspace { distance: f64, min: f64, max: f64 },
+    /// The solver produced a NaN or infinite angle for `joint`, e.g. an
+    /// unreachable wrist orientation.
+    NonFinite { joint: usize },
+    /// The solution puts `joint` at `value`, outside its limits (deg / linear units).
+    JointLimit { joint: usize, value: f64, min: Option<f64>, max: Option<f64> },
+    /// FK of the solution misses the target (linear units / rad), e.g. a wrong branch.
+    Mismatch { position_error: f64, orientation_error: f64 },
+    /// Any other solver error.
+    Solver(IkError),
+}
+
+impl From<IkError> for BatchIkFailure {
+    fn from(e: IkError) -> Self {
+        match e {
+            IkError::Unreachable { distance, min, max } => BatchIkFailure::OutOfWorkspace { distance, min, max },
+            IkError::NonFiniteJoint { joint_index } => BatchIkFailure::NonFinite { joint: joint_index },
+            IkError::RoundTripMismatch { position_error, orientation_error } => {
+                BatchIkFailure::Mismatch { position_error, orientation_error }
+            }
+            e => BatchIkFailure::Solver(e),
+        }
+    }
+}
+
+impl fmt::Display for BatchIkFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchIkFailure::OutOfWorkspace { distance, min, max } => write!(
+                f, "out of workspace: wrist centre at {:.3}, reachable {:.3} to {:.3}", distance, min, max
+            ),
+            BatchIkFailure::NonFinite { joint } => write!(f, "non-finite solution for joint {}", joint + 1),
+            BatchIkFailure::JointLimit { joint, value, min, max } => {
+                let bound = |b: &Option<f64>| b.map_or("-".to_string(), |b| format!("{:.3}", b));
+                write!(f, "joint {} at {:.3} is outside its limits [{}, {}]", joint + 1, value, bound(min), bound(max))
+            }
+            BatchIkFailure::Mismatch { position_error, orientation_error } => write!(
+                f, "solution misses by {:.3e} in position and {:.3e} rad in orientation", position_error, orientation_error
+            ),
+            BatchIkFailure::Solver(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// A solved target of a batch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatchIkSolution<const J: usize> {
+    /// Within the joint limits; revolute joints are shifted by whole turns if that
+    /// brings them inside.
+    pub joints: JointAngles<J>,
+    pub residual: IkResidual,
+}
+
+/// Counts of each outcome over a batch, and the worst accepted residuals.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BatchIkStats {
+    pub targets: usize,
+    pub solved: usize,
+    pub out_of_workspace: usize,
+    pub non_finite: usize,
+    pub joint_limit: usize,
+    pub mismatch: usize,
+    pub other: usize,
+    /// Over solved targets (linear units).
+    pub max_position_error: f64,
+    /// Over solved targets (rad).
+    pub max_orientation_error: f64,
+    /// Wall time taken to solve the batch.
+    pub elapsed: Duration,
+}
+
+impl fmt::Display for BatchIkStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} targets solved in {:.1} ms ({} out of workspace, {} non-finite, {} joint limit, {} mismatch, {} other); \
+             max residual {:.3e} / {:.3e} rad",
+            self.solved,
+            self.targets,
+            self.elapsed.as_secs_f64() * 1e3,
+            self.out_of_workspace,
+            self.non_finite,
+            self.joint_limit,
+            self.mismatch,
+            self.other,
+            self.max_position_error,
+            self.max_orientation_error
+        )
+    }
+}
+
+/// Outcome of [`DHArmModel::solve_ik_batch`]: one result per target, in order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchIkReport<const J: usize> {
+    pub results: Vec<Result<BatchIkSolution<J>, BatchIkFailure>>,
+    pub stats: BatchIkStats,
+}
+
+impl<const J: usize> BatchIkReport<J> {
+    fn new(results: Vec<Result<BatchIkSolution<J>, BatchIkFailure>>, elapsed: Duration) -> Self {
+        let mut stats = BatchIkStats { targets: results.len(), elapsed, ..BatchIkStats::default() };
+        for result in &results {
+            match result {
+                Ok(solution) => {
+                    stats.solved += 1;
+                    stats.max_position_error = stats.max_position_error.max(solution.residual.position_error);
+                    stats.max_orientation_error = stats.max_orientation_error.max(solution.residual.orientation_error);
+                }
+                Err(BatchIkFailure::OutOfWorkspace { .. }) => stats.out_of_workspace += 1,
+                Err(BatchIkFailure::NonFinite { .. }) => stats.non_finite += 1,
+                Err(BatchIkFailure::JointLimit { .. }) => stats.joint_limit += 1,
+                Err(BatchIkFailure::Mismatch { .. }) => stats.mismatch += 1,
+                Err(BatchIkFailure::Solver(_)) => stats.other += 1,
+            }
+        }
+        Self { results, stats }
+    }
+
+    /// True if every target was solved.
+    pub fn all_solved(&self) -> bool {
+        self.stats.solved == self.stats.targets
+    }
+
+    /// Index and reason of every target that failed.
+    pub fn failures(&self) -> impl Iterator<Item = (usize, &BatchIkFailure)> {
+        self.results.iter().enumerate().filter_map(|(i, r)| r.as_ref().err().map(|e| (i, e)))
+    }
+}
+
+/// Moves a revolute joint by whole turns into its limits if that is possible, else
+/// reports the limit it violates.
+fn fit_to_limits(joint: &Joint, index: usize, q: f64) -> Result<f64, BatchIkFailure> {
+    let within = |q: f64| joint.limit_min.is_none_or(|min| q >= min) && joint.limit_max.is_none_or(|max| q <= max);
+    if within(q) {
+        return Ok(q);
+    }
+    if joint.joint_type == JointType::Revolute {
+        let reference = joint.limit_min.or(joint.limit_max).unwrap_or(q);
+        let shifted = q - ((q - reference) / TAU).floor() * TAU;
+        if let Some(q) = [shifted, shifted - TAU, shifted + TAU].into_iter().find(|&q| within(q)) {
+            return Ok(q);
+        }
+    }
+    let to_user = |v: f64| joint.joint_type.to_user_units(v);
+    Err(BatchIkFailure::JointLimit {
+        joint: index,
+        value: to_user(q),
+        min: joint.limit_min.map(to_user),
+        max: joint.limit_max.map(to_user),
+    })
+}
+
+impl<const F: usize, const J: usize, S: IkSolver<J>> DHArmModel<F, J, S> {
+    /// Solves one target of a batch: reachability pre-check, IK, joint limits, then the
+    /// FK round trip.
+    fn solve_batch_target(&self, pose: &Pose, tol: &IkTolerance) -> Result<BatchIkSolution<J>, BatchIkFailure> {
+        self.check_reachable(pose)?;
+        let mut q = self.solve_ik_from_pose(pose)?;
+        for (i, joint) in self.joints().iter().enumerate() {
+            q[i] = fit_to_limits(joint, i, q[i])?;
+        }
+        let residual = self.verify_ik_solution(&q, pose, tol)?;
+        Ok(BatchIkSolution { joints: JointAngles::from_radians(q), residual })
+    }
+
+    /// Solves IK for every pose of `poses` and reports, per target, the solution or
+    /// why there is none, with counts of each outcome — e.g. to check that a whole
+    /// scan path is reachable before running it.
+    ///
+    /// Each target is solved independently (no warm start), so the results do not
+    /// depend on the order of the poses. For paths that must also be followed
+    /// continuously, use [`DHArmModel::solve_path_ik`].
+    pub fn solve_ik_batch(&self, poses: &[Pose], tol: &IkTolerance) -> BatchIkReport<J> {
+        let start = Instant::now();
+        let results = poses.iter().map(|pose| self.solve_batch_target(pose, tol)).collect();
+        BatchIkReport::new(results, start.elapsed())
+    }
+
+    /// [`DHArmModel::solve_ik_batch`] split across `threads` threads (all available
+    /// cores if 0). Gives the same results; worth it from a few thousand targets.
+    pub fn solve_ik_batch_parallel(&self, poses: &[Pose], tol: &IkTolerance, threads: usize) -> BatchIkReport<J>
+    where
+        S: Sync,
+    {
+        let start = Instant::now();
+        let threads = match threads {
+            0 => thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        };
+        let chunk = poses.len().div_ceil(threads).max(1);
+        let results = thread::scope(|scope| {
+            let workers: Vec<_> = poses
+                .chunks(chunk)
+                .map(|chunk| {
+                    scope.spawn(move || chunk.iter().map(|pose| self.solve_batch_target(pose, tol)).collect::<Vec<_>>())
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("IK worker thread panicked"))
+                .collect()
+        });
+        BatchIkReport::new(results, start.elapsed())
+    }
+}
@@ -0,0 +1,219 @@
+use nalgebra::SVector;
+
+use crate::dh::Pose;
+use crate::dh_arm_model::DHArmModel;
+use crate::inverse_kinematics_solvers::{IkSolver, IkTolerance};
+use crate::joint::JointType;
+use crate::task_space_limits::limit_norm;
+use crate::units::{AngleUnit, AngularVelocityUnit, JointAngles};
+
+/// When and how the executor leaves Cartesian tracking near a singularity.
+#[derive(Debug, Clone, Copy)]
+pub struct SingularityReplan {
+    /// Jacobian condition number above which Cartesian tracking is paused. The
+    /// Jacobian mixes lengths and angles, so this depends on the length unit: for
+    /// the URT arm in cm, the default 2000 trips about 7° from the wrist singularity.
+    pub max_condition_number: f64,
+    /// Speed of the fastest joint during the joint-space detour (deg/s or linear units/s).
+    pub joint_speed: f64,
+    /// Waypoints to skip past the current one when choosing where to rejoin the path;
+    /// 0 rejoins at the waypoint that was being approached.
+    pub lookahead: usize,
+}
+
+impl Default for SingularityReplan {
+    fn default() -> Self {
+        Self { max_condition_number: 2000.0, joint_speed: 30.0, lookahead: 1 }
+    }
+}
+
+/// Where the executor currently is along the trajectory.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExecutorState<const J: usize> {
+    /// Tracking the straight-line path to waypoint `target`.
+    Tracking { target: usize },
+    /// Moving through joint space from `start` to the IK solution `goal` of waypoint
+    /// `rejoin` (deg / linear units), after which Cartesian tracking resumes.
+    Detour { rejoin: usize, start: [f64; J], goal: [f64; J], elapsed: f64, duration: f64 },
+    Finished,
+}
+
+/// Follows a list of end-effector waypoints by resolved-rate control, and detours
+/// through joint space when the Jacobian becomes ill-conditioned instead of
+/// grinding through the singularity.
+///
+/// Call [`TrajectoryExecutor::step`] once per control cycle with the measured joint
+/// positions; it returns joint velocity commands in the same units as
+/// [`TaskSpacePidController::compute`](crate::task_space_pid_controller::TaskSpacePidController::compute).
+pub struct TrajectoryExecutor<const J: usize> {
+    waypoints: Vec<Pose>,
+    /// Maximum EE linear speed (linear units/s).
+    pub linear_speed: f64,
+    /// Maximum EE angular speed (deg/s).
+    pub angular_speed: f64,
+    /// A waypoint counts as reached within this distance (linear units) ...
+    pub position_tolerance: f64,
+    /// ... and this orientation error (rad).
+    pub orientation_tolerance: f64,
+    pub replan: SingularityReplan,
+    /// IK solutions for detours are FK-checked against this; waypoints whose solution fails are skipped.
+    pub ik_tolerance: IkTolerance,
+    state: ExecutorState<J>,
+    replans: usize,
+}
+
+impl<const J: usize> TrajectoryExecutor<J> {
+    pub fn new(waypoints: Vec<Pose>, linear_speed: f64, angular_speed: f64) -> Self {
+        let state = if waypoints.is_empty() { ExecutorState::Finished } else { ExecutorState::Tracking { target: 0 } };
+        Self {
+            waypoints,
+            linear_speed,
+            angular_speed,
+            position_tolerance: 0.05,
+            orientation_tolerance: AngleUnit::Degree.to_radians(0.5),
+            replan: SingularityReplan::default(),
+            ik_tolerance: IkTolerance::default(),
+            state,
+            replans: 0,
+        }
+    }
+
+    /// A one-waypoint trajectory for a small relative move from the end-effector pose
+    /// at `joint_pos` (deg / linear units), for fine alignment.
+    ///
+    /// `offset` is `[dx, dy, dz, droll, dpitch, dyaw]`: linear units along the base
+    /// axes and degrees about the end-effector axes (see [`Pose::nudged`]).
+    pub fn nudge<const F: usize, S: IkSolver<J>>(
+        arm: &mut DHArmModel<F, J, S>,
+        joint_pos: &[f64; J],
+        offset: &[f64; 6],
+        linear_speed: f64,
+        angular_speed: f64,
+    ) -> Self {
+        arm.set_joint_positions(joint_pos);
+        let [dx, dy, dz, droll, dpitch, dyaw] = *offset;
+        let target = arm.end_effector_pose().nudged(dx, dy, dz, droll, dpitch, dyaw);
+        Self::new(vec![target], linear_speed, angular_speed)
+    }
+
+    pub fn state(&self) -> &ExecutorState<J> {
+        &self.state
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.state == ExecutorState::Finished
+    }
+
+    /// Number of joint-space detours taken so far.
+    pub fn replans(&self) -> usize {
+        self.replans
+    }
+
+    /// Computes the joint velocity command for one control cycle of length `dt`.
+    ///
+    /// * `joint_pos`: measured joint positions (deg / linear units).
+    ///
+    /// Fails if the arm has to leave Cartesian tracking but no remaining waypoint has an IK solution.
+    pub fn step<const F: usize, S: IkSolver<J>>(
+        &mut self,
+        arm: &mut DHArmModel<F, J, S>,
+        joint_pos: &[f64; J],
+        dt: f64,
+    ) -> Result<[f64; J], String> {
+        arm.set_joint_positions(joint_pos);
+
+        match self.state {
+            ExecutorState::Finished => Ok([0.0; J]),
+            ExecutorState::Detour { rejoin, start, goal, elapsed, duration } => {
+                let elapsed = elapsed + dt;
+                if elapsed < duration {
+                    self.state = ExecutorState::Detour { rejoin, start, goal, elapsed, duration };
+                    return Ok(core::array::from_fn(|i| (goal[i] - start[i]) / duration));
+                }
+                // Land exactly on the rejoin configuration, then carry on from there
+                self.state = self.tracking_after(rejoin);
+                Ok(core::array::from_fn(|i| (goal[i] - joint_pos[i]) / dt))
+            }
+            ExecutorState::Tracking { target } => self.track(arm, target, joint_pos, dt),
+        }
+    }
+
+    fn tracking_after(&self, waypoint: usize) -> ExecutorState<J> {
+        if waypoint + 1 < self.waypoints.len() {
+            ExecutorState::Tracking { target: waypoint + 1 }
+        } else {
+            ExecutorState::Finished
+        }
+    }
+
+    fn track<const F: usize, S: IkSolver<J>>(
+        &mut self,
+        arm: &mut DHArmModel<F, J, S>,
+        target: usize,
+        joint_pos: &[f64; J],
+        dt: f64,
+    ) -> Result<[f64; J], String> {
+        let ee = arm.end_effector_pose();
+        let goal = &self.waypoints[target];
+
+        let e_pos = goal.position - ee.position;
+        // Same cross-product orientation error as the task-space PID controller (world frame)
+        let e_ori = 0.5 * (ee.x_axis().cross(&goal.x_axis())
+            + ee.y_axis().cross(&goal.y_axis())
+            + ee.z_axis().cross(&goal.z_axis()));
+
+        // The cross-product error vanishes at 180°, so test arrival on the true angle
+        if e_pos.norm() <= self.position_tolerance && ee.rotation_angle_to(goal) <= self.orientation_tolerance {
+            self.state = self.tracking_after(target);
+            return match self.state {
+                ExecutorState::Tracking { target } => self.track(arm, target, joint_pos, dt),
+                _ => Ok([0.0; J]),
+            };
+        }
+
+        if arm.jacobian_condition_number() > self.replan.max_condition_number {
+            return self.start_detour(arm, target, joint_pos, dt);
+        }
+
+        // Head straight for the waypoint, capped at the configured speeds and without overshooting
+        let v = limit_norm(e_pos / dt, self.linear_speed);
+        let w = limit_norm(e_ori / dt, AngularVelocityUnit::DegreesPerSecond.to_radians_per_second(self.angular_speed));
+        let mut twist = SVector::<f64, 6>::zeros();
+        twist.fixed_rows_mut::<3>(0).copy_from(&v);
+        twist.fixed_rows_mut::<3>(3).copy_from(&w);
+
+        let qd = arm.inv_jacobian() * twist;
+        Ok(core::array::from_fn(|i| arm.joints()[i].joint_type.to_user_units(qd[i])))
+    }
+
+    /// Re-plans the rest of the path: a joint-space move to the first reachable
+    /// waypoint at least `lookahead` past `target`.
+    fn start_detour<const F: usize, S: IkSolver<J>>(
+        &mut self,
+        arm: &mut DHArmModel<F, J, S>,
+        target: usize,
+        joint_pos: &[f64; J],
+        dt: f64,
+    ) -> Result<[f64; J], String> {
+        let first = (target + self.replan.lookahead).min(self.waypoints.len() - 1);
+        let (rejoin, solution) = (first..self.waypoints.len())
+            .find_map(|i| arm.solve_ik_verified(&self.waypoints[i], &self.ik_tolerance).ok().map(|q| (i, q)))
+            .ok_or_else(|| format!(
+                "Singularity near waypoint {}: no later waypoint has an IK solution to re-plan through", target
+            ))?;
+
+        let joints = arm.joints();
+        let solution = JointAngles::from_radians(solution).to_user_units(joints);
+        let goal: [f64; J] = core::array::from_fn(|i| match joints[i].joint_type {
+            // Take the equivalent angle nearest the current one so the detour does not spin a joint
+            JointType::Revolute => solution[i] + 360.0 * ((joint_pos[i] - solution[i]) / 360.0).round(),
+            JointType::Prismatic => solution[i],
+        });
+        let largest_move = goal.iter().zip(joint_pos).map(|(g, q)| (g - q).abs()).fold(0.0, f64::max);
+        let duration = (largest_move / self.replan.joint_speed).max(dt);
+
+        self.replans += 1;
+        self.state = ExecutorState::Detour { rejoin, start: *joint_pos, goal, elapsed: 0.0, duration };
+        self.step(arm, joint_pos, dt)
+    }
+}
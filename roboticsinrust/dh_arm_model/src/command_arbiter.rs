@@ -0,0 +1,130 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Exclusive, time-limited right to command an arm.
+#[derive(Debug, Clone)]
+pub struct Lease {
+    pub owner: String,
+    pub priority: u8,
+    pub expires_at: Instant,
+}
+
+/// Snapshot of who controls an arm, shared with every client.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArbiterState {
+    /// Current lease holder, `None` if the arm is free.
+    pub owner: Option<String>,
+    pub priority: u8,
+    /// Time left before the lease lapses unless renewed.
+    pub remaining: Duration,
+}
+
+impl fmt::Display for ArbiterState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.owner {
+            Some(owner) => write!(
+                f, "{} (priority {}, {:.1}s left)",
+                owner, self.priority, self.remaining.as_secs_f64()
+            ),
+            None => write!(f, "free"),
+        }
+    }
+}
+
+/// Ownership/lease arbitration between clients commanding the same arm.
+///
+/// A client must hold the lease to command the arm. Every accepted command renews
+/// the lease; if the owner goes quiet for `lease_timeout` the arm becomes free.
+/// A client with strictly higher priority preempts the current owner, whose
+/// subsequent commands are rejected, so commands from different clients never
+/// interleave.
+#[derive(Debug, Clone)]
+pub struct CommandArbiter {
+    lease_timeout: Duration,
+    lease: Option<Lease>,
+}
+
+impl CommandArbiter {
+    pub fn new(lease_timeout: Duration) -> Self {
+        Self { lease_timeout, lease: None }
+    }
+
+    pub fn lease_timeout(&self) -> Duration {
+        self.lease_timeout
+    }
+
+    /// Drops the lease if it has lapsed.
+    fn expire(&mut self, now: Instant) {
+        if self.lease.as_ref().is_some_and(|l| now >= l.expires_at) {
+            self.lease = None;
+        }
+    }
+
+    /// Claims or renews the lease for `client`.
+    ///
+    /// Returns `Ok(true)` if ownership changed hands (the caller should discard any
+    /// command state left by the previous owner), `Ok(false)` on renewal, and an
+    /// error if another client holds the lease with equal or higher priority.
+    pub fn claim(&mut self, client: &str, priority: u8, now: Instant) -> Result<bool, String> {
+        self.expire(now);
+
+        let changed = match &self.lease {
+            Some(lease) if lease.owner == client => false,
+            Some(lease) if priority <= lease.priority => {
+                return Err(format!(
+                    "Arm is controlled by '{}' (priority {}) for another {:.1}s",
+                    lease.owner,
+                    lease.priority,
+                    (lease.expires_at - now).as_secs_f64()
+                ));
+            }
+            _ => true,
+        };
+
+        self.lease = Some(Lease {
+            owner: client.to_string(),
+            priority,
+            expires_at: now + self.lease_timeout,
+        });
+        Ok(changed)
+    }
+
+    /// Gives up the lease. Fails if `client` does not hold it.
+    pub fn release(&mut self, client: &str, now: Instant) -> Result<(), String> {
+        self.expire(now);
+        match &self.lease {
+            Some(lease) if lease.owner == client => {
+                self.lease = None;
+                Ok(())
+            }
+            Some(lease) => Err(format!("'{}' does not hold the lease (owner: '{}')", client, lease.owner)),
+            None => Err(format!("'{}' does not hold the lease (arm is free)", client)),
+        }
+    }
+
+    /// Current lease, if still valid at `now`.
+    pub fn lease(&self, now: Instant) -> Option<&Lease> {
+        self.lease.as_ref().filter(|l| now < l.expires_at)
+    }
+
+    pub fn is_owner(&self, client: &str, now: Instant) -> bool {
+        self.lease(now).is_some_and(|l| l.owner == client)
+    }
+
+    pub fn state(&self, now: Instant) -> ArbiterState {
+        match self.lease(now) {
+            Some(lease) => ArbiterState {
+                owner: Some(lease.owner.clone()),
+                priority: lease.priority,
+                remaining: lease.expires_at - now,
+            },
+            None => ArbiterState { owner: None, priority: 0, remaining: Duration::ZERO },
+        }
+    }
+}
+
+impl Default for CommandArbiter {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(1))
+    }
+}
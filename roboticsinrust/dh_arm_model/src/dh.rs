@@ -1,11 +1,25 @@
 use crate::joint::{Joint, JointType};
+use core::fmt;
 use nalgebra::{Matrix4, Matrix3,  Vector3, SMatrix};
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec::Vec};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// What drives a DH row: a joint of the arm (by index into its joint array) or nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameType {
+    Joint(usize),
+    /// A static offset, e.g. from the last joint to the tool point.
+    Fixed,
+}
 
 
 /// Represents a single row in a Denavit-Hartenberg (DH) parameter table.
 /// 
 /// This struct manages the transformation data for a single frame, which can
 /// either be a physical joint or a fixed frame offset.
+#[derive(Debug, Clone)]
 pub struct DHRow {
     a: f64,      
     alpha: f64,  
@@ -33,6 +47,31 @@ impl DHRow {
         }
     }
 
+    /// Creates a row bound to `frame`, which cannot disagree with itself the way the
+    /// `fixed_frame` / `joint_index` pair of [`DHRow::new`] can.
+    ///
+    /// As with `new`, `alpha` and `theta` are in **degrees**.
+    pub fn from_frame(a: f64, alpha: f64, d: f64, theta: f64, frame: FrameType) -> Self {
+        match frame {
+            FrameType::Joint(index) => Self::new(a, alpha, d, theta, false, Some(index)),
+            FrameType::Fixed => Self::new(a, alpha, d, theta, true, None),
+        }
+    }
+
+    /// What drives this row, or `None` for a joint row without a joint index.
+    pub fn frame_type(&self) -> Option<FrameType> {
+        if self.fixed_frame {
+            Some(FrameType::Fixed)
+        } else {
+            self.joint_index.map(FrameType::Joint)
+        }
+    }
+
+    /// `[a, alpha, d, theta]`, angles in degrees as passed to [`DHRow::new`].
+    pub fn parameters(&self) -> [f64; 4] {
+        [self.a, self.alpha.to_degrees(), self.d, self.theta.to_degrees()]
+    }
+
     /// Internal helper to generate a standard DH transformation matrix.
     /// 
     /// Uses the convention: T = T(x)*R(alpha)*T(z)*R(theta).
@@ -49,27 +88,26 @@ impl DHRow {
         )
     }
 
-    /// Computes the 4x4 transformation matrix for this row given the current joint states.
-    pub fn get_row_trans_mat(&self, joints: &[Joint]) -> Matrix4<f64> {
-        
-        let theta_total = if self.fixed_frame {
-            self.theta
+    /// Returns the joint value driving this row, or `None` for a fixed frame.
+    pub fn joint_value(&self, joints: &[Joint]) -> Option<f64> {
+        if self.fixed_frame {
+            None
         } else {
             let idx = self.joint_index.expect("Joint row missing joint_index");
-            match joints[idx].joint_type {
-                JointType::Revolute => self.theta + joints[idx].position,
-                JointType::Prismatic => self.theta,
-            }
-        };
-        
+            Some(joints[idx].position)
+        }
+    }
 
-        let d_total = if self.fixed_frame {
-            self.d
+    /// Computes the 4x4 transformation matrix for this row given the current joint states.
+    pub fn get_row_trans_mat(&self, joints: &[Joint]) -> Matrix4<f64> {
+        // The joint value adds to theta (revolute) or d (prismatic)
+        let (d_total, theta_total) = if self.fixed_frame {
+            (self.d, self.theta)
         } else {
             let idx = self.joint_index.expect("Joint row missing joint_index");
             match joints[idx].joint_type {
-                JointType::Revolute => self.d,
-                JointType::Prismatic => self.d + joints[idx].position,
+                JointType::Revolute => (self.d, self.theta + joints[idx].position),
+                JointType::Prismatic => (self.d + joints[idx].position, self.theta),
             }
         };
 
@@ -77,6 +115,7 @@ impl DHRow {
     }
 
     /// Print DH row info, showing joint type and current joint value if applicable
+    #[cfg(feature = "std")]
     pub fn print_row(&self, row_index: usize, joints: &[Joint]) {
         if self.fixed_frame {
             println!("Frame {}: Fixed Frame | a={:.2}, alpha={:.2}, d={:.2}, theta={:.2}",
@@ -99,6 +138,7 @@ impl DHRow {
 /// # Type Parameters
 /// * `F`: The number of Frames in the table.
 /// * `J`: The number of movable Joints.
+#[derive(Debug, Clone)]
 pub struct DHTable<const F: usize, const J: usize> {
     rows: [DHRow; F],
 }
@@ -107,42 +147,91 @@ impl<const F: usize, const J: usize> DHTable<F, J> {
     pub fn new(rows: [DHRow; F]) -> Self {
         Self { rows }
     }
-    pub fn transformation_matrix_j_i(&self, initial_row_index: usize, final_row_index:usize, joints: &[Joint; J]) -> Matrix4<f64> {
 
-        let r = F;
+    /// Like [`DHTable::new`], but refuses a table that [`DHTable::validate`] finds errors in.
+    pub fn try_new(rows: [DHRow; F]) -> Result<Self, String> {
+        let table = Self::new(rows);
+        table.validate()?;
+        Ok(table)
+    }
+
+    pub fn rows(&self) -> &[DHRow; F] {
+        &self.rows
+    }
+
+    /// Checks the table before it is used, returning the warnings on success.
+    ///
+    /// Errors: non-finite parameters, joint rows without a joint index or with one
+    /// outside `0..J`, two rows driven by the same joint, and joints no row uses.
+    /// Warnings: angles that look like radians passed where degrees are expected
+    /// (e.g. 1.5708 instead of 90).
+    pub fn validate(&self) -> Result<Vec<String>, String> {
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+        let mut driven_by: [Option<usize>; J] = [None; J];
+
+        for (i, row) in self.rows.iter().enumerate() {
+            let params = [("a", row.a), ("alpha", row.alpha), ("d", row.d), ("theta", row.theta)];
+            for (name, value) in params {
+                if !value.is_finite() {
+                    errors.push(format!("row {}: {} is {}", i, name, value));
+                }
+            }
+            for (name, value) in [("alpha", row.alpha), ("theta", row.theta)] {
+                if looks_like_radians(value.to_degrees()) {
+                    warnings.push(format!(
+                        "row {}: {} = {:.4} deg looks like a value in radians; DH angles are given in degrees",
+                        i, name, value.to_degrees()
+                    ));
+                }
+            }
+
+            match row.frame_type() {
+                Some(FrameType::Fixed) => {
+                    if let Some(index) = row.joint_index {
+                        warnings.push(format!("row {}: fixed frame ignores its joint index {}", i, index));
+                    }
+                }
+                Some(FrameType::Joint(index)) if index >= J => {
+                    errors.push(format!("row {}: joint index {} out of range (arm has {} joints)", i, index, J));
+                }
+                Some(FrameType::Joint(index)) => match driven_by[index] {
+                    Some(other) => errors.push(format!("rows {} and {} are both driven by joint {}", other, i, index)),
+                    None => driven_by[index] = Some(i),
+                },
+                None => errors.push(format!("row {}: joint row has no joint index", i)),
+            }
+        }
+        for (index, row) in driven_by.iter().enumerate() {
+            if row.is_none() {
+                errors.push(format!("joint {} does not drive any row", index));
+            }
+        }
 
+        if errors.is_empty() { Ok(warnings) } else { Err(errors.join("; ")) }
+    }
+    /// Transform from frame j to frame i: the product of rows j..i (exclusive i).
+    pub fn transformation_matrix_j_i(&self, initial_row_index: usize, final_row_index:usize, joints: &[Joint; J]) -> Matrix4<f64> {
         let j = initial_row_index;
         let i = final_row_index;
 
         assert!(
-            j < i && i <= r,
+            j < i && i <= F,
             "Invalid frame range: require 0 <= j < i <= {}, got j={}, i={}",
-            r, j, i
+            F, j, i
         );
 
-        let mut transformation_matrix = Matrix4::<f64>::identity();
-
-        //multiply transformation matrices from j to i-1
-        for f in j..i {
-            transformation_matrix *=  self.rows[f].get_row_trans_mat(joints);
-        }
-
-        transformation_matrix
+        self.chain_transform(j, i, joints)
     }
 
-        /// Get pose between frame j and frame i (exclusive i index convention)
+    /// Get pose between frame j and frame i (exclusive i index convention)
     pub fn pose_between_j_i(&self, j: usize, i: usize, joints: &[Joint; J]) -> Pose {
-        assert!(j < i && i <= F);
-        let mut transform = Matrix4::<f64>::identity();
-        for k in j..i {
-            transform *= self.rows[k].get_row_trans_mat(joints);
-        }
-        Pose::from_homogeneous(&transform)
+        Pose::from_homogeneous(&self.transformation_matrix_j_i(j, i, joints))
     }
 
      /// Compute poses for each frame relative to base frame (0).
     pub fn all_poses(&self, joints: &[Joint; J]) -> [Pose; F] {
-        let mut poses: [Pose; F] = std::array::from_fn(|_|  Pose::identity());
+        let mut poses: [Pose; F] = core::array::from_fn(|_|  Pose::identity());
         let mut transform = Matrix4::<f64>::identity();
 
         for i in 0..F {
@@ -155,11 +244,45 @@ impl<const F: usize, const J: usize> DHTable<F, J> {
 
     pub fn get_frame_pose(&self, frame_index: usize, joints: &[Joint; J]) -> Pose {
         assert!(frame_index < F);
-        let mut transform = Matrix4::<f64>::identity();
-        for k in 0..frame_index {
-            transform *= self.rows[k].get_row_trans_mat(joints);
+        Pose::from_homogeneous(&self.chain_transform(0, frame_index, joints))
+    }
+
+    /// Product of the row transforms `from..to`; the single walk of the chain that the
+    /// frame and pose queries above share (identity for an empty range).
+    fn chain_transform(&self, from: usize, to: usize, joints: &[Joint; J]) -> Matrix4<f64> {
+        self.rows[from..to]
+            .iter()
+            .fold(Matrix4::identity(), |transform, row| transform * row.get_row_trans_mat(joints))
+    }
+
+    /// Brings `cache` up to date with the given joint states.
+    ///
+    /// Only rows whose joint value differs from the one the cached transform was
+    /// built with are recomputed; cumulative products are rebuilt from the first
+    /// changed row onwards. Returns `true` if any frame pose changed.
+    pub fn refresh_fk_cache(&self, cache: &mut FkCache<F>, joints: &[Joint; J]) -> bool {
+        let mut first_changed = None;
+
+        for (i, row) in self.rows.iter().enumerate() {
+            // Fixed frames are keyed by 0.0: computed once, never invalidated.
+            let key = row.joint_value(joints).unwrap_or(0.0);
+            if cache.row_keys[i] != Some(key) {
+                cache.row_transforms[i] = row.get_row_trans_mat(joints);
+                cache.row_keys[i] = Some(key);
+                first_changed.get_or_insert(i);
+            }
+        }
+
+        let Some(start) = first_changed else { return false };
+
+        let mut transform = if start == 0 { Matrix4::identity() } else { cache.cumulative[start - 1] };
+        for i in start..F {
+            transform *= cache.row_transforms[i];
+            cache.cumulative[i] = transform;
+            cache.poses[i] = Pose::from_homogeneous(&transform);
         }
-        Pose::from_homogeneous(&transform)
+
+        true
     }
 
     /// Computes the geometric Jacobian matrix ($6 \times J$) for the current configuration.
@@ -167,6 +290,12 @@ impl<const F: usize, const J: usize> DHTable<F, J> {
     /// The top 3 rows represent linear velocity mapping; the bottom 3 represent angular.
     pub fn compute_jacobian(&self, joints: &[Joint; J]) -> SMatrix<f64, 6, J> {
         let poses = self.all_poses(joints);
+        self.jacobian_from_poses(&poses, joints)
+    }
+
+    /// Computes the geometric Jacobian from already known frame poses (as returned by
+    /// [`DHTable::all_poses`] or an [`FkCache`]), avoiding a second walk of the chain.
+    pub fn jacobian_from_poses(&self, poses: &[Pose; F], joints: &[Joint; J]) -> SMatrix<f64, 6, J> {
         let p_end = poses[F - 1].position;
 
         let mut j = SMatrix::<f64,6, J>::zeros(); 
@@ -229,19 +358,11 @@ impl<const F: usize, const J: usize> DHTable<F, J> {
         if J >= 6 {
             // --- RIGHT PSEUDO-INVERSE (Redundant/Full-DOF) ---
             // Formula: Jᵀ * (J * Jᵀ + λ²I)⁻¹
-            
-            let mut damped_inner: SMatrix<f64, 6, 6> = j * jt;
-            
-            // Add damping to the 6x6 diagonal
-            for i in 0..6 {
-                damped_inner[(i, i)] += l2;
-            }
-
-            // Invert 6x6 and multiply by Jᵀ
-            match damped_inner.try_inverse() {
-                Some(inv) => jt * inv,
+            match damped_right_pseudo_inverse(j, l2) {
+                Some(pinv) => pinv,
                 None => {
                     // Fallback if matrix is still singular (e.g. NaNs in Jacobian)
+                    #[cfg(feature = "std")]
                     eprintln!("Warning: Right inverse failed, returning zeros");
                     SMatrix::<f64, J, 6>::zeros()
                 }
@@ -261,6 +382,7 @@ impl<const F: usize, const J: usize> DHTable<F, J> {
             match damped_inner.try_inverse() {
                 Some(inv) => inv * jt,
                 None => {
+                    #[cfg(feature = "std")]
                     eprintln!("Warning: Left inverse failed, returning zeros");
                     SMatrix::<f64, J, 6>::zeros()
                 }
@@ -268,6 +390,21 @@ impl<const F: usize, const J: usize> DHTable<F, J> {
         }
     }
 
+    /// Prints the table (see its `Display`), then any problems [`DHTable::validate`] finds.
+    #[cfg(feature = "std")]
+    pub fn print_diagnostics(&self) {
+        print!("{}", self);
+        match self.validate() {
+            Ok(warnings) => {
+                for warning in warnings {
+                    println!("warning: {}", warning);
+                }
+            }
+            Err(e) => println!("error: {}", e),
+        }
+    }
+
+    #[cfg(feature = "std")]
     pub fn print_table(&self, joints: &[Joint; J]) {
         println!("================ DH TABLE ================");
         for (i, row) in self.rows.iter().enumerate() {
@@ -278,10 +415,171 @@ impl<const F: usize, const J: usize> DHTable<F, J> {
 
 }
 
+impl fmt::Display for DHRow {
+    /// `a` and `d` in the table's length unit, `alpha` and `theta` in degrees.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "a={:.3}, alpha={:.2} deg, d={:.3}, theta={:.2} deg, ",
+            self.a, self.alpha.to_degrees(), self.d, self.theta.to_degrees()
+        )?;
+        match self.frame_type() {
+            Some(FrameType::Joint(index)) => write!(f, "joint {}", index),
+            Some(FrameType::Fixed) => write!(f, "fixed"),
+            None => write!(f, "joint ?"),
+        }
+    }
+}
+
+impl<const F: usize, const J: usize> fmt::Display for DHTable<F, J> {
+    /// One line per row with its frame chain link; lengths in the table's unit, angles in degrees.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "DH table: {} frames, {} joints (lengths in table units, angles in deg)", F, J)?;
+        writeln!(f, "{:>5} {:>10} {:>10} {:>10} {:>10}  driven by", "row", "a", "alpha", "d", "theta")?;
+        for (i, row) in self.rows.iter().enumerate() {
+            let driver = match row.frame_type() {
+                Some(FrameType::Joint(index)) => format!("joint {}", index),
+                Some(FrameType::Fixed) => String::from("fixed"),
+                None => String::from("joint ?"),
+            };
+            writeln!(
+                f,
+                "{:>5} {:>10.3} {:>10.2} {:>10.3} {:>10.2}  {} (frame {} -> {})",
+                i, row.a, row.alpha.to_degrees(), row.d, row.theta.to_degrees(), driver, i, i + 1
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// An angle (degrees) that is not a round number of degrees but is close to a
+/// multiple of 45° expressed in radians (0.7854, 1.5708, 3.1416, ...).
+fn looks_like_radians(degrees: f64) -> bool {
+    let quarter_pi = core::f64::consts::FRAC_PI_4;
+    let k = (degrees / quarter_pi).round();
+    k != 0.0 && k.abs() <= 8.0 && (degrees - k * quarter_pi).abs() < 1e-3 && (degrees - degrees.round()).abs() > 1e-6
+}
+
+/// Jᵀ(JJᵀ + λ²I)⁻¹ for a 6 x J Jacobian, `None` if the damped matrix is not positive definite.
+///
+/// JJᵀ + λ²I is symmetric positive definite, so instead of a general 6x6 LU inverse
+/// this factors it with a Cholesky on a plain 6x6 array and solves for the result
+/// directly, using ((JJᵀ + λ²I)⁻¹J)ᵀ. This is the per-cycle hot path of the
+/// 6-joint arm; it works on slices rather than nalgebra indexing so it stays fast
+/// without LTO.
+fn damped_right_pseudo_inverse<const J: usize>(j: &SMatrix<f64, 6, J>, l2: f64) -> Option<SMatrix<f64, J, 6>> {
+    // Column-major storage: column k of J is cols[6k..6k+6]
+    let cols = j.as_slice();
+
+    // Lower triangle of JJᵀ + λ²I
+    let mut l = [[0.0f64; 6]; 6];
+    for col in cols.chunks_exact(6) {
+        for (r, row) in l.iter_mut().enumerate() {
+            for (c, value) in row.iter_mut().enumerate().take(r + 1) {
+                *value += col[r] * col[c];
+            }
+        }
+    }
+    for (i, row) in l.iter_mut().enumerate() {
+        row[i] += l2;
+    }
+
+    // In-place Cholesky: A = L Lᵀ
+    for c in 0..6 {
+        let (upper, lower) = l.split_at_mut(c + 1);
+        let pivot_row = &mut upper[c];
+        let d = pivot_row[c] - pivot_row[..c].iter().map(|v| v * v).sum::<f64>();
+        if d.is_nan() || d <= 0.0 {
+            return None;
+        }
+        let d = d.sqrt();
+        pivot_row[c] = d;
+        for row in lower.iter_mut() {
+            let s: f64 = row[..c].iter().zip(&pivot_row[..c]).map(|(a, b)| a * b).sum();
+            row[c] = (row[c] - s) / d;
+        }
+    }
+
+    // Solve L Lᵀ x = (column of J) for each joint; x becomes a row of the result
+    let mut pinv = SMatrix::<f64, J, 6>::zeros();
+    for (k, col) in cols.chunks_exact(6).enumerate() {
+        let mut y = [0.0f64; 6];
+        for r in 0..6 {
+            let s: f64 = l[r][..r].iter().zip(&y[..r]).map(|(a, b)| a * b).sum();
+            y[r] = (col[r] - s) / l[r][r];
+        }
+        for r in (0..6).rev() {
+            let s: f64 = ((r + 1)..6).map(|i| l[i][r] * y[i]).sum();
+            y[r] = (y[r] - s) / l[r][r];
+        }
+        for (r, value) in y.iter().enumerate() {
+            pinv[(k, r)] = *value;
+        }
+    }
+    Some(pinv)
+}
+
+/// Cached forward kinematics for a [`DHTable`], refreshed with [`DHTable::refresh_fk_cache`].
+///
+/// Holds each row's transform together with the joint value it was computed for,
+/// and the cumulative base-to-frame products, so that a control cycle where only
+/// the wrist moved does not re-multiply the whole chain.
+pub struct FkCache<const F: usize> {
+    /// Joint value each row transform was built with (`None` = never computed).
+    row_keys: [Option<f64>; F],
+    row_transforms: [Matrix4<f64>; F],
+    /// `cumulative[i]` is the product of rows `0..=i`.
+    cumulative: [Matrix4<f64>; F],
+    poses: [Pose; F],
+}
+
+impl<const F: usize> FkCache<F> {
+    /// Creates an empty cache; every row is computed on the first refresh.
+    pub fn new() -> Self {
+        Self {
+            row_keys: [None; F],
+            row_transforms: [Matrix4::identity(); F],
+            cumulative: [Matrix4::identity(); F],
+            poses: core::array::from_fn(|_| Pose::identity()),
+        }
+    }
+
+    /// Cached poses for each frame relative to the base (same layout as [`DHTable::all_poses`]).
+    pub fn poses(&self) -> &[Pose; F] {
+        &self.poses
+    }
+
+    /// Cached transform of each DH row on its own, at the current joint values.
+    pub fn row_transforms(&self) -> &[Matrix4<f64>; F] {
+        &self.row_transforms
+    }
+
+    /// Cached pose of frame `frame_index` (same convention as [`DHTable::get_frame_pose`]).
+    pub fn frame_pose(&self, frame_index: usize) -> Pose {
+        assert!(frame_index < F);
+        if frame_index == 0 {
+            Pose::identity()
+        } else {
+            self.poses[frame_index - 1].clone()
+        }
+    }
+
+    /// Forces every row to be recomputed on the next refresh.
+    pub fn invalidate(&mut self) {
+        self.row_keys = [None; F];
+    }
+}
+
+impl<const F: usize> Default for FkCache<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 
 /// Represents the pose of a frame using a vector for position and a rotation matrix for orientation.
 /// Converts between homogeneous transformation matrices and this structured format for easier manipulation in task-space control.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Pose {
     pub position: Vector3<f64>,
     pub rotation: Matrix3<f64>,
@@ -321,6 +619,49 @@ impl Pose {
     /// Returns the z-axis of this frame (the joint axis direction).
     pub fn z_axis(&self) -> Vector3<f64> { self.rotation.column(2).into() }
 
+    /// Composes two poses: `other` expressed in this frame, mapped into this frame's parent.
+    ///
+    /// Equivalent to multiplying the homogeneous transforms `self * other`.
+    pub fn compose(&self, other: &Pose) -> Pose {
+        Pose::new(self.position + self.rotation * other.position, self.rotation * other.rotation)
+    }
+
+    /// The inverse transform: this frame's parent expressed in this frame.
+    pub fn inverse(&self) -> Pose {
+        let rotation = self.rotation.transpose();
+        Pose::new(-(rotation * self.position), rotation)
+    }
+
+    /// Angle (radians) of the rotation taking this pose's orientation to `other`'s.
+    ///
+    /// Uses atan2 of the skew-symmetric and trace parts rather than `acos` of the
+    /// trace, which loses precision for near-identity rotations.
+    pub fn rotation_angle_to(&self, other: &Pose) -> f64 {
+        let r = self.rotation.transpose() * other.rotation;
+        let sin_part = Vector3::new(
+            r[(2, 1)] - r[(1, 2)],
+            r[(0, 2)] - r[(2, 0)],
+            r[(1, 0)] - r[(0, 1)],
+        ).norm() / 2.0;
+        let cos_part = (r.trace() - 1.0) / 2.0;
+        sin_part.atan2(cos_part)
+    }
+
+    /// Returns a copy of this pose translated by `distance` along its own z-axis.
+    ///
+    /// Negative distances move "backwards" out of the tool, as used for approach/retreat poses.
+    pub fn offset_along_tool_z(&self, distance: f64) -> Pose {
+        Pose::new(self.position + self.z_axis() * distance, self.rotation)
+    }
+
+    /// Returns a copy of this pose moved by a small relative offset: `(dx, dy, dz)` in
+    /// the parent frame, then rotated by `(droll, dpitch, dyaw)` degrees about this
+    /// frame's own axes, the task-space controller's convention for jogs.
+    pub fn nudged(&self, dx: f64, dy: f64, dz: f64, droll: f64, dpitch: f64, dyaw: f64) -> Pose {
+        let rotation = Self::orientation_mat(dyaw.to_radians(), dpitch.to_radians(), droll.to_radians());
+        Pose::new(self.position + Vector3::new(dx, dy, dz), self.rotation * rotation)
+    }
+
     /// Compute orientation matrix from yaw (Z), pitch (Y), roll (X).
     /// Rotation order: Z * Y * X (yaw, pitch, roll).
     pub fn orientation_mat(yaw: f64, pitch: f64, roll: f64) -> Matrix3<f64> {
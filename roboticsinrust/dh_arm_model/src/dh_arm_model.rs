@@ -1,11 +1,48 @@
-use std::usize;
-
-use crate::dh::{DHTable, Pose};
+use crate::dh::{DHTable, FkCache, Pose};
+use crate::dynamics::{LinkInertia, PseudoInverse};
 use crate::joint::{Joint};
+use crate::units::{JointAngles, LengthUnit};
+#[cfg(feature = "std")]
+use crate::pose_snapshot::PoseSnapshot;
+#[cfg(feature = "profiling")]
+use crate::profiling::{Phase, PhaseTimings};
+#[cfg(feature = "profiling")]
+use std::time::Instant;
+
+use crate::inverse_kinematics_solvers::{IkError, IkResidual, IkSolver, IkTolerance}; // <-- IMPORT TRAIT 
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 
-use crate::inverse_kinematics_solvers::IkSolver; // <-- IMPORT TRAIT 
+use nalgebra::{DMatrix, Matrix3, Matrix4, SMatrix, SVector, Vector3};
 
-use nalgebra::{SMatrix, SVector};
+/// Standard gravitational acceleration (m/s²).
+pub const STANDARD_GRAVITY: f64 = 9.80665;
+
+/// When the cached Jacobian and its inverse are recomputed after joints move.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JacobianRefresh {
+    /// Recompute on the first update after any joint moved (default).
+    EveryChange,
+    /// Reuse the cached matrices for up to `max_cycles` updates after joints moved,
+    /// or until some joint is more than `max_joint_delta` (rad / linear units) away
+    /// from where they were computed. Trades accuracy for per-cycle cost on slow hardware.
+    Reduced { max_cycles: u32, max_joint_delta: f64 },
+}
+
+/// How out of date the cached Jacobian is, see [`DHArmModel::jacobian_staleness`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JacobianStaleness {
+    /// Updates served from the cache since the last recompute.
+    pub cycles: u32,
+    /// Largest joint displacement since the last recompute (rad / linear units).
+    pub max_joint_delta: f64,
+}
+
+/// Closed-form Jacobian of a specific arm, in the same frames as
+/// [`DHTable::jacobian_from_poses`]. It is given the per-row DH transforms from the
+/// FK cache ([`FkCache::row_transforms`]) so it can reuse their joint sines and cosines.
+pub type AnalyticJacobian<const F: usize, const J: usize> = fn(&[Matrix4<f64>; F]) -> SMatrix<f64, 6, J>;
 
 /// High-level controller for a robotic arm defined by Denavit-Hartenberg parameters.
 /// 
@@ -22,9 +59,11 @@ pub struct DHArmModel<const F: usize, const J: usize, S: IkSolver<J>> {
     dh_table: DHTable<F, J>,          
     /// State of each physical joint (position, velocity, limits).
     joints: [Joint ; J],        
+    /// Per-row and cumulative FK transforms, refreshed whenever joint positions change.
+    fk_cache: FkCache<F>,
     /// Cached geometric Jacobian
     jacobian: Option<SMatrix<f64, 6, J>>,  
-    /// Cached damped Moore-Penrose pseudo-inverse of the Jacobian
+    /// Cached pseudo-inverse of the Jacobian (see [`PseudoInverse`])
     inv_jacobian: Option<SMatrix<f64, J, 6>>, 
 
     /// State flag indicating if joint positions have changed since the last update.
@@ -32,10 +71,27 @@ pub struct DHArmModel<const F: usize, const J: usize, S: IkSolver<J>> {
     dirty: bool,                 
     /// Damping factor ($\lambda$) used in pseudo-inverse to handle singularities.
     damping: f64,                
+    jacobian_refresh: JacobianRefresh,
+    /// Specialized Jacobian used instead of the generic one from the FK cache.
+    analytic_jacobian: Option<AnalyticJacobian<F, J>>,
+    /// Gravitational acceleration in the base frame (DH length unit / s²).
+    gravity: Vector3<f64>,
+    /// Mass properties of the link carried by each frame, `None` if massless.
+    link_inertias: [Option<LinkInertia>; F],
+    pseudo_inverse: PseudoInverse,
+    /// Unit the DH table's lengths are in.
+    length_unit: LengthUnit,
+    /// Joint positions the cached Jacobian was computed at.
+    jacobian_joint_positions: [f64; J],
+    /// Updates served from the cached Jacobian since it was last recomputed.
+    jacobian_age: u32,
 
     ik_solver: S, // Inverse Kinematics solver
     /// Generic list of link parameters needed by the specific IkSolver.
     ik_link_parameters: Vec<f64>,
+    /// Time spent in FK / Jacobian / pseudo-inverse since the last `take_timings`.
+    #[cfg(feature = "profiling")]
+    timings: PhaseTimings,
 }
 
 impl<const F: usize, const J: usize, S: IkSolver<J>> DHArmModel<F, J, S> {
@@ -49,15 +105,29 @@ impl<const F: usize, const J: usize, S: IkSolver<J>> DHArmModel<F, J, S> {
         ik_solver: S,
         ik_link_parameters: Vec<f64>
     ) -> Self {
+        let mut fk_cache = FkCache::new();
+        dh_table.refresh_fk_cache(&mut fk_cache, &joints);
+
         Self {
             dh_table,
             joints,
+            fk_cache,
             jacobian: None,
             inv_jacobian: None,
             dirty: true,
             damping: damping.unwrap_or(1e-4),
+            jacobian_refresh: JacobianRefresh::EveryChange,
+            analytic_jacobian: None,
+            gravity: Vector3::new(0.0, 0.0, -STANDARD_GRAVITY),
+            link_inertias: [None; F],
+            pseudo_inverse: PseudoInverse::Kinematic,
+            length_unit: LengthUnit::Metre,
+            jacobian_joint_positions: [0.0; J],
+            jacobian_age: 0,
             ik_solver,
             ik_link_parameters,
+            #[cfg(feature = "profiling")]
+            timings: PhaseTimings::default(),
         }
     }
 
@@ -65,7 +135,77 @@ impl<const F: usize, const J: usize, S: IkSolver<J>> DHArmModel<F, J, S> {
         &self.dh_table
     }
 
-    /// Updates the position of all joints and marks the kinematics as "dirty."
+    /// Gravitational acceleration in the arm's base frame, in the DH table's length
+    /// unit per s². Anything that models loads on the arm should read gravity from here.
+    ///
+    /// Defaults to [`STANDARD_GRAVITY`] along -Z, i.e. a floor-mounted arm, in the
+    /// arm's [`LengthUnit`].
+    pub fn gravity(&self) -> Vector3<f64> {
+        self.gravity
+    }
+
+    pub fn set_gravity(&mut self, gravity: Vector3<f64>) {
+        self.gravity = gravity;
+    }
+
+    /// Mass properties of the link carried by frame `frame_index`, if set.
+    pub fn link_inertia(&self, frame_index: usize) -> Option<&LinkInertia> {
+        self.link_inertias[frame_index].as_ref()
+    }
+
+    pub fn link_inertias(&self) -> &[Option<LinkInertia>; F] {
+        &self.link_inertias
+    }
+
+    /// Gives the link carried by frame `frame_index` mass properties (see
+    /// [`DHArmModel::mass_matrix`]); `None` makes it massless. Lengths are in the DH
+    /// table's unit.
+    pub fn set_link_inertia(&mut self, frame_index: usize, inertia: Option<LinkInertia>) {
+        self.link_inertias[frame_index] = inertia;
+        if self.pseudo_inverse == PseudoInverse::DynamicallyConsistent {
+            self.invalidate_jacobian();
+        }
+    }
+
+    pub fn pseudo_inverse(&self) -> PseudoInverse {
+        self.pseudo_inverse
+    }
+
+    /// Chooses the inverse [`DHArmModel::inv_jacobian`] returns, and so the one the
+    /// controllers map task commands through.
+    pub fn set_pseudo_inverse(&mut self, pseudo_inverse: PseudoInverse) {
+        self.pseudo_inverse = pseudo_inverse;
+        self.invalidate_jacobian();
+    }
+
+    /// Unit of the DH table's lengths, and so of positions, linear velocities and gravity.
+    pub fn length_unit(&self) -> LengthUnit {
+        self.length_unit
+    }
+
+    /// Declares the unit the DH table's lengths are in (metres by default).
+    ///
+    /// The table is not rescaled; gravity is converted to the new unit.
+    pub fn set_length_unit(&mut self, unit: LengthUnit) {
+        self.gravity *= unit.per_metre() / self.length_unit.per_metre();
+        self.length_unit = unit;
+    }
+
+    /// Points gravity along world -Z for an arm whose base frame has orientation
+    /// `base_rotation` in the world (e.g. rotated 180° about X when ceiling-mounted,
+    /// 90° when wall-mounted), keeping the current magnitude.
+    pub fn set_gravity_from_mounting(&mut self, base_rotation: &Matrix3<f64>) {
+        let world_gravity = Vector3::new(0.0, 0.0, -self.gravity.norm());
+        self.gravity = base_rotation.transpose() * world_gravity;
+    }
+
+    /// Updates the position of all joints and refreshes the FK cache.
+    ///
+    /// Positions are in degrees for revolute joints (lengths for prismatic ones);
+    /// [`DHArmModel::set_joint_angles`] takes an explicit unit instead.
+    ///
+    /// Only rows whose joint value changed are recomputed; the kinematics are
+    /// marked "dirty" only if some frame pose actually moved.
     /// 
     /// # Panics
     /// Panics if the input slice length does not match the joint count `J`.
@@ -74,22 +214,47 @@ impl<const F: usize, const J: usize, S: IkSolver<J>> DHArmModel<F, J, S> {
         for (joint, &pos) in self.joints.iter_mut().zip(positions.iter()) {
             joint.set_position(pos);
         }
-        self.dirty = true;
+        #[cfg(feature = "profiling")]
+        let start = Instant::now();
+        if self.dh_table.refresh_fk_cache(&mut self.fk_cache, &self.joints) {
+            self.dirty = true;
+        }
+        #[cfg(feature = "profiling")]
+        self.timings.add(Phase::Fk, start.elapsed());
+    }
+
+    /// Sets all joint positions from angles in an explicit unit (see [`JointAngles`]).
+    pub fn set_joint_angles(&mut self, angles: &JointAngles<J>) {
+        let positions = angles.to_user_units(&self.joints);
+        self.set_joint_positions(&positions);
+    }
+
+    /// Current joint positions as [`JointAngles`].
+    pub fn joint_angles(&self) -> JointAngles<J> {
+        JointAngles::from_radians(core::array::from_fn(|i| self.joints[i].position))
     }
 
     /// Update joint velocities
+    ///
+    /// The Jacobian depends only on positions, so this does not invalidate the cached kinematics.
     pub fn set_joint_velocities(&mut self, velocities: &[f64; J]) {
         assert_eq!(velocities.len(), self.joints.len(), "Velocity vector length mismatch");
         for (joint, &vel) in self.joints.iter_mut().zip(velocities.iter()) {
             joint.set_velocity(vel);
         }
-        self.dirty = true;
     }
 
     pub fn joints(&self) -> &[Joint; J] {
         &self.joints
     }
 
+    /// Link parameters handed to the IK solver.
+    pub fn ik_link_parameters(&self) -> &[f64] {
+        &self.ik_link_parameters
+    }
+
+    /// Current joint positions in radians (lengths for prismatic joints), unlike the
+    /// degrees [`DHArmModel::set_joint_positions`] takes; see [`DHArmModel::joint_angles`].
     pub fn joint_positions(&self) -> SVector<f64, J> {
         SVector::from_iterator(self.joints.iter().map(|j| j.position as f64))
     }
@@ -99,30 +264,136 @@ impl<const F: usize, const J: usize, S: IkSolver<J>> DHArmModel<F, J, S> {
     }
 
 
+    pub fn set_jacobian_refresh(&mut self, policy: JacobianRefresh) {
+        self.jacobian_refresh = policy;
+    }
+
+    pub fn jacobian_refresh(&self) -> JacobianRefresh {
+        self.jacobian_refresh
+    }
+
+    /// How far the joints have moved, and for how many updates, since the cached
+    /// Jacobian was computed. Always zero with [`JacobianRefresh::EveryChange`] after `update`.
+    pub fn jacobian_staleness(&self) -> JacobianStaleness {
+        JacobianStaleness {
+            cycles: self.jacobian_age,
+            max_joint_delta: self.max_joint_delta_since_jacobian(),
+        }
+    }
+
+    /// Uses `jacobian` instead of the generic geometric Jacobian; it must describe
+    /// this model's DH table. `None` goes back to the generic computation.
+    pub fn set_analytic_jacobian(&mut self, jacobian: Option<AnalyticJacobian<F, J>>) {
+        self.analytic_jacobian = jacobian;
+        self.invalidate_jacobian();
+    }
+
+    pub fn analytic_jacobian(&self) -> Option<AnalyticJacobian<F, J>> {
+        self.analytic_jacobian
+    }
+
+    /// Damping factor ($\lambda$) of the Jacobian pseudo-inverse.
+    pub fn damping(&self) -> f64 {
+        self.damping
+    }
+
+    /// Changes the pseudo-inverse damping; the next `update` recomputes the inverse with it.
+    pub fn set_damping(&mut self, damping: f64) {
+        self.damping = damping;
+        self.invalidate_jacobian();
+    }
+
+    /// Forces the next `update` to recompute the Jacobian regardless of the refresh policy.
+    pub fn invalidate_jacobian(&mut self) {
+        self.jacobian = None;
+        self.inv_jacobian = None;
+        self.dirty = true;
+    }
+
+    fn max_joint_delta_since_jacobian(&self) -> f64 {
+        self.joints
+            .iter()
+            .zip(self.jacobian_joint_positions.iter())
+            .map(|(joint, &at)| (joint.position - at).abs())
+            .fold(0.0, f64::max)
+    }
+
+    /// True if the refresh policy allows serving this update from the cached Jacobian.
+    fn can_reuse_jacobian(&self) -> bool {
+        match self.jacobian_refresh {
+            JacobianRefresh::EveryChange => false,
+            JacobianRefresh::Reduced { max_cycles, max_joint_delta } => {
+                self.jacobian.is_some()
+                    && self.jacobian_age < max_cycles
+                    && self.max_joint_delta_since_jacobian() <= max_joint_delta
+            }
+        }
+    }
+
     /// Compute / update cached FK, Jacobian, and inverse if dirty
     pub fn update(&mut self) {
+        if self.dirty && self.can_reuse_jacobian() {
+            self.jacobian_age += 1;
+            self.dirty = false;
+        }
         if self.dirty {
-            let j = self.dh_table.compute_jacobian(&self.joints);
-            let inv_j = self.dh_table.damped_moore_penrose_pseudo_inverse(
-                &self.joints,
-                Some(&j),
-                Some(self.damping),
-            );
+            #[cfg(feature = "profiling")]
+            let start = Instant::now();
+            let j = match self.analytic_jacobian {
+                Some(jacobian) => jacobian(self.fk_cache.row_transforms()),
+                None => self.dh_table.jacobian_from_poses(self.fk_cache.poses(), &self.joints),
+            };
+            #[cfg(feature = "profiling")]
+            let start = {
+                self.timings.add(Phase::Jacobian, start.elapsed());
+                Instant::now()
+            };
+            let dynamic = match self.pseudo_inverse {
+                PseudoInverse::Kinematic => None,
+                PseudoInverse::DynamicallyConsistent => self.dynamically_consistent_inverse_of(&j),
+            };
+            let inv_j = dynamic.unwrap_or_else(|| {
+                self.dh_table.damped_moore_penrose_pseudo_inverse(&self.joints, Some(&j), Some(self.damping))
+            });
+            #[cfg(feature = "profiling")]
+            self.timings.add(Phase::PseudoInverse, start.elapsed());
 
             self.jacobian = Some(j);
             self.inv_jacobian = Some(inv_j);
+            for (at, joint) in self.jacobian_joint_positions.iter_mut().zip(self.joints.iter()) {
+                *at = joint.position;
+            }
+            self.jacobian_age = 0;
             self.dirty = false;
         }
     }
 
-    /// Get the current end-effector pose (computes if dirty)
+    /// Returns and clears the kinematics timings gathered since the last call.
+    #[cfg(feature = "profiling")]
+    pub fn take_timings(&mut self) -> PhaseTimings {
+        core::mem::take(&mut self.timings)
+    }
+
+    /// Get the pose of a frame from the FK cache
     pub fn frame_pose(&self, frame_index: usize) -> Pose {
-        // Pass self.joints to DHTable
-        self.dh_table.get_frame_pose(frame_index, &self.joints)
+        self.fk_cache.frame_pose(frame_index)
     }
 
     pub fn frame_poses(&self) -> [Pose; F] {
-        self.dh_table.all_poses(&self.joints)
+        self.fk_cache.poses().clone()
+    }
+
+    /// Pose of the last frame (the tool point the Jacobian is taken at).
+    ///
+    /// Note that `frame_pose(F - 1)` stops one row short of it.
+    pub fn end_effector_pose(&self) -> Pose {
+        self.fk_cache.poses()[F - 1].clone()
+    }
+
+    /// Capture all frame poses at the current joint positions as a golden snapshot.
+    #[cfg(feature = "std")]
+    pub fn pose_snapshot(&self) -> PoseSnapshot {
+        PoseSnapshot::capture(&self.dh_table, &self.joints)
     }
 
     /// Get the current Jacobian (computes if dirty)
@@ -131,14 +402,75 @@ impl<const F: usize, const J: usize, S: IkSolver<J>> DHArmModel<F, J, S> {
         self.jacobian.as_ref().unwrap()
     }
 
-    /// Get the current inverse Jacobian (computes if dirty)
+    /// Ratio of the largest to the smallest singular value of the current Jacobian
+    /// (computes if dirty). Grows without bound near a singularity; infinite at one, and
+    /// for arms with fewer than 6 joints, which cannot span all task directions.
+    pub fn jacobian_condition_number(&mut self) -> f64 {
+        if J < 6 {
+            return f64::INFINITY;
+        }
+        // Singular values of J itself; the eigenvalues of J·Jᵀ would square the conditioning.
+        // The SVD of a 6 x J matrix needs its dimensions at runtime for a generic J.
+        let j = self.jacobian();
+        let singular_values = DMatrix::from_column_slice(6, J, j.as_slice()).singular_values();
+        let max = singular_values.max();
+        let min = singular_values.min();
+        if min <= 0.0 { f64::INFINITY } else { max / min }
+    }
+
+    /// Get the current inverse Jacobian (computes if dirty); the damped Moore-Penrose
+    /// inverse unless [`DHArmModel::set_pseudo_inverse`] chose another.
     pub fn inv_jacobian(&mut self) -> &SMatrix<f64, J, 6> {
         self.update();
         self.inv_jacobian.as_ref().unwrap()
     }
 
+    /// Cheap check, before solving, that the IK solver can reach `target_pose` at all
+    /// (see [`IkSolver::check_reachable`]).
+    pub fn check_reachable(&self, target_pose: &Pose) -> Result<(), IkError> {
+        let p = &target_pose.position;
+        self.ik_solver.check_reachable(p.x, p.y, p.z, &target_pose.rotation, &self.ik_link_parameters)
+    }
+
+    /// Runs FK on an IK solution `q` (radians / linear units, as returned by the
+    /// solver) and reports how far it lands from `target_pose`. The arm's own joint
+    /// state is left untouched.
+    ///
+    /// Fails with [`IkError::RoundTripMismatch`] if either error exceeds `tol`,
+    /// which catches solvers returning the wrong branch.
+    pub fn verify_ik_solution(&self, q: &[f64; J], target_pose: &Pose, tol: &IkTolerance) -> Result<IkResidual, IkError> {
+        let mut joints = self.joints.clone();
+        for (joint, &q_i) in joints.iter_mut().zip(q) {
+            joint.position = q_i;
+        }
+        let reached = &self.dh_table.all_poses(&joints)[F - 1];
+
+        let residual = IkResidual {
+            position_error: (reached.position - target_pose.position).norm(),
+            orientation_error: reached.rotation_angle_to(target_pose),
+        };
+        // Written so that NaN errors fail too
+        if residual.position_error <= tol.position && residual.orientation_error <= tol.orientation {
+            Ok(residual)
+        } else {
+            Err(IkError::RoundTripMismatch {
+                position_error: residual.position_error,
+                orientation_error: residual.orientation_error,
+            })
+        }
+    }
+
+    /// [`DHArmModel::solve_ik_from_pose`] with a reachability pre-check and an FK
+    /// round-trip check of the result.
+    pub fn solve_ik_verified(&self, target_pose: &Pose, tol: &IkTolerance) -> Result<[f64; J], IkError> {
+        self.check_reachable(target_pose)?;
+        let q = self.solve_ik_from_pose(target_pose)?;
+        self.verify_ik_solution(&q, target_pose, tol)?;
+        Ok(q)
+    }
+
     /// Solves IK using the End-Effector target pose (position + rotation matrix)
-    pub fn solve_ik_from_pose(&self, target_pose: &Pose) -> Result<[f64; J], String> {
+    pub fn solve_ik_from_pose(&self, target_pose: &Pose) -> Result<[f64; J], IkError> {
         let x = target_pose.position.x;
         let y = target_pose.position.y;
         let z = target_pose.position.z;
@@ -148,12 +480,18 @@ impl<const F: usize, const J: usize, S: IkSolver<J>> DHArmModel<F, J, S> {
         self.ik_solver.solve_ik(x, y, z, r, link_lengths)
     }
 
+    /// [`DHArmModel::solve_ik_from_pose`] warm-started from `seed` (radians; see [`IkSolver::solve_ik_seeded`]).
+    pub fn solve_ik_seeded(&self, target_pose: &Pose, seed: &[f64; J]) -> Result<[f64; J], IkError> {
+        let p = &target_pose.position;
+        self.ik_solver.solve_ik_seeded(p.x, p.y, p.z, &target_pose.rotation, &self.ik_link_parameters, seed)
+    }
+
     /// Solves IK using the End-Effector target position (x,y,z) and Euler angles (yaw, pitch, roll)
     pub fn solve_ik_from_components(
         &self, 
         x: f64, y: f64, z: f64, 
         yaw: f64, pitch: f64, roll: f64
-    ) -> Result<[f64; J], String> {
+    ) -> Result<[f64; J], IkError> {
         let r = Pose::orientation_mat(yaw, pitch, roll); 
         let link_lengths = &self.ik_link_parameters;
 
@@ -0,0 +1,117 @@
+use nalgebra::{Matrix3, SMatrix, Vector3};
+
+use crate::dh::FrameType;
+use crate::dh_arm_model::DHArmModel;
+use crate::inverse_kinematics_solvers::IkSolver;
+use crate::joint::JointType;
+
+/// Mass properties of the link carried by one frame of the DH table.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinkInertia {
+    /// kg
+    pub mass: f64,
+    /// Centre of mass in the frame (DH length unit).
+    pub center_of_mass: Vector3<f64>,
+    /// Inertia tensor about the centre of mass, along the frame's axes
+    /// (kg · length unit²).
+    pub inertia: Matrix3<f64>,
+}
+
+impl LinkInertia {
+    pub fn new(mass: f64, center_of_mass: Vector3<f64>, inertia: Matrix3<f64>) -> Self {
+        Self { mass, center_of_mass, inertia }
+    }
+
+    /// All the mass at `center_of_mass`, e.g. a motor or a payload.
+    pub fn point_mass(mass: f64, center_of_mass: Vector3<f64>) -> Self {
+        Self::new(mass, center_of_mass, Matrix3::zeros())
+    }
+}
+
+/// How [`DHArmModel::inv_jacobian`] maps task-space quantities to joint space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PseudoInverse {
+    /// Damped Moore-Penrose inverse: least joint motion for a task velocity (default).
+    #[default]
+    Kinematic,
+    /// Dynamically consistent inverse M⁻¹JᵀΛ: the inverse weighted by the arm's mass
+    /// matrix, under which task-space forces and null-space torques do not disturb each
+    /// other. Needs the links' mass properties (see [`DHArmModel::set_link_inertia`]);
+    /// where they leave a joint moving no mass, the kinematic inverse is used instead.
+    DynamicallyConsistent,
+}
+
+impl<const F: usize, const J: usize, S: IkSolver<J>> DHArmModel<F, J, S> {
+    /// Joint-space mass matrix M(q) at the current joint positions, from the mass
+    /// properties set with [`DHArmModel::set_link_inertia`] (kg · length unit² for
+    /// revolute joints, kg for prismatic ones). Kinetic energy is ½ q̇ᵀ M q̇ with q̇ in
+    /// rad/s / length unit per s.
+    pub fn mass_matrix(&self) -> SMatrix<f64, J, J> {
+        let mut m = SMatrix::<f64, J, J>::zeros();
+        // Pose after each row; a joint turns about the Z axis of its row's pose, as in
+        // `DHTable::jacobian_from_poses`
+        let row_poses = self.frame_poses();
+        for (frame, link) in self.link_inertias().iter().enumerate() {
+            let Some(link) = link else { continue };
+            let pose = self.frame_pose(frame);
+            let com = pose.position + pose.rotation * link.center_of_mass;
+
+            // Jacobian of the link's centre of mass; only the joints of earlier rows move it
+            let mut j_v = SMatrix::<f64, 3, J>::zeros();
+            let mut j_w = SMatrix::<f64, 3, J>::zeros();
+            for (row_index, row) in self.dh_table().rows().iter().enumerate().take(frame) {
+                let Some(FrameType::Joint(joint)) = row.frame_type() else { continue };
+                let axis_pose = &row_poses[row_index];
+                let z = axis_pose.z_axis();
+                match self.joints()[joint].joint_type {
+                    JointType::Revolute => {
+                        j_v.set_column(joint, &z.cross(&(com - axis_pose.position)));
+                        j_w.set_column(joint, &z);
+                    }
+                    JointType::Prismatic => j_v.set_column(joint, &z),
+                }
+            }
+
+            let inertia_base = pose.rotation * link.inertia * pose.rotation.transpose();
+            m += j_v.transpose() * j_v * link.mass + j_w.transpose() * inertia_base * j_w;
+        }
+        m
+    }
+
+    /// Operational-space inertia Λ = (J M⁻¹ Jᵀ)⁻¹ of the end-effector at the current
+    /// joint positions: the apparent mass and inertia a force or torque applied at the
+    /// tool meets. Damped with the arm's damping factor like the kinematic inverse, so
+    /// it stays finite at singularities.
+    ///
+    /// `None` if the mass matrix is not positive definite, i.e. some joint moves no mass.
+    pub fn operational_space_inertia(&mut self) -> Option<SMatrix<f64, 6, 6>> {
+        let j = *self.jacobian();
+        self.operational_space_terms(&j).map(|(_, lambda)| lambda)
+    }
+
+    /// Dynamically consistent inverse J̄ = M⁻¹ Jᵀ Λ of the current Jacobian, whatever
+    /// [`PseudoInverse`] the arm uses; `None` as for [`DHArmModel::operational_space_inertia`].
+    ///
+    /// Maps task velocities to joint velocities like J⁺, but a task-space force F
+    /// enters as τ = JᵀF and null-space torques as (I - JᵀJ̄ᵀ)τ₀ without accelerating
+    /// the end-effector.
+    pub fn dynamically_consistent_inverse(&mut self) -> Option<SMatrix<f64, J, 6>> {
+        let j = *self.jacobian();
+        self.dynamically_consistent_inverse_of(&j)
+    }
+
+    pub(crate) fn dynamically_consistent_inverse_of(&self, j: &SMatrix<f64, 6, J>) -> Option<SMatrix<f64, J, 6>> {
+        self.operational_space_terms(j).map(|(m_inv, lambda)| m_inv * j.transpose() * lambda)
+    }
+
+    /// M⁻¹ and Λ for Jacobian `j`.
+    fn operational_space_terms(&self, j: &SMatrix<f64, 6, J>) -> Option<(SMatrix<f64, J, J>, SMatrix<f64, 6, 6>)> {
+        let m_inv = self.mass_matrix().cholesky()?.inverse();
+        let mut apparent = j * m_inv * j.transpose();
+        let l2 = self.damping() * self.damping();
+        for i in 0..6 {
+            apparent[(i, i)] += l2;
+        }
+        Some((m_inv, apparent.cholesky()?.inverse()))
+    }
+}
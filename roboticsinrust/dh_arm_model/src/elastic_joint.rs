@@ -0,0 +1,88 @@
+/// Compliance between a servo and the link it drives (gear reduction, flexible
+/// 3D-printed links), seen from the link as a damped oscillator.
+///
+/// The link swings on the drive with `natural_frequency` when the motor is held;
+/// tune both values against ring-down measured on the real arm.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointElasticity {
+    /// Natural frequency of the link on the drive's compliance (Hz).
+    pub natural_frequency: f64,
+    /// Damping ratio of that oscillation (1 = critically damped, no overshoot).
+    pub damping_ratio: f64,
+}
+
+impl JointElasticity {
+    pub fn new(natural_frequency: f64, damping_ratio: f64) -> Self {
+        Self { natural_frequency, damping_ratio }
+    }
+}
+
+/// Series-elastic joints: each motor-side position (what the servo encoder reads
+/// and the controller commands) is connected to its link-side position (what the
+/// arm actually does) by a spring and damper.
+///
+/// Positions are in controller units (deg / linear units). The motor side is taken
+/// to follow its command exactly; only the link side is simulated, with
+///
+/// `link_acc = ω² (motor_pos - link_pos) + 2ζω (motor_vel - link_vel)`.
+#[derive(Debug, Clone)]
+pub struct SeriesElasticJoints<const J: usize> {
+    pub elasticity: [JointElasticity; J],
+    link_pos: [f64; J],
+    link_vel: [f64; J],
+}
+
+impl<const J: usize> SeriesElasticJoints<J> {
+    /// Starts at rest with the links at `positions`.
+    pub fn new(elasticity: [JointElasticity; J], positions: &[f64; J]) -> Self {
+        Self { elasticity, link_pos: *positions, link_vel: [0.0; J] }
+    }
+
+    /// Puts the links back at rest at `positions`.
+    pub fn reset(&mut self, positions: &[f64; J]) {
+        self.link_pos = *positions;
+        self.link_vel = [0.0; J];
+    }
+
+    pub fn link_positions(&self) -> &[f64; J] {
+        &self.link_pos
+    }
+
+    pub fn link_velocities(&self) -> &[f64; J] {
+        &self.link_vel
+    }
+
+    /// Spring deflection of each joint, motor side minus link side.
+    pub fn deflection(&self, motor_pos: &[f64; J]) -> [f64; J] {
+        core::array::from_fn(|i| motor_pos[i] - self.link_pos[i])
+    }
+
+    /// Advances the links by `dt`, during which the motors moved at `motor_vel` and
+    /// ended at `motor_pos`.
+    ///
+    /// Integrates in substeps of at most 1/20 of the fastest joint's period, so
+    /// stiff joints stay stable at control-loop step sizes.
+    pub fn step(&mut self, motor_pos: &[f64; J], motor_vel: &[f64; J], dt: f64) {
+        if dt <= 0.0 {
+            return;
+        }
+        let max_frequency = self.elasticity.iter().map(|e| e.natural_frequency).fold(0.0, f64::max);
+        let substeps = ((dt * max_frequency * 20.0) as usize).max(1);
+        let h = dt / substeps as f64;
+
+        for k in 1..=substeps {
+            let remaining = dt - k as f64 * h;
+            for i in 0..J {
+                let omega = core::f64::consts::TAU * self.elasticity[i].natural_frequency;
+                let zeta = self.elasticity[i].damping_ratio;
+                let motor = motor_pos[i] - motor_vel[i] * remaining;
+
+                let acc = omega * omega * (motor - self.link_pos[i])
+                    + 2.0 * zeta * omega * (motor_vel[i] - self.link_vel[i]);
+                // Semi-implicit Euler: velocity first, so the oscillation does not gain energy
+                self.link_vel[i] += acc * h;
+                self.link_pos[i] += self.link_vel[i] * h;
+            }
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use std::f64::consts::TAU;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// What a frequency-response test excites.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SweepAxis {
+    /// Joint velocity command of joint `i` (deg/s / linear units per s).
+    Joint(usize),
+    /// Task velocity command component `i`, in the controller's convention
+    /// (0-2 linear in the base frame, 3-5 angular in the end-effector frame, deg/s).
+    Task(usize),
+}
+
+/// Shape of the excitation signal.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Excitation {
+    /// One sine per frequency (Hz), held for `settle_cycles` before `measure_cycles`
+    /// are analysed. Slow but robust to noise and non-linearity.
+    SteppedSine { frequencies: Vec<f64>, settle_cycles: u32, measure_cycles: u32 },
+    /// Logarithmic chirp from `start_hz` to `end_hz` over `duration` seconds.
+    Chirp { start_hz: f64, end_hz: f64, duration: f64 },
+}
+
+impl Excitation {
+    /// `count` frequencies spaced logarithmically from `start_hz` to `end_hz`.
+    pub fn log_frequencies(start_hz: f64, end_hz: f64, count: usize) -> Vec<f64> {
+        if count < 2 {
+            return vec![start_hz];
+        }
+        let ratio = (end_hz / start_hz).ln();
+        (0..count).map(|k| start_hz * (ratio * k as f64 / (count - 1) as f64).exp()).collect()
+    }
+}
+
+/// Gain and phase of the response at one frequency.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BodePoint {
+    /// Hz.
+    pub frequency: f64,
+    /// Output amplitude over input amplitude.
+    pub gain: f64,
+    /// Output phase relative to the input (deg); negative is lag.
+    pub phase: f64,
+}
+
+impl BodePoint {
+    pub fn gain_db(&self) -> f64 {
+        20.0 * self.gain.log10()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    time: f64,
+    input: f64,
+    output: f64,
+}
+
+/// An empirical frequency-response (Bode) measurement of one axis of the
+/// servo + controller stack.
+///
+/// Drive the loop with [`FrequencyResponseTest::excitation`], added to the command of
+/// the chosen [`SweepAxis`], and feed back what was applied and what was measured
+/// through [`FrequencyResponseTest::record`] until [`FrequencyResponseTest::is_finished`].
+/// The same test runs in the simulator (`ArmSim::start_frequency_response`) or in a
+/// hardware loop.
+#[derive(Debug, Clone)]
+pub struct FrequencyResponseTest {
+    pub axis: SweepAxis,
+    /// Peak excitation, in the units of the axis.
+    pub amplitude: f64,
+    pub excitation: Excitation,
+    samples: Vec<Sample>,
+}
+
+impl FrequencyResponseTest {
+    pub fn new(axis: SweepAxis, amplitude: f64, excitation: Excitation) -> Self {
+        Self { axis, amplitude, excitation, samples: Vec::new() }
+    }
+
+    /// Total length of the excitation (s).
+    pub fn duration(&self) -> f64 {
+        match &self.excitation {
+            Excitation::SteppedSine { frequencies, settle_cycles, measure_cycles } => frequencies.iter()
+                .map(|f| (settle_cycles + measure_cycles) as f64 / f)
+                .sum(),
+            Excitation::Chirp { duration, .. } => *duration,
+        }
+    }
+
+    pub fn is_finished(&self, time: f64) -> bool {
+        time >= self.duration()
+    }
+
+    /// Excitation to add to the axis command at `time` seconds into the test (0 once finished).
+    pub fn excitation(&self, time: f64) -> f64 {
+        match &self.excitation {
+            Excitation::SteppedSine { frequencies, settle_cycles, measure_cycles } => {
+                let mut start = 0.0;
+                for &f in frequencies {
+                    let length = (settle_cycles + measure_cycles) as f64 / f;
+                    if time < start + length {
+                        return self.amplitude * (TAU * f * (time - start)).sin();
+                    }
+                    start += length;
+                }
+                0.0
+            }
+            Excitation::Chirp { start_hz, end_hz, duration } => {
+                if time >= *duration {
+                    return 0.0;
+                }
+                let k = (end_hz / start_hz).ln();
+                let phase = TAU * start_hz * duration / k * ((k * time / duration).exp() - 1.0);
+                self.amplitude * phase.sin()
+            }
+        }
+    }
+
+    /// Records the excitation actually applied at `time` and the measured response of the axis.
+    pub fn record(&mut self, time: f64, input: f64, output: f64) {
+        self.samples.push(Sample { time, input, output });
+    }
+
+    pub fn num_samples(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Estimates gain and phase at each test frequency from the recorded samples,
+    /// in the order the frequencies were tested.
+    ///
+    /// Stepped sines are analysed over their whole measurement cycles. A chirp is
+    /// analysed at `chirp_points` log-spaced frequencies, each over a Hann window of
+    /// a few cycles around the moment the chirp passes it. Frequencies without
+    /// enough samples (e.g. above the loop's Nyquist rate) are skipped.
+    pub fn bode(&self, chirp_points: usize) -> Vec<BodePoint> {
+        let mut points = self.raw_bode(chirp_points);
+        // Unwrap the phase so lag keeps accumulating past -180°
+        for i in 1..points.len() {
+            let prev = points[i - 1].phase;
+            points[i].phase -= 360.0 * ((points[i].phase - prev) / 360.0).round();
+        }
+        points
+    }
+
+    fn raw_bode(&self, chirp_points: usize) -> Vec<BodePoint> {
+        match &self.excitation {
+            Excitation::SteppedSine { frequencies, settle_cycles, measure_cycles } => {
+                let mut start = 0.0;
+                let mut points = Vec::new();
+                for &f in frequencies {
+                    let settle_end = start + *settle_cycles as f64 / f;
+                    let end = settle_end + *measure_cycles as f64 / f;
+                    points.extend(self.response_at(f, settle_end, end, |_| 1.0));
+                    start = end;
+                }
+                points
+            }
+            Excitation::Chirp { start_hz, end_hz, duration } => {
+                let k = (end_hz / start_hz).ln();
+                Excitation::log_frequencies(*start_hz, *end_hz, chirp_points)
+                    .into_iter()
+                    .filter_map(|f| {
+                        // Instant the chirp sweeps through f, and a window of ~4 of its cycles
+                        let centre = duration * (f / start_hz).ln() / k;
+                        let half = 2.0 / f;
+                        self.response_at(f, centre - half, centre + half, |t| {
+                            0.5 * (1.0 + (TAU * (t - centre) / (2.0 * half)).cos())
+                        })
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Ratio of the windowed single-frequency DFTs of output and input over [from, to).
+    fn response_at(&self, f: f64, from: f64, to: f64, window: impl Fn(f64) -> f64) -> Option<BodePoint> {
+        let omega = TAU * f;
+        let (mut u_re, mut u_im, mut y_re, mut y_im) = (0.0, 0.0, 0.0, 0.0);
+        let mut count = 0;
+
+        // Rectangle rule with each sample's own step, as the loop rate may vary
+        for pair in self.samples.windows(2) {
+            let (s, dt) = (pair[1], pair[1].time - pair[0].time);
+            if s.time < from || s.time >= to || dt <= 0.0 {
+                continue;
+            }
+            let w = window(s.time) * dt;
+            let (sin, cos) = (omega * s.time).sin_cos();
+            u_re += w * s.input * cos;
+            u_im -= w * s.input * sin;
+            y_re += w * s.output * cos;
+            y_im -= w * s.output * sin;
+            count += 1;
+        }
+
+        // Need a few samples per cycle for the estimate to mean anything
+        let cycles = (to - from) * f;
+        let u_mag2 = u_re * u_re + u_im * u_im;
+        if (count as f64) < 4.0 * cycles || u_mag2 <= 0.0 {
+            return None;
+        }
+
+        // H = Y / U
+        let h_re = (y_re * u_re + y_im * u_im) / u_mag2;
+        let h_im = (y_im * u_re - y_re * u_im) / u_mag2;
+        Some(BodePoint {
+            frequency: f,
+            gain: (h_re * h_re + h_im * h_im).sqrt(),
+            phase: h_im.atan2(h_re).to_degrees(),
+        })
+    }
+}
+
+/// Writes `points` as CSV (`frequency_hz,gain,gain_db,phase_deg`).
+pub fn write_bode_csv<P: AsRef<Path>>(points: &[BodePoint], path: P) -> Result<(), String> {
+    let mut csv = String::from("frequency_hz,gain,gain_db,phase_deg\n");
+    for p in points {
+        let _ = writeln!(csv, "{},{},{},{}", p.frequency, p.gain, p.gain_db(), p.phase);
+    }
+    fs::write(path.as_ref(), csv)
+        .map_err(|e| format!("Failed to write {}: {}", path.as_ref().display(), e))
+}
+
+/// Renders `points` as an SVG Bode plot: gain (dB) above phase (deg), both against
+/// log frequency.
+pub fn render_bode_svg(points: &[BodePoint]) -> String {
+    const WIDTH: f64 = 640.0;
+    const PANEL: f64 = 240.0;
+    const MARGIN: f64 = 50.0;
+
+    let mut svg = String::new();
+    let height = 2.0 * PANEL + 3.0 * MARGIN;
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n\
+         <rect width=\"{w}\" height=\"{h}\" fill=\"white\"/>",
+        w = WIDTH,
+        h = height,
+    );
+    if points.is_empty() {
+        svg.push_str("</svg>\n");
+        return svg;
+    }
+
+    let (f_min, f_max) = min_max(points.iter().map(|p| p.frequency.log10()));
+    let f_span = (f_max - f_min).max(1e-9);
+    let to_x = |f: f64| MARGIN + (f.log10() - f_min) / f_span * (WIDTH - 2.0 * MARGIN);
+
+    let panels: [(&str, f64, Vec<f64>); 2] = [
+        ("gain (dB)", MARGIN, points.iter().map(|p| p.gain_db()).collect()),
+        ("phase (deg)", 2.0 * MARGIN + PANEL, points.iter().map(|p| p.phase).collect()),
+    ];
+    for (label, top, values) in panels {
+        let (v_min, v_max) = min_max(values.iter().copied());
+        let v_span = (v_max - v_min).max(1e-9);
+        let to_y = |v: f64| top + PANEL - (v - v_min) / v_span * PANEL;
+
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{x}\" y=\"{y}\" width=\"{w}\" height=\"{h}\" fill=\"none\" stroke=\"#888\"/>\n\
+             <text x=\"{x}\" y=\"{ty}\" font-size=\"12\">{label}: {min:.1} to {max:.1}</text>",
+            x = MARGIN,
+            y = top,
+            w = WIDTH - 2.0 * MARGIN,
+            h = PANEL,
+            ty = top - 6.0,
+            label = label,
+            min = v_min,
+            max = v_max,
+        );
+        let path: Vec<String> = points.iter().zip(&values)
+            .map(|(p, &v)| format!("{:.1},{:.1}", to_x(p.frequency), to_y(v)))
+            .collect();
+        let _ = writeln!(
+            svg,
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"#4a90e2\" stroke-width=\"2\"/>",
+            path.join(" ")
+        );
+    }
+
+    let (first, last) = (points[0].frequency, points[points.len() - 1].frequency);
+    let _ = writeln!(
+        svg,
+        "<text x=\"{x}\" y=\"{y}\" font-size=\"12\">{first:.2} Hz to {last:.2} Hz (log)</text>\n</svg>",
+        x = MARGIN,
+        y = height - MARGIN / 2.0,
+        first = first,
+        last = last,
+    );
+    svg
+}
+
+fn min_max(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)))
+}
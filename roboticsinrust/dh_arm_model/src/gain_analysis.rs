@@ -0,0 +1,115 @@
+use crate::dh_arm_model::DHArmModel;
+use crate::inverse_kinematics_solvers::IkSolver;
+use crate::task_space_pid_controller::TaskSpacePidController;
+
+use nalgebra::linalg::Schur;
+use nalgebra::{Complex, Matrix6, SMatrix, SVector};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// Size of the closed-loop state: error, integral and previous error per task axis.
+const STATES: usize = 18;
+
+/// Spectral radius within this of 1 counts as marginal rather than (un)stable; repeated
+/// eigenvalues at 1 (axes with zero gains) only come out of the solver to about this.
+const MARGINAL_TOLERANCE: f64 = 1e-6;
+
+/// Convergence threshold and iteration cap of the eigenvalue solver.
+const SCHUR_EPSILON: f64 = 1e-12;
+const SCHUR_MAX_ITERATIONS: usize = 100_000;
+
+/// Linearised stability of a [`TaskSpacePidController`]'s gains at one arm configuration.
+///
+/// The loop is modelled as the controller holding a fixed reference while the joints
+/// follow their velocity commands exactly for one step `dt`. The 6D pose error then
+/// evolves as
+///
+/// `e[k+1] = e[k] - dt · M · u[k]`, with `u[k]` the PID output and `M = J · J⁺`,
+///
+/// where `J⁺` is the damped pseudo-inverse the controller uses (`M = I` away from
+/// singularities). Ignores the speed limits, which only slow convergence down, and
+/// the servos' own dynamics, which a frequency-response measurement covers.
+#[derive(Debug, Clone)]
+pub struct GainAnalysis {
+    /// Eigenvalues of the discrete closed-loop error dynamics.
+    pub eigenvalues: SVector<Complex<f64>, STATES>,
+    /// Largest eigenvalue magnitude: errors shrink by about this factor per step.
+    pub spectral_radius: f64,
+    /// Control step the analysis was done for (s).
+    pub dt: f64,
+    /// Smallest eigenvalue of `M`: how much of a commanded task velocity the arm
+    /// still achieves in its weakest direction (1 far from singularities).
+    pub min_task_authority: f64,
+}
+
+impl GainAnalysis {
+    /// Every error decays (spectral radius below 1).
+    pub fn is_stable(&self) -> bool {
+        self.spectral_radius < 1.0 - MARGINAL_TOLERANCE
+    }
+
+    /// Some error grows without bound (spectral radius above 1): the gains must not be run.
+    ///
+    /// Neither stable nor unstable is marginal, e.g. an axis with all-zero gains,
+    /// whose error is simply never corrected.
+    pub fn is_unstable(&self) -> bool {
+        self.spectral_radius > 1.0 + MARGINAL_TOLERANCE
+    }
+
+    /// Distance of the slowest mode from the unit circle (negative when unstable).
+    pub fn stability_margin(&self) -> f64 {
+        1.0 - self.spectral_radius
+    }
+
+    /// Time constant of the slowest decaying mode (s), or `None` unless stable.
+    pub fn time_constant(&self) -> Option<f64> {
+        if !self.is_stable() {
+            return None;
+        }
+        Some(-self.dt / self.spectral_radius.ln())
+    }
+}
+
+impl TaskSpacePidController {
+    /// Linearises the closed loop about the arm's current joint positions and
+    /// reports its stability with these gains at control step `dt` (see [`GainAnalysis`]).
+    ///
+    /// Cheap enough to run on every gain change, so obviously unstable gain sets
+    /// (too much `kp` or `kd` for the loop rate) are caught before they reach the arm.
+    /// Returns `None` if the eigenvalues cannot be found (non-finite gains or Jacobian).
+    pub fn analyze_gains<const F: usize, const J: usize, S: IkSolver<J>>(
+        &self,
+        arm: &mut DHArmModel<F, J, S>,
+        dt: f64,
+    ) -> Option<GainAnalysis> {
+        let authority: Matrix6<f64> = *arm.jacobian() * *arm.inv_jacobian();
+        let kp = Matrix6::from_diagonal(&self.kp);
+        let ki = Matrix6::from_diagonal(&self.ki);
+        let kd = Matrix6::from_diagonal(&self.kd);
+
+        // State [e[k], I[k-1], e[k-1]] with I[k] = I[k-1] + dt·e[k] and
+        // u[k] = (kp + dt·ki + kd/dt) e[k] + ki I[k-1] - (kd/dt) e[k-1]
+        let identity = Matrix6::<f64>::identity();
+        let mut a = SMatrix::<f64, STATES, STATES>::zeros();
+        a.fixed_slice_mut::<6, 6>(0, 0)
+            .copy_from(&(identity - authority * (kp * dt + ki * (dt * dt) + kd)));
+        a.fixed_slice_mut::<6, 6>(0, 6).copy_from(&(-authority * ki * dt));
+        a.fixed_slice_mut::<6, 6>(0, 12).copy_from(&(authority * kd));
+        a.fixed_slice_mut::<6, 6>(12, 0).copy_from(&identity);
+        // The integral of an axis without integral gain never acts; leaving its state
+        // out keeps its (harmless) growth from reading as a marginal mode
+        for i in 0..6 {
+            if self.ki[i] != 0.0 {
+                a[(6 + i, i)] = dt;
+                a[(6 + i, 6 + i)] = 1.0;
+            }
+        }
+
+        let eigenvalues = Schur::try_new(a, SCHUR_EPSILON, SCHUR_MAX_ITERATIONS)?.complex_eigenvalues();
+        let spectral_radius = eigenvalues.iter().map(|l| l.re.hypot(l.im)).fold(0.0, f64::max);
+        // M is symmetric for the damped right pseudo-inverse
+        let min_task_authority = authority.symmetric_eigenvalues().min().max(0.0);
+
+        Some(GainAnalysis { eigenvalues, spectral_radius, dt, min_task_authority })
+    }
+}
@@ -0,0 +1,187 @@
+use crate::dh::Pose;
+use crate::dh_arm_model::DHArmModel;
+use crate::inverse_kinematics_solvers::IkSolver;
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec::Vec};
+use nalgebra::{Matrix3, Rotation3, Vector3};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// Relative rotations smaller than this (rad) have no well-defined axis and do not
+/// constrain the camera's orientation.
+const MIN_ROTATION: f64 = 1e-3;
+/// The rotation axes of the sample motions must spread over at least two directions:
+/// the second singular value of their correlation must exceed this fraction of the first.
+const MIN_AXIS_SPREAD: f64 = 1e-3;
+
+/// Where a camera sits relative to the arm.
+#[derive(Debug, Clone)]
+pub struct CameraMount {
+    /// DH frame the camera is attached to and moves with (eye-in-hand), or `None`
+    /// for a camera fixed relative to the arm's base (eye-to-hand).
+    pub frame: Option<usize>,
+    /// Pose of the camera frame in that DH frame, or in the base frame.
+    pub camera_in_frame: Pose,
+}
+
+/// How the camera and the calibration target are arranged while calibrating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandEyeSetup {
+    /// Camera on DH frame `frame`, target fixed in the workspace.
+    EyeInHand { frame: usize },
+    /// Camera fixed in the workspace, target held by DH frame `frame` (usually the end-effector).
+    EyeToHand { frame: usize },
+}
+
+/// One calibration view: where the arm was, and the pose of the calibration target
+/// (checkerboard, marker board, ...) as the camera detected it.
+#[derive(Debug, Clone)]
+pub struct HandEyeSample<const J: usize> {
+    /// Joint positions (deg / linear units).
+    pub joint_positions: [f64; J],
+    pub target_in_camera: Pose,
+}
+
+/// Solution of `A X = X B` and how well it fits the motions it was solved from.
+#[derive(Debug, Clone)]
+pub struct HandEyeSolution {
+    pub x: Pose,
+    /// RMS angle between `A X` and `X B` over all motions (rad).
+    pub rotation_residual: f64,
+    /// RMS distance between `A X` and `X B` over all motions (linear units).
+    pub translation_residual: f64,
+}
+
+/// Solves the hand-eye equation `A_k X = X B_k` for the rigid transform `X`, given
+/// pairs of motions `(A_k, B_k)` observed from the two sides of `X`.
+///
+/// The rotation is fitted first: the rotation axes (scaled by angle) of each `A_k`
+/// are those of `B_k` rotated by `X`, which is solved in closed form by SVD
+/// (Park & Martin). The translation then follows from the stacked linear equations
+/// `(R_A - I) t_X = R_X t_B - t_A` by least squares.
+///
+/// Needs at least two motions whose rotation axes are not parallel; noisy detections
+/// are averaged out by using more, with large rotations between them.
+pub fn solve_ax_xb(motions: &[(Pose, Pose)]) -> Result<HandEyeSolution, String> {
+    let axis = |pose: &Pose| Rotation3::from_matrix_unchecked(pose.rotation).scaled_axis();
+
+    let mut correlation = Matrix3::zeros();
+    for (a, b) in motions {
+        let (alpha, beta) = (axis(a), axis(b));
+        if alpha.norm() >= MIN_ROTATION && beta.norm() >= MIN_ROTATION {
+            correlation += beta * alpha.transpose();
+        }
+    }
+    let svd = correlation.svd(true, true);
+    let (Some(u), Some(v_t)) = (svd.u, svd.v_t) else {
+        return Err("Hand-eye rotation fit failed".into());
+    };
+    let mut spread = [svd.singular_values[0], svd.singular_values[1], svd.singular_values[2]];
+    spread.sort_by(|a, b| b.total_cmp(a));
+    if spread[0] <= 0.0 || spread[1] < MIN_AXIS_SPREAD * spread[0] {
+        return Err(format!(
+            "Hand-eye calibration needs rotations about at least two different axes ({} motions given)",
+            motions.len()
+        ));
+    }
+    // Closest proper rotation: flip the weakest direction if the fit came out a reflection
+    let v = v_t.transpose();
+    let weakest = (0..3).min_by(|&i, &j| svd.singular_values[i].total_cmp(&svd.singular_values[j])).unwrap_or(2);
+    let mut d = Matrix3::identity();
+    if (v * u.transpose()).determinant() < 0.0 {
+        d[(weakest, weakest)] = -1.0;
+    }
+    let rotation = v * d * u.transpose();
+
+    let mut normal = Matrix3::zeros();
+    let mut rhs = Vector3::zeros();
+    for (a, b) in motions {
+        let m = a.rotation - Matrix3::identity();
+        normal += m.transpose() * m;
+        rhs += m.transpose() * (rotation * b.position - a.position);
+    }
+    let position = normal
+        .try_inverse()
+        .map(|inverse| inverse * rhs)
+        .ok_or_else(|| String::from("Hand-eye translation is not determined by these motions"))?;
+    let x = Pose::new(position, rotation);
+
+    let (mut rotation_sq, mut translation_sq) = (0.0, 0.0);
+    for (a, b) in motions {
+        let (ax, xb) = (a.compose(&x), x.compose(b));
+        rotation_sq += ax.rotation_angle_to(&xb).powi(2);
+        translation_sq += (ax.position - xb.position).norm_squared();
+    }
+    let n = motions.len().max(1) as f64;
+    Ok(HandEyeSolution {
+        x,
+        rotation_residual: (rotation_sq / n).sqrt(),
+        translation_residual: (translation_sq / n).sqrt(),
+    })
+}
+
+impl<const F: usize, const J: usize, S: IkSolver<J>> DHArmModel<F, J, S> {
+    /// Pose of the camera in the base frame at the current joint positions.
+    pub fn camera_pose(&self, mount: &CameraMount) -> Pose {
+        match mount.frame {
+            Some(frame) => self.frame_pose(frame).compose(&mount.camera_in_frame),
+            None => mount.camera_in_frame.clone(),
+        }
+    }
+
+    /// Maps a pose detected by the camera (camera frame) into the base frame at the
+    /// current joint positions, e.g. to use a detected object as an IK target.
+    pub fn camera_to_base(&self, mount: &CameraMount, pose_in_camera: &Pose) -> Pose {
+        self.camera_pose(mount).compose(pose_in_camera)
+    }
+
+    /// Hand-eye calibration: finds where the camera is mounted from views of a
+    /// calibration target taken at different arm configurations.
+    ///
+    /// Every pair of samples gives one motion of the mount frame and the matching
+    /// motion of the target seen by the camera, which [`solve_ax_xb`] fits. Vary the
+    /// wrist orientation about different axes between samples; translations alone do
+    /// not determine the camera's orientation. Leaves the arm at the last sample's
+    /// joint positions.
+    pub fn calibrate_camera(
+        &mut self,
+        setup: HandEyeSetup,
+        samples: &[HandEyeSample<J>],
+    ) -> Result<(CameraMount, HandEyeSolution), String> {
+        let (HandEyeSetup::EyeInHand { frame } | HandEyeSetup::EyeToHand { frame }) = setup;
+        if frame >= F {
+            return Err(format!("No frame with index {}", frame));
+        }
+        if samples.len() < 3 {
+            return Err(format!("Hand-eye calibration needs at least 3 samples, got {}", samples.len()));
+        }
+        let links: Vec<Pose> = samples
+            .iter()
+            .map(|sample| {
+                self.set_joint_positions(&sample.joint_positions);
+                self.frame_pose(frame)
+            })
+            .collect();
+
+        // Eye-in-hand: link_i X cam_i is the fixed target, so (link_j⁻¹ link_i) X = X (cam_j cam_i⁻¹).
+        // Eye-to-hand: link_i⁻¹ X cam_i is the fixed target on the link, so link_j link_i⁻¹ takes its place.
+        let mut motions = Vec::new();
+        for i in 0..samples.len() {
+            for j in i + 1..samples.len() {
+                let a = match setup {
+                    HandEyeSetup::EyeInHand { .. } => links[j].inverse().compose(&links[i]),
+                    HandEyeSetup::EyeToHand { .. } => links[j].compose(&links[i].inverse()),
+                };
+                let b = samples[j].target_in_camera.compose(&samples[i].target_in_camera.inverse());
+                motions.push((a, b));
+            }
+        }
+        let solution = solve_ax_xb(&motions)?;
+        let mount = CameraMount {
+            frame: matches!(setup, HandEyeSetup::EyeInHand { .. }).then_some(frame),
+            camera_in_frame: solution.x.clone(),
+        };
+        Ok((mount, solution))
+    }
+}
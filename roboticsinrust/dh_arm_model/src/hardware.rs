@@ -0,0 +1,273 @@
+use crate::joint::JointType;
+use crate::position_integrator::PositionIntegrator;
+use crate::units::{AngleUnit, AngularVelocityUnit};
+
+// ----------------------------------------------------------------------
+// 1. COMMAND MODES AND STATE
+// ----------------------------------------------------------------------
+
+/// The kind of setpoint a servo / motor driver accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandMode {
+    /// Absolute joint position (degrees for revolute, linear units for prismatic).
+    Position,
+    /// Joint velocity (degrees/s for revolute, linear units/s for prismatic).
+    Velocity,
+    /// Raw effort, e.g. normalized PWM duty cycle or torque, in driver units.
+    Effort,
+}
+
+/// A command for all joints in one of the supported modes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JointCommand<const J: usize> {
+    Position([f64; J]),
+    Velocity([f64; J]),
+    Effort([f64; J]),
+}
+
+impl<const J: usize> JointCommand<J> {
+    pub fn mode(&self) -> CommandMode {
+        match self {
+            JointCommand::Position(_) => CommandMode::Position,
+            JointCommand::Velocity(_) => CommandMode::Velocity,
+            JointCommand::Effort(_) => CommandMode::Effort,
+        }
+    }
+
+    pub fn values(&self) -> &[f64; J] {
+        match self {
+            JointCommand::Position(v) | JointCommand::Velocity(v) | JointCommand::Effort(v) => v,
+        }
+    }
+}
+
+/// Joint feedback as reported by the hardware, in the same user-facing units as
+/// [`crate::dh_arm_model::DHArmModel::set_joint_positions`] (degrees, degrees/s).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointState<const J: usize> {
+    pub positions: [f64; J],
+    pub velocities: [f64; J],
+}
+
+// ----------------------------------------------------------------------
+// 2. GENERIC TRAIT DEFINITION
+// ----------------------------------------------------------------------
+
+/// Defines the interface a physical (or simulated) arm driver must implement.
+pub trait ArmHardware<const J: usize> {
+    /// The command mode this driver accepts.
+    fn command_mode(&self) -> CommandMode;
+
+    /// Reads the latest joint feedback.
+    fn read_state(&mut self) -> Result<JointState<J>, String>;
+
+    /// Sends a command. Implementations should reject commands whose mode does
+    /// not match [`ArmHardware::command_mode`].
+    fn write_command(&mut self, command: &JointCommand<J>) -> Result<(), String>;
+}
+
+// ----------------------------------------------------------------------
+// 3. CONTROLLER OUTPUT CONVERSION
+// ----------------------------------------------------------------------
+
+/// Converts the joint velocity commands emitted by the controllers (degrees/s)
+/// into whichever [`CommandMode`] the hardware accepts.
+///
+/// The URT servos only take position setpoints, while `TaskSpacePidController`
+/// produces velocities, so the conversion is selected per driver.
+#[derive(Debug, Clone)]
+pub struct CommandConverter<const J: usize> {
+    /// Velocity (deg/s) to effort gain per joint, used in [`CommandMode::Effort`].
+    pub effort_gains: [f64; J],
+    /// Symmetric saturation applied to effort commands (e.g. 1.0 for PWM duty).
+    effort_limit: f64,
+    /// Turns velocities into setpoints in [`CommandMode::Position`].
+    position_integrator: PositionIntegrator<J>,
+}
+
+impl<const J: usize> CommandConverter<J> {
+    /// Rate (1/s) at which the default position integrator pulls its setpoint towards
+    /// the measured positions.
+    pub const DEFAULT_DRIFT_GAIN: f64 = 5.0;
+
+    /// A converter whose position setpoints are not limited; see
+    /// [`CommandConverter::with_position_integrator`]. Fails if `effort_limit` is
+    /// negative or not finite.
+    pub fn new(effort_gains: [f64; J], effort_limit: f64) -> Result<Self, String> {
+        if !(effort_limit.is_finite() && effort_limit >= 0.0) {
+            return Err(format!("effort_limit must be a non-negative number, got {}", effort_limit));
+        }
+        let position_integrator = PositionIntegrator::new(Self::DEFAULT_DRIFT_GAIN, None)?;
+        Ok(Self { effort_gains, effort_limit, position_integrator })
+    }
+
+    /// Uses `integrator` for position setpoints, e.g. one from
+    /// [`PositionIntegrator::from_joints`] that clamps them to the joint limits.
+    pub fn with_position_integrator(mut self, integrator: PositionIntegrator<J>) -> Self {
+        self.position_integrator = integrator;
+        self
+    }
+
+    pub fn effort_limit(&self) -> f64 {
+        self.effort_limit
+    }
+
+    pub fn position_integrator(&self) -> &PositionIntegrator<J> {
+        &self.position_integrator
+    }
+
+    /// Builds the command for `mode` from a joint velocity command.
+    ///
+    /// * `Velocity`: passed through unchanged.
+    /// * `Position`: the next setpoint of the [`PositionIntegrator`], which clamps to
+    ///   its limits and corrects drift from the measured positions.
+    /// * `Effort`: per-joint proportional mapping, saturated at `effort_limit`.
+    pub fn convert(
+        &mut self,
+        mode: CommandMode,
+        joint_velocities: &[f64; J],
+        state: &JointState<J>,
+        dt: f64,
+    ) -> JointCommand<J> {
+        match mode {
+            CommandMode::Velocity => JointCommand::Velocity(*joint_velocities),
+            CommandMode::Position => {
+                JointCommand::Position(self.position_integrator.step(joint_velocities, Some(&state.positions), dt))
+            }
+            CommandMode::Effort => {
+                let mut u = [0.0; J];
+                for (i, u_i) in u.iter_mut().enumerate() {
+                    *u_i = (self.effort_gains[i] * joint_velocities[i])
+                        .max(-self.effort_limit)
+                        .min(self.effort_limit);
+                }
+                JointCommand::Effort(u)
+            }
+        }
+    }
+
+    /// Converts `joint_velocities` for `hardware`'s mode and sends it.
+    pub fn send<H: ArmHardware<J>>(
+        &mut self,
+        hardware: &mut H,
+        joint_velocities: &[f64; J],
+        state: &JointState<J>,
+        dt: f64,
+    ) -> Result<JointCommand<J>, String> {
+        let command = self.convert(hardware.command_mode(), joint_velocities, state, dt);
+        hardware.write_command(&command)?;
+        Ok(command)
+    }
+}
+
+impl<const J: usize> Default for CommandConverter<J> {
+    fn default() -> Self {
+        Self::new([1.0; J], 1.0).expect("default effort limit is valid")
+    }
+}
+
+// ----------------------------------------------------------------------
+// 4. DRIVE UNITS
+// ----------------------------------------------------------------------
+
+/// Wraps a driver that reports and takes revolute joint values in other units than
+/// the crate's (degrees, degrees/s), e.g. a drive working in radians and rpm, so the
+/// controllers and [`CommandConverter`] see the usual ones. Prismatic joints and
+/// efforts pass through unchanged.
+#[derive(Debug, Clone)]
+pub struct DriveUnits<H, const J: usize> {
+    hardware: H,
+    joint_types: [JointType; J],
+    angle: AngleUnit,
+    angular_velocity: AngularVelocityUnit,
+}
+
+impl<H, const J: usize> DriveUnits<H, J> {
+    /// `angle` and `angular_velocity` are the units `hardware` works in.
+    pub fn new(hardware: H, joint_types: [JointType; J], angle: AngleUnit, angular_velocity: AngularVelocityUnit) -> Self {
+        Self { hardware, joint_types, angle, angular_velocity }
+    }
+
+    pub fn hardware(&self) -> &H {
+        &self.hardware
+    }
+
+    pub fn hardware_mut(&mut self) -> &mut H {
+        &mut self.hardware
+    }
+
+    pub fn into_inner(self) -> H {
+        self.hardware
+    }
+
+    /// Applies `convert` to the values of the revolute joints.
+    fn revolute(&self, values: &[f64; J], convert: impl Fn(f64) -> f64) -> [f64; J] {
+        core::array::from_fn(|i| match self.joint_types[i] {
+            JointType::Revolute => convert(values[i]),
+            JointType::Prismatic => values[i],
+        })
+    }
+}
+
+impl<H: ArmHardware<J>, const J: usize> ArmHardware<J> for DriveUnits<H, J> {
+    fn command_mode(&self) -> CommandMode {
+        self.hardware.command_mode()
+    }
+
+    fn read_state(&mut self) -> Result<JointState<J>, String> {
+        let state = self.hardware.read_state()?;
+        Ok(JointState {
+            positions: self.revolute(&state.positions, |v| self.angle.convert(v, AngleUnit::Degree)),
+            velocities: self.revolute(&state.velocities, |v| {
+                self.angular_velocity.convert(v, AngularVelocityUnit::DegreesPerSecond)
+            }),
+        })
+    }
+
+    fn write_command(&mut self, command: &JointCommand<J>) -> Result<(), String> {
+        let command = match command {
+            JointCommand::Position(p) => JointCommand::Position(self.revolute(p, |v| AngleUnit::Degree.convert(v, self.angle))),
+            JointCommand::Velocity(v) => JointCommand::Velocity(self.revolute(v, |v| {
+                AngularVelocityUnit::DegreesPerSecond.convert(v, self.angular_velocity)
+            })),
+            JointCommand::Effort(_) => *command,
+        };
+        self.hardware.write_command(&command)
+    }
+}
+
+// ----------------------------------------------------------------------
+// 5. TOOL I/O
+// ----------------------------------------------------------------------
+
+/// Digital I/O and event outlet that programs drive alongside the arm, e.g. a
+/// gripper valve on an output, a part-present sensor on an input or a message to
+/// the cell controller. Channel numbers are the driver's own.
+pub trait ToolIo {
+    fn set_output(&mut self, channel: usize, on: bool) -> Result<(), String>;
+
+    fn read_input(&mut self, channel: usize) -> Result<bool, String>;
+
+    /// Announces that a program reached a named point, e.g. for logging or for
+    /// other machines to synchronise on.
+    fn publish_event(&mut self, event: &str) -> Result<(), String>;
+}
+
+/// [`ToolIo`] for setups without any: every call fails, so a program that uses I/O
+/// stops instead of running on without it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoToolIo;
+
+impl ToolIo for NoToolIo {
+    fn set_output(&mut self, channel: usize, _on: bool) -> Result<(), String> {
+        Err(format!("No tool I/O to set output {} on", channel))
+    }
+
+    fn read_input(&mut self, channel: usize) -> Result<bool, String> {
+        Err(format!("No tool I/O to read input {} from", channel))
+    }
+
+    fn publish_event(&mut self, event: &str) -> Result<(), String> {
+        Err(format!("No tool I/O to publish event '{}' on", event))
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::dh::Pose;
+use crate::dh_arm_model::DHArmModel;
+use crate::inverse_kinematics_solvers::{IkError, IkSolver};
+
+/// Grid cell of the spatial hash.
+type Cell = (i64, i64, i64);
+
+/// A solved IK query.
+#[derive(Debug, Clone)]
+struct CachedSolution<const J: usize> {
+    pose: Pose,
+    solution: [f64; J],
+}
+
+/// Small spatial hash of recent (pose → IK solution) pairs, used to seed IK queries
+/// near poses solved before.
+///
+/// Dense Cartesian interpolation asks for targets millimetres apart; the solution
+/// of the nearest cached pose is then an excellent starting point for an iterative
+/// solver, and keeps a closed-form one on the same branch and turn (see
+/// [`IkSolver::solve_ik_seeded`]). Positions are hashed into cubes of `cell_size`;
+/// a lookup searches the target's cube and its neighbours, so only poses within
+/// about one cell are ever used. Once `capacity` solutions are held, the oldest is
+/// dropped for each new one.
+#[derive(Debug, Clone)]
+pub struct IkWarmStartCache<const J: usize> {
+    cell_size: f64,
+    capacity: usize,
+    /// Linear units one radian of orientation difference counts as when ranking
+    /// cached poses by distance to the target.
+    pub orientation_weight: f64,
+    cells: HashMap<Cell, Vec<CachedSolution<J>>>,
+    /// Cells in insertion order of their entries, for evicting the oldest.
+    order: VecDeque<Cell>,
+    hits: usize,
+    misses: usize,
+}
+
+impl<const J: usize> IkWarmStartCache<J> {
+    /// `cell_size` in the arm's linear units; a few times the step between
+    /// consecutive targets works well.
+    pub fn new(cell_size: f64, capacity: usize) -> Self {
+        Self {
+            cell_size,
+            capacity,
+            orientation_weight: cell_size,
+            cells: HashMap::new(),
+            order: VecDeque::new(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    fn cell(&self, pose: &Pose) -> Cell {
+        let index = |v: f64| (v / self.cell_size).floor() as i64;
+        (index(pose.position.x), index(pose.position.y), index(pose.position.z))
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// Lookups that found a seed, and lookups that did not.
+    pub fn stats(&self) -> (usize, usize) {
+        (self.hits, self.misses)
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.order.clear();
+    }
+
+    /// Remembers `solution` (radians) as the IK solution of `pose`.
+    pub fn insert(&mut self, pose: &Pose, solution: [f64; J]) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.order.len() == self.capacity
+            && let Some(oldest) = self.order.pop_front()
+            && let Some(entries) = self.cells.get_mut(&oldest)
+        {
+            // Entries within a cell are kept oldest first
+            entries.remove(0);
+            if entries.is_empty() {
+                self.cells.remove(&oldest);
+            }
+        }
+        let cell = self.cell(pose);
+        self.cells.entry(cell).or_default().push(CachedSolution { pose: pose.clone(), solution });
+        self.order.push_back(cell);
+    }
+
+    /// Solution of the cached pose nearest to `pose` within about a cell, if any.
+    pub fn seed(&mut self, pose: &Pose) -> Option<[f64; J]> {
+        let (cx, cy, cz) = self.cell(pose);
+        let mut best: Option<(f64, &CachedSolution<J>)> = None;
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let Some(entries) = self.cells.get(&(cx + dx, cy + dy, cz + dz)) else { continue };
+                    for entry in entries {
+                        let distance = (entry.pose.position - pose.position).norm()
+                            + self.orientation_weight * entry.pose.rotation_angle_to(pose);
+                        if best.is_none_or(|(d, _)| distance < d) {
+                            best = Some((distance, entry));
+                        }
+                    }
+                }
+            }
+        }
+        let seed = best.map(|(_, entry)| entry.solution);
+        if seed.is_some() { self.hits += 1 } else { self.misses += 1 }
+        seed
+    }
+}
+
+impl<const F: usize, const J: usize, S: IkSolver<J>> DHArmModel<F, J, S> {
+    /// Solves IK for `target_pose` seeded from the nearest pose in `cache`, and caches
+    /// the result. Without a nearby pose it solves unseeded.
+    pub fn solve_ik_cached(&self, target_pose: &Pose, cache: &mut IkWarmStartCache<J>) -> Result<[f64; J], IkError> {
+        let solution = match cache.seed(target_pose) {
+            Some(seed) => self.solve_ik_seeded(target_pose, &seed)?,
+            None => self.solve_ik_from_pose(target_pose)?,
+        };
+        cache.insert(target_pose, solution);
+        Ok(solution)
+    }
+}
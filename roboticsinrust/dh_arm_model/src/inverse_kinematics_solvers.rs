@@ -1,4 +1,71 @@
+use core::fmt;
+
 use nalgebra::Matrix3;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// Reasons an IK solve can fail.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IkError {
+    /// The solver was given the wrong number of link parameters.
+    LinkParameterCount { expected: usize, found: usize },
+    /// A computed joint value is NaN or infinite, i.e. the target is outside the workspace.
+    NonFiniteJoint { joint_index: usize },
+    /// The wrist centre is outside the annulus the upper arm and forearm can reach
+    /// (`distance` from the shoulder, linear units).
+    Unreachable { distance: f64, min: f64, max: f64 },
+    /// FK of the returned solution misses the target (linear units / rad), e.g. a wrong branch.
+    RoundTripMismatch { position_error: f64, orientation_error: f64 },
+}
+
+impl fmt::Display for IkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IkError::LinkParameterCount { expected, found } => write!(
+                f, "IK solver requires {} link parameters, but {} were provided.", expected, found
+            ),
+            IkError::NonFiniteJoint { joint_index } => write!(
+                f, "Joint {} angle is invalid (NaN or Inf); target is likely out of workspace.", joint_index + 1
+            ),
+            IkError::Unreachable { distance, min, max } => write!(
+                f, "Target out of workspace: wrist centre is {:.3} from the shoulder, reachable range is {:.3} to {:.3}.",
+                distance, min, max
+            ),
+            IkError::RoundTripMismatch { position_error, orientation_error } => write!(
+                f, "IK solution misses the target by {:.3e} in position and {:.3e} rad in orientation.",
+                position_error, orientation_error
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for IkError {}
+
+/// How far FK of an IK solution lands from its target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IkResidual {
+    /// Distance between the reached and target positions (linear units).
+    pub position_error: f64,
+    /// Angle of the rotation between the reached and target orientations (rad).
+    pub orientation_error: f64,
+}
+
+/// Largest [`IkResidual`] at which an IK solution is accepted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IkTolerance {
+    /// Linear units.
+    pub position: f64,
+    /// Radians.
+    pub orientation: f64,
+}
+
+impl Default for IkTolerance {
+    /// Tight enough to catch a wrong branch, loose enough for closed-form rounding.
+    fn default() -> Self {
+        Self { position: 1e-6, orientation: 1e-6 }
+    }
+}
 
 // ----------------------------------------------------------------------
 // 1. GENERIC TRAIT DEFINITION
@@ -9,7 +76,7 @@ pub trait IkSolver<const J: usize> {
     /// Solves the inverse kinematics problem for a given target pose components and link lengths.
     /// The number of required link lengths is specific to the solver implementation.
     /// 
-    /// Returns: Result containing the joint angles [theta1..theta6] or an error string.
+    /// Returns: Result containing the joint angles [theta1..theta6] or an [`IkError`].
     fn solve_ik(
         &self,
         x: f64, 
@@ -17,7 +84,39 @@ pub trait IkSolver<const J: usize> {
         z: f64, 
         r: &Matrix3<f64>,
         link_lengths: &[f64], // <--- CHANGE: Now a dynamically sized slice
-    ) -> Result<[f64; J], String>;
+    ) -> Result<[f64; J], IkError>;
+
+    /// [`IkSolver::solve_ik`] starting from `seed` (radians), typically the solution
+    /// of a nearby pose (e.g. from an `ik_cache::IkWarmStartCache`).
+    ///
+    /// The default ignores the seed. Iterative solvers should start from it, and
+    /// closed-form ones can use it to pick the solution nearest to it.
+    fn solve_ik_seeded(
+        &self,
+        x: f64,
+        y: f64,
+        z: f64,
+        r: &Matrix3<f64>,
+        link_lengths: &[f64],
+        _seed: &[f64; J],
+    ) -> Result<[f64; J], IkError> {
+        self.solve_ik(x, y, z, r, link_lengths)
+    }
+
+    /// Cheap test of whether the target can be reached at all, without solving.
+    ///
+    /// The default accepts every target; solvers with a closed-form workspace
+    /// should override it and call it at the start of [`IkSolver::solve_ik`].
+    fn check_reachable(
+        &self,
+        _x: f64,
+        _y: f64,
+        _z: f64,
+        _r: &Matrix3<f64>,
+        _link_lengths: &[f64],
+    ) -> Result<(), IkError> {
+        Ok(())
+    }
 }
 
 // ----------------------------------------------------------------------
@@ -25,8 +124,20 @@ pub trait IkSolver<const J: usize> {
 // ----------------------------------------------------------------------
 
 /// Concrete struct for the URT arm's closed-form IK solver.
+///
+/// Link lengths are [l1, l2, l3, l4, l5]: base height, upper arm, forearm (elbow to
+/// wrist centre), wrist centre to flange and flange to tool point. Joint angles follow
+/// the URT DH table ([`crate::urt::urt_dh_table`]), elbow on the positive-θ3 branch.
 pub struct UrtIkSolver;
 
+impl UrtIkSolver {
+    /// Wrist centre for a tool pose: `l4 + l5` back along the tool z-axis.
+    fn wrist_centre(x: f64, y: f64, z: f64, r: &Matrix3<f64>, link_lengths: &[f64]) -> (f64, f64, f64) {
+        let d = link_lengths[3] + link_lengths[4];
+        (x - d * r[(0, 2)], y - d * r[(1, 2)], z - d * r[(2, 2)])
+    }
+}
+
 impl IkSolver<6> for UrtIkSolver {
     /// Solves IK for the URT arm, which requires exactly 5 link lengths.
     fn solve_ik(
@@ -34,39 +145,17 @@ impl IkSolver<6> for UrtIkSolver {
         x: f64, y: f64, z: f64,
         r: &Matrix3<f64>,
         link_lengths: &[f64], // <--- Slice input
-    ) -> Result<[f64; 6], String> {
+    ) -> Result<[f64; 6], IkError> {
         
-        // --- CHECK: Ensure the correct number of link lengths were provided ---
-        if link_lengths.len() != 5 {
-            return Err(format!(
-                "URT IK Solver requires 5 link parameters, but {} were provided.", 
-                link_lengths.len()
-            ));
-        }
-
-        // --- ADDED: Print input position (x, y, z) and rotation matrix (r) ---
-        println!("--- IK Solver Input ---");
-        println!("Target Position (x, y, z): ({:.4}, {:.4}, {:.4})", x, y, z);
-        println!("Target Rotation Matrix (R):");
-        
-        // Print the 3x3 matrix row-by-row for readability
-        for i in 0..3 {
-            println!("\t| {:.4}  {:.4}  {:.4} |", 
-                r[(i, 0)], r[(i, 1)], r[(i, 2)]);
-        }
-        println!("-----------------------");
+        // --- CHECK: Link lengths and reachability, before any trigonometry ---
+        self.check_reachable(x, y, z, r, link_lengths)?;
 
         let l1 = link_lengths[0];
         let l2 = link_lengths[1];
         let l3 = link_lengths[2];
-        let l4 = link_lengths[3];
-        let l5 = link_lengths[4];
-        
+
         // Step 2: wrist center (subtract distance along effector Z)
-        let d = l4 + l5;
-        let wx = x - d * r[(0, 2)];
-        let wy = y - d * r[(1, 2)];
-        let wz = z - d * r[(2, 2)];
+        let (wx, wy, wz) = Self::wrist_centre(x, y, z, r, link_lengths);
 
         // Step 3: theta1
         let theta1 = wy.atan2(wx);
@@ -78,20 +167,13 @@ impl IkSolver<6> for UrtIkSolver {
         // Step 5: theta3 (using law of cosines)
         let numerator = r_val.powi(2) + s.powi(2) - l2.powi(2) - l3.powi(2);
         let denom = 2.0 * l2 * l3;
-        let cos_theta3 = numerator / denom;
-        //if cos_theta3.abs() > 1.0 {
-        //    return Err("Target out of workspace: theta3 complex".into());
-        //}
+        // Reachability is checked above; clamp away rounding at full extension / fold
+        let cos_theta3 = (numerator / denom).clamp(-1.0, 1.0);
         let sin_theta3 = (1.0 - cos_theta3 * cos_theta3).sqrt();
         let theta3 = sin_theta3.atan2(cos_theta3);
 
-        // Step 6: theta2 (standard 2R geometry)
-        let theta2 = (s).atan2(r_val) - (l3 * sin_theta3).atan2(l2 + l3 * cos_theta3);
-        
-        // Validate first three joints are finite
-        //if !theta1.is_finite() || !theta2.is_finite() || !theta3.is_finite() {
-        //    return Err("Target out of workspace: base joints complex".into());
-        //}
+        // Step 6: theta2 (2R geometry; the DH table measures it from vertical)
+        let theta2 = core::f64::consts::FRAC_PI_2 - (s).atan2(r_val) - (l3 * sin_theta3).atan2(l2 + l3 * cos_theta3);
 
         // Precompute sines/cosines used for wrist orientation
         let c1 = theta1.cos();
@@ -104,24 +186,57 @@ impl IkSolver<6> for UrtIkSolver {
             .atan2( r[(0, 2)] * c23 * c1 - r[(2, 2)] * s23 + r[(1, 2)] * c23 * s1 );
 
         let expr = -r[(2, 2)] * c23 - r[(0, 2)] * s23 * c1 - r[(1, 2)] * s23 * s1;
-        let theta5 = ( (1.0 - expr.powi(2)).sqrt() ).atan2(-expr);
+        let theta5 = ( (1.0 - expr.powi(2)).max(0.0).sqrt() ).atan2(-expr);
 
-        let theta6 = ( -r[(2, 1)] * c23 - r[(0, 1)] * s23 * c1 - r[(1, 1)] * s23 * s1 )
+        let theta6 = ( r[(2, 1)] * c23 + r[(0, 1)] * s23 * c1 + r[(1, 1)] * s23 * s1 )
             .atan2( -r[(2, 0)] * c23 - r[(0, 0)] * s23 * c1 - r[(1, 0)] * s23 * s1 );
 
         // Final check
         let thetas = [theta1, theta2, theta3, theta4, theta5, theta6];
-        if thetas.iter().any(|t| !t.is_finite()) {
-            // --- MODIFIED ERROR MESSAGE ---
-            let thetas_str: Vec<String> = thetas.iter().map(|t| format!("{:.4}", t)).collect();
-            
-            return Err(format!(
-                "One or more joint angles are invalid (NaN or Inf). Calculated: [{}]",
-                thetas_str.join(", ")
-            ));
-            // ------------------------------
+        if let Some(joint_index) = thetas.iter().position(|t| !t.is_finite()) {
+            return Err(IkError::NonFiniteJoint { joint_index });
         }
         
         Ok(thetas)
     }
-}
\ No newline at end of file
+
+    /// Closed form, so the seed only picks among equivalent angles: each joint is
+    /// shifted by whole turns to lie nearest the seed, which keeps consecutive
+    /// solutions along a dense path from jumping by 2π.
+    fn solve_ik_seeded(
+        &self,
+        x: f64, y: f64, z: f64,
+        r: &Matrix3<f64>,
+        link_lengths: &[f64],
+        seed: &[f64; 6],
+    ) -> Result<[f64; 6], IkError> {
+        let thetas = self.solve_ik(x, y, z, r, link_lengths)?;
+        let tau = core::f64::consts::TAU;
+        Ok(core::array::from_fn(|i| thetas[i] + tau * ((seed[i] - thetas[i]) / tau).round()))
+    }
+
+    /// Rejects targets whose wrist centre is nearer to or farther from the shoulder
+    /// than the upper arm and forearm can fold or stretch to.
+    fn check_reachable(
+        &self,
+        x: f64, y: f64, z: f64,
+        r: &Matrix3<f64>,
+        link_lengths: &[f64],
+    ) -> Result<(), IkError> {
+        if link_lengths.len() != 5 {
+            return Err(IkError::LinkParameterCount { expected: 5, found: link_lengths.len() });
+        }
+        let (wx, wy, wz) = Self::wrist_centre(x, y, z, r, link_lengths);
+        let (l1, l2, l3) = (link_lengths[0], link_lengths[1], link_lengths[2]);
+
+        let distance = (wx * wx + wy * wy + (wz - l1) * (wz - l1)).sqrt();
+        let (min, max) = ((l2 - l3).abs(), l2 + l3);
+        // Allow for rounding when the target was generated at full extension
+        let slack = 1e-9 * max;
+        if distance.is_finite() && distance >= min - slack && distance <= max + slack {
+            Ok(())
+        } else {
+            Err(IkError::Unreachable { distance, min, max })
+        }
+    }
+}
@@ -1,4 +1,5 @@
 /// The mechanical classification of a joint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum JointType {
     Revolute,   // angle, radians
     Prismatic,  // position, meters (or consistent linear unit)
@@ -9,6 +10,7 @@ pub enum JointType {
 /// This struct acts as a safety wrapper, ensuring that commanded positions 
 /// stay within physical hardware limits and that user-facing units (like degrees) 
 /// are correctly internalized as standard SI units (radians/meters).
+#[derive(Debug, Clone)]
 pub struct Joint {
     pub joint_type: JointType,
 
@@ -32,28 +34,19 @@ pub struct Joint {
 impl Joint {
     /// Create a joint with optional limits.
     pub fn new(joint_type: JointType, limit_min: Option<f64>, limit_max: Option<f64>) -> Self {
-        let is_revolute = matches!(joint_type, JointType::Revolute);
-
         Self {
             joint_type,
             position: 0.0,
             velocity: 0.0,
-            limit_min: limit_min.map(|val| if is_revolute { val.to_radians() } else { val }),
-            limit_max: limit_max.map(|val| if is_revolute { val.to_radians() } else { val }),
+            limit_min: limit_min.map(|val| joint_type.from_user_units(val)),
+            limit_max: limit_max.map(|val| joint_type.from_user_units(val)),
         }
     }
 
 
     /// Set joint position with limit checking. For revolute joints, assume input is in degrees for user and convert to radians.
     pub fn set_position(&mut self, pos: f64) {
-        match self.joint_type {
-            JointType::Revolute => {
-                self.position = pos.to_radians(); // Will apply limits below
-            }
-            JointType::Prismatic => {
-                self.position = pos; // Will apply limits below
-            }
-        }
+        self.position = self.joint_type.from_user_units(pos); // Will apply limits below
 
 
         if let Some(min) = self.limit_min {
@@ -70,20 +63,14 @@ impl Joint {
     }
     /// Set joint velocity. For revolute joints, assume input is in degrees/s for user and convert to radians/s.
     pub fn set_velocity(&mut self, vel: f64) {
-        match self.joint_type {
-            JointType::Revolute => {
-                self.velocity = vel.to_radians(); // Assume input is in rad/s for revolute joints
-            }
-            JointType::Prismatic => {
-                self.velocity = vel; // Assume input is in m/s for prismatic joints
-            }
-        }
+        self.velocity = self.joint_type.from_user_units(vel);
     }
 
     // -------------------------------
     // Pretty Printer
     // -------------------------------
 
+    #[cfg(feature = "std")]
     pub fn print_info(&self) {
         match self.joint_type {
             JointType::Revolute => {
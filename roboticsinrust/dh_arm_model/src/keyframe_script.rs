@@ -0,0 +1,571 @@
+use std::fs;
+use std::path::Path;
+
+use crate::dh::Pose;
+use crate::dh_arm_model::DHArmModel;
+use crate::hardware::{ArmHardware, CommandConverter, ToolIo};
+use crate::inverse_kinematics_solvers::IkSolver;
+use crate::joint::JointType;
+use crate::motion_history::JointSpaceMove;
+use crate::path_ik::{sample_line, PathIkLimits};
+use crate::units::{AngleUnit, JointAngles};
+
+/// What a gripper keyframe asks the gripper to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GripperAction {
+    Open,
+    Close,
+}
+
+/// One step of a [`KeyframeScript`].
+#[derive(Debug, Clone)]
+pub enum Keyframe<const J: usize> {
+    /// Move straight through joint space to `target` (the script's angle unit for
+    /// revolute joints, linear units for prismatic ones) in `duration` s.
+    Joints { target: [f64; J], duration: f64 },
+    /// Move the end-effector along a straight line to `target` (base frame) in `duration` s.
+    Pose { target: Pose, duration: f64 },
+    Gripper(GripperAction),
+    /// Hold still for this many seconds.
+    Pause(f64),
+}
+
+/// What a [`Trigger`] does when it fires.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TriggerAction {
+    /// Switches a digital output (see [`ToolIo::set_output`]).
+    Output { channel: usize, on: bool },
+    /// Holds the motion at the trigger point until the input reads `on`; the script
+    /// aborts if that takes longer than `timeout` s.
+    WaitInput { channel: usize, on: bool, timeout: Option<f64> },
+    /// Publishes a named event (see [`ToolIo::publish_event`]).
+    Event(String),
+    /// Returned in [`ScriptCommand::gripper`] like a gripper keyframe.
+    Gripper(GripperAction),
+}
+
+/// An action fired part way through a move or pause keyframe, e.g. opening the
+/// gripper at 90 % of the approach.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trigger {
+    /// Index of the keyframe it belongs to.
+    pub keyframe: usize,
+    /// Point of that keyframe it fires at, as a fraction of its duration from 0 to 1.
+    /// Moves are spread evenly over their duration, so this is also the fraction of
+    /// the path.
+    pub at: f64,
+    pub action: TriggerAction,
+}
+
+/// A timed sequence of keyframes, for running a demo routine without an operator.
+///
+/// Scripts are written in a small subset of TOML: optional top-level settings
+/// `loop = true` to repeat the routine until stopped and `angle_unit = "rad"` to give
+/// joint and pose angles in radians instead of degrees, then one `[[keyframe]]`
+/// table per step holding exactly one of
+///
+/// ```toml
+/// [[keyframe]]
+/// joints = [0, 45, 90, 0, 45, 0]   # angles / linear units, with `duration` (s)
+/// duration = 2.0
+///
+/// [[keyframe]]
+/// pose = [30, 0, 20, 0, 90, 0]     # x y z (base frame), yaw pitch roll (angles), with `duration`
+/// duration = 1.5
+///
+/// [[keyframe]]
+/// gripper = "close"                # or "open"
+///
+/// [[keyframe]]
+/// pause = 0.5                      # s
+/// ```
+///
+/// A move or pause keyframe can be followed by `[[keyframe.trigger]]` tables, each
+/// firing one action at `at`, a fraction of the keyframe from 0 to 1:
+///
+/// ```toml
+/// [[keyframe.trigger]]
+/// at = 0.9
+/// gripper = "open"                 # or
+/// output = 2                       # digital output, with `on = false` to switch it off
+/// wait_input = 1                   # hold here until the input is on (`on = false`: off),
+/// timeout = 5.0                    # optionally failing after this many s
+/// event = "part_placed"            # published to the tool I/O
+/// ```
+///
+/// Values are numbers, arrays of numbers, quoted strings and booleans; `#` starts a comment.
+#[derive(Debug, Clone)]
+pub struct KeyframeScript<const J: usize> {
+    pub keyframes: Vec<Keyframe<J>>,
+    /// Actions fired along the keyframes, in any order.
+    pub triggers: Vec<Trigger>,
+    /// Start over after the last keyframe.
+    pub repeat: bool,
+    /// Unit of the revolute joint targets and pose orientations (degrees unless the
+    /// script sets `angle_unit`).
+    pub angle_unit: AngleUnit,
+}
+
+/// A parsed `key = value` line.
+#[derive(Debug, Clone)]
+enum Value {
+    Number(f64),
+    Array(Vec<f64>),
+    Text(String),
+    Bool(bool),
+}
+
+/// A `key = value` line of a keyframe table, with its line number.
+type Entry = (usize, String, Value);
+
+/// The line a table starts on and its entries.
+type Table = (usize, Vec<Entry>);
+
+impl<const J: usize> KeyframeScript<J> {
+    const TABLE: &'static str = "[[keyframe]]";
+    const TRIGGER_TABLE: &'static str = "[[keyframe.trigger]]";
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let text = fs::read_to_string(path.as_ref())
+            .map_err(|e| format!("Failed to read script {}: {}", path.as_ref().display(), e))?;
+        Self::parse(&text).map_err(|e| format!("{}: {}", path.as_ref().display(), e))
+    }
+
+    /// Parses the script format described on [`KeyframeScript`].
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut repeat = false;
+        let mut angle_unit = AngleUnit::Degree;
+        // Each keyframe table with its trigger tables; entries belong to the table
+        // opened last
+        let mut tables: Vec<(Table, Vec<Table>)> = Vec::new();
+
+        for (line_no, line) in text.lines().enumerate().map(|(i, l)| (i + 1, strip_comment(l).trim())) {
+            if line.is_empty() {
+                continue;
+            }
+            if line == Self::TABLE {
+                tables.push(((line_no, Vec::new()), Vec::new()));
+                continue;
+            }
+            if line == Self::TRIGGER_TABLE {
+                let (_, triggers) = tables
+                    .last_mut()
+                    .ok_or_else(|| format!("Trigger on line {} comes before any keyframe", line_no))?;
+                triggers.push((line_no, Vec::new()));
+                continue;
+            }
+            if line.starts_with('[') {
+                return Err(format!("Unknown table '{}' on line {}", line, line_no));
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("Expected 'key = value' on line {}", line_no))?;
+            let (key, value) = (key.trim().to_string(), parse_value(value.trim(), line_no)?);
+            match tables.last_mut() {
+                Some(((_, entries), triggers)) => match triggers.last_mut() {
+                    Some((_, trigger_entries)) => trigger_entries.push((line_no, key, value)),
+                    None => entries.push((line_no, key, value)),
+                },
+                None => match (key.as_str(), value) {
+                    ("loop", Value::Bool(b)) => repeat = b,
+                    ("angle_unit", Value::Text(unit)) => {
+                        angle_unit = unit.parse().map_err(|e| format!("{} on line {}", e, line_no))?;
+                    }
+                    _ => return Err(format!("Unknown setting '{}' on line {}", key, line_no)),
+                },
+            }
+        }
+
+        let mut keyframes = Vec::with_capacity(tables.len());
+        let mut triggers = Vec::new();
+        for (index, ((line_no, entries), trigger_tables)) in tables.into_iter().enumerate() {
+            let keyframe = Self::keyframe(line_no, &entries, angle_unit)?;
+            for (trigger_line, trigger_entries) in trigger_tables {
+                if let Keyframe::Gripper(_) = keyframe {
+                    return Err(format!("Trigger on line {} belongs to a gripper keyframe, which has no duration", trigger_line));
+                }
+                triggers.push(trigger(index, trigger_line, &trigger_entries)?);
+            }
+            keyframes.push(keyframe);
+        }
+        Ok(Self { keyframes, triggers, repeat, angle_unit })
+    }
+
+    /// Builds the keyframe of the table starting on `line_no`, reading pose angles in `angle_unit`.
+    fn keyframe(line_no: usize, entries: &[Entry], angle_unit: AngleUnit) -> Result<Keyframe<J>, String> {
+        let mut duration = None;
+        let mut step: Option<Keyframe<J>> = None;
+        for (entry_line, key, value) in entries {
+            let number = |value: &Value| match value {
+                Value::Number(v) if v.is_finite() && *v >= 0.0 => Ok(*v),
+                _ => Err(format!("'{}' on line {} must be a non-negative number", key, entry_line)),
+            };
+            let array = |value: &Value, len: usize| match value {
+                Value::Array(values) if values.len() == len => Ok(values.clone()),
+                _ => Err(format!("'{}' on line {} must be an array of {} numbers", key, entry_line, len)),
+            };
+            let parsed = match key.as_str() {
+                "duration" => {
+                    duration = Some(number(value)?);
+                    continue;
+                }
+                "joints" => {
+                    let values = array(value, J)?;
+                    Keyframe::Joints { target: core::array::from_fn(|i| values[i]), duration: 0.0 }
+                }
+                "pose" => {
+                    let v = array(value, 6)?;
+                    let angle = |v: f64| angle_unit.to_radians(v);
+                    let target = Pose::from_components(v[0], v[1], v[2], angle(v[3]), angle(v[4]), angle(v[5]));
+                    Keyframe::Pose { target, duration: 0.0 }
+                }
+                "gripper" => Keyframe::Gripper(gripper_action(value, *entry_line)?),
+                "pause" => Keyframe::Pause(number(value)?),
+                _ => return Err(format!("Unknown key '{}' on line {}", key, entry_line)),
+            };
+            if step.replace(parsed).is_some() {
+                return Err(format!("Keyframe on line {} has more than one action", line_no));
+            }
+        }
+
+        match (step, duration) {
+            (None, _) => Err(format!("Keyframe on line {} has no action", line_no)),
+            (Some(Keyframe::Joints { target, .. }), Some(duration)) => Ok(Keyframe::Joints { target, duration }),
+            (Some(Keyframe::Pose { target, .. }), Some(duration)) => Ok(Keyframe::Pose { target, duration }),
+            (Some(Keyframe::Joints { .. } | Keyframe::Pose { .. }), None) => {
+                Err(format!("Move keyframe on line {} needs a 'duration'", line_no))
+            }
+            (Some(_), Some(_)) => Err(format!("Keyframe on line {} does not take a 'duration'", line_no)),
+            (Some(step), None) => Ok(step),
+        }
+    }
+}
+
+/// Builds the trigger of keyframe `keyframe` from the table starting on `line_no`.
+fn trigger(keyframe: usize, line_no: usize, entries: &[Entry]) -> Result<Trigger, String> {
+    let (mut at, mut on, mut timeout) = (None, None, None);
+    let mut action = None;
+    for (entry_line, key, value) in entries {
+        let channel = || match value {
+            Value::Number(v) if *v >= 0.0 && v.fract() == 0.0 => Ok(*v as usize),
+            _ => Err(format!("'{}' on line {} must be a channel number", key, entry_line)),
+        };
+        let parsed = match (key.as_str(), value) {
+            ("at", Value::Number(v)) if (0.0..=1.0).contains(v) => {
+                at = Some(*v);
+                continue;
+            }
+            ("at", _) => return Err(format!("'at' on line {} must be a number from 0 to 1", entry_line)),
+            ("on", Value::Bool(b)) => {
+                on = Some(*b);
+                continue;
+            }
+            ("on", _) => return Err(format!("'on' on line {} must be true or false", entry_line)),
+            ("timeout", Value::Number(v)) if v.is_finite() && *v >= 0.0 => {
+                timeout = Some(*v);
+                continue;
+            }
+            ("timeout", _) => return Err(format!("'timeout' on line {} must be a non-negative number", entry_line)),
+            ("output", _) => TriggerAction::Output { channel: channel()?, on: true },
+            ("wait_input", _) => TriggerAction::WaitInput { channel: channel()?, on: true, timeout: None },
+            ("event", Value::Text(name)) => TriggerAction::Event(name.clone()),
+            ("event", _) => return Err(format!("'event' on line {} must be a quoted name", entry_line)),
+            ("gripper", _) => TriggerAction::Gripper(gripper_action(value, *entry_line)?),
+            _ => return Err(format!("Unknown key '{}' on line {}", key, entry_line)),
+        };
+        if action.replace(parsed).is_some() {
+            return Err(format!("Trigger on line {} has more than one action", line_no));
+        }
+    }
+
+    let at = at.ok_or_else(|| format!("Trigger on line {} needs an 'at'", line_no))?;
+    let action = match (action, on, timeout) {
+        (None, _, _) => return Err(format!("Trigger on line {} has no action", line_no)),
+        (Some(TriggerAction::Output { channel, .. }), on, None) => TriggerAction::Output { channel, on: on.unwrap_or(true) },
+        (Some(TriggerAction::WaitInput { channel, .. }), on, timeout) => {
+            TriggerAction::WaitInput { channel, on: on.unwrap_or(true), timeout }
+        }
+        (Some(action @ (TriggerAction::Event(_) | TriggerAction::Gripper(_))), None, None) => action,
+        (Some(_), _, _) => return Err(format!("Trigger on line {} does not take 'on' or 'timeout' with its action", line_no)),
+    };
+    Ok(Trigger { keyframe, at, action })
+}
+
+fn gripper_action(value: &Value, line_no: usize) -> Result<GripperAction, String> {
+    match value {
+        Value::Text(s) if s == "open" => Ok(GripperAction::Open),
+        Value::Text(s) if s == "close" => Ok(GripperAction::Close),
+        _ => Err(format!("'gripper' on line {} must be \"open\" or \"close\"", line_no)),
+    }
+}
+
+/// `line` up to a `#` that is not inside a string.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_value(s: &str, line_no: usize) -> Result<Value, String> {
+    let number = |s: &str| s.trim().parse::<f64>().map_err(|_| format!("Invalid number '{}' on line {}", s.trim(), line_no));
+    if let Some(inner) = s.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+        let values = inner.split(',').map(str::trim).filter(|v| !v.is_empty()).map(number);
+        return Ok(Value::Array(values.collect::<Result<_, _>>()?));
+    }
+    if let Some(text) = s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        return Ok(Value::Text(text.to_string()));
+    }
+    match s {
+        "true" => Ok(Value::Bool(true)),
+        "false" => Ok(Value::Bool(false)),
+        _ => Ok(Value::Number(number(s)?)),
+    }
+}
+
+/// Output of one control cycle of a [`ScriptPlayer`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScriptCommand<const J: usize> {
+    /// Joint velocity command (deg/s or linear units/s).
+    pub joint_velocity: [f64; J],
+    /// Gripper action reached this cycle, by a keyframe or a trigger, for the caller to
+    /// forward to the gripper.
+    pub gripper: Option<GripperAction>,
+}
+
+/// Keyframe being played.
+#[derive(Debug, Clone)]
+enum Playing<const J: usize> {
+    /// Following `path` (deg / linear units), evenly spread over `duration` s.
+    Move { path: Vec<[f64; J]>, duration: f64, elapsed: f64 },
+    Pause { duration: f64, elapsed: f64 },
+}
+
+impl<const J: usize> Playing<J> {
+    /// Duration and time elapsed so far (s).
+    fn timing(&self) -> (f64, f64) {
+        match self {
+            Playing::Move { duration, elapsed, .. } | Playing::Pause { duration, elapsed } => (*duration, *elapsed),
+        }
+    }
+}
+
+/// Plays a [`KeyframeScript`] one control cycle at a time, on the simulator or on
+/// hardware.
+///
+/// Each move is planned when its keyframe starts, from wherever the arm is then:
+/// joint moves interpolate linearly in joint space, pose moves follow a straight
+/// line solved by [`DHArmModel::solve_path_ik`], and both are checked against the
+/// joint limits before the arm moves. A keyframe that cannot be planned aborts the
+/// script with an error naming it.
+///
+/// Triggers fire through a [`ToolIo`] in the cycle their keyframe reaches them; one
+/// that fails aborts the script the same way. While a trigger waits for an input,
+/// the keyframe's clock stops and the arm holds at the trigger point.
+#[derive(Debug, Clone)]
+pub struct ScriptPlayer<const J: usize> {
+    script: KeyframeScript<J>,
+    /// Keyframe to play next, and the one being played if it has been started.
+    index: usize,
+    playing: Option<Playing<J>>,
+    /// Triggers of the keyframe being played that have fired, in order of `at`.
+    fired: usize,
+    /// Time spent waiting on the current trigger's input (s).
+    waited: f64,
+    /// Largest end-effector step between samples of a pose move (linear units) ...
+    pub max_linear_step: f64,
+    /// ... and largest rotation between them (rad).
+    pub max_angular_step: f64,
+    /// Continuity limits on the joint solutions of a pose move.
+    pub path_limits: PathIkLimits<J>,
+}
+
+impl<const J: usize> ScriptPlayer<J> {
+    pub fn new(mut script: KeyframeScript<J>) -> Self {
+        // Keeps the file order of triggers at the same point
+        script.triggers.sort_by(|a, b| a.keyframe.cmp(&b.keyframe).then(a.at.total_cmp(&b.at)));
+        Self {
+            script,
+            index: 0,
+            playing: None,
+            fired: 0,
+            waited: 0.0,
+            max_linear_step: 0.5,
+            max_angular_step: AngleUnit::Degree.to_radians(2.0),
+            path_limits: PathIkLimits::uniform(10.0),
+        }
+    }
+
+    pub fn script(&self) -> &KeyframeScript<J> {
+        &self.script
+    }
+
+    /// Index of the keyframe being played (or about to be).
+    pub fn keyframe(&self) -> usize {
+        self.index
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.index >= self.script.keyframes.len()
+    }
+
+    /// Plans the motion of `keyframe` from `joint_pos`; `None` for keyframes without one.
+    fn start<const F: usize, S: IkSolver<J>>(
+        &self,
+        arm: &mut DHArmModel<F, J, S>,
+        joint_pos: &[f64; J],
+        keyframe: &Keyframe<J>,
+    ) -> Result<Option<Playing<J>>, String> {
+        let (path, duration) = match keyframe {
+            Keyframe::Joints { target, duration } => {
+                // Joint positions here are in degrees, whatever the script was written in
+                let target = core::array::from_fn(|i| match arm.joints()[i].joint_type {
+                    JointType::Revolute => self.script.angle_unit.convert(target[i], AngleUnit::Degree),
+                    JointType::Prismatic => target[i],
+                });
+                (vec![*joint_pos, target], *duration)
+            }
+            Keyframe::Pose { target, duration } => {
+                arm.set_joint_positions(joint_pos);
+                let line = sample_line(&arm.end_effector_pose(), target, self.max_linear_step, self.max_angular_step);
+                let start = JointAngles::from_user_units(joint_pos, arm.joints());
+                let solutions = arm.solve_path_ik(&line, &start, &self.path_limits).map_err(|e| e.to_string())?;
+                let path = solutions.iter().map(|q| q.to_user_units(arm.joints())).collect();
+                (path, *duration)
+            }
+            Keyframe::Pause(duration) => return Ok(Some(Playing::Pause { duration: *duration, elapsed: 0.0 })),
+            Keyframe::Gripper(_) => return Ok(None),
+        };
+        JointSpaceMove::new(path[0], path[1..].to_vec(), 1.0).check(arm, None)?;
+        Ok(Some(Playing::Move { path, duration, elapsed: 0.0 }))
+    }
+
+    /// Moves on to the next keyframe, wrapping around if the script repeats.
+    fn advance(&mut self) {
+        self.playing = None;
+        self.fired = 0;
+        self.waited = 0.0;
+        self.index += 1;
+        if self.is_finished() && self.script.repeat {
+            self.index = 0;
+        }
+    }
+
+    /// Fires the triggers of the keyframe being played up to `progress` (fraction of
+    /// the keyframe). Returns the point of a trigger still waiting for its input, where
+    /// the keyframe holds.
+    fn fire_triggers<I: ToolIo + ?Sized>(
+        &mut self,
+        io: &mut I,
+        progress: f64,
+        dt: f64,
+        command: &mut ScriptCommand<J>,
+    ) -> Result<Option<f64>, String> {
+        let index = self.index;
+        while let Some(trigger) = self.script.triggers.iter().filter(|t| t.keyframe == index).nth(self.fired) {
+            if trigger.at > progress {
+                break;
+            }
+            match &trigger.action {
+                TriggerAction::Output { channel, on } => io.set_output(*channel, *on)?,
+                TriggerAction::WaitInput { channel, on, timeout } => {
+                    if io.read_input(*channel)? != *on {
+                        self.waited += dt;
+                        if timeout.is_some_and(|timeout| self.waited > timeout) {
+                            return Err(format!(
+                                "Timed out after {:.1} s waiting for input {} to turn {}",
+                                self.waited, channel, if *on { "on" } else { "off" }
+                            ));
+                        }
+                        return Ok(Some(trigger.at));
+                    }
+                    self.waited = 0.0;
+                }
+                TriggerAction::Event(name) => io.publish_event(name)?,
+                TriggerAction::Gripper(action) => command.gripper = Some(*action),
+            }
+            self.fired += 1;
+        }
+        Ok(None)
+    }
+
+    /// Command for one control cycle of length `dt` from the measured `joint_pos`
+    /// (deg / linear units), firing triggers through `io`; zero velocity once the
+    /// script has finished.
+    pub fn step<const F: usize, S: IkSolver<J>, I: ToolIo + ?Sized>(
+        &mut self,
+        arm: &mut DHArmModel<F, J, S>,
+        io: &mut I,
+        joint_pos: &[f64; J],
+        dt: f64,
+    ) -> Result<ScriptCommand<J>, String> {
+        let mut command = ScriptCommand { joint_velocity: [0.0; J], gripper: None };
+        let Some(keyframe) = self.script.keyframes.get(self.index) else {
+            return Ok(command);
+        };
+        if self.playing.is_none() {
+            if let Keyframe::Gripper(action) = keyframe {
+                command.gripper = Some(*action);
+                self.advance();
+                return Ok(command);
+            }
+            self.playing = self
+                .start(arm, joint_pos, keyframe)
+                .map_err(|e| format!("Keyframe {} cannot be played: {}", self.index, e))?;
+        }
+        let Some((duration, elapsed)) = self.playing.as_ref().map(Playing::timing) else {
+            return Ok(command);
+        };
+
+        let progress = |elapsed: f64| if duration > 0.0 { (elapsed / duration).min(1.0) } else { 1.0 };
+        let mut elapsed = elapsed + dt;
+        let hold = self
+            .fire_triggers(io, progress(elapsed), dt, &mut command)
+            .map_err(|e| format!("Keyframe {} trigger failed: {}", self.index, e))?;
+        if let Some(at) = hold {
+            elapsed = at * duration;
+        }
+        let s = progress(elapsed);
+
+        match &mut self.playing {
+            Some(Playing::Move { path, elapsed: move_elapsed, .. }) => {
+                *move_elapsed = elapsed;
+                // Setpoint along the path, reached from the measured position in one cycle
+                let position = s * (path.len() - 1) as f64;
+                let k = (position.floor() as usize).min(path.len() - 2);
+                let f = position - k as f64;
+                let (a, b) = (&path[k], &path[k + 1]);
+                command.joint_velocity = core::array::from_fn(|i| (a[i] + f * (b[i] - a[i]) - joint_pos[i]) / dt);
+            }
+            Some(Playing::Pause { elapsed: pause_elapsed, .. }) => *pause_elapsed = elapsed,
+            None => {}
+        }
+        if s >= 1.0 && hold.is_none() {
+            self.advance();
+        }
+        Ok(command)
+    }
+
+    /// One control cycle on `hardware`: reads its joint state, steps the script and
+    /// sends the joint velocity command converted for its mode. Triggers fire through
+    /// `io`; gripper actions are returned for the caller to forward to the gripper
+    /// driver.
+    pub fn step_hardware<const F: usize, S: IkSolver<J>, H: ArmHardware<J>, I: ToolIo + ?Sized>(
+        &mut self,
+        arm: &mut DHArmModel<F, J, S>,
+        hardware: &mut H,
+        io: &mut I,
+        converter: &mut CommandConverter<J>,
+        dt: f64,
+    ) -> Result<ScriptCommand<J>, String> {
+        let state = hardware.read_state()?;
+        let command = self.step(arm, io, &state.positions, dt)?;
+        converter.send(hardware, &command.joint_velocity, &state, dt)?;
+        Ok(command)
+    }
+}
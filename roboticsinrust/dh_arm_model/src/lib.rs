@@ -1,7 +1,75 @@
+//! Kinematic modelling and control of DH-parameterised robot arms.
+//!
+//! The core math (`dh`, `joint`, `units`, `inverse_kinematics_solvers`,
+//! `task_space_pid_controller`, `task_space_limits`, `gain_analysis`, `manipulability`,
+//! `dh_arm_model`, `position_integrator`, `elastic_joint`, `time_parameterization`,
+//! `path_ik`, `hand_eye`, `velocity_filter`, `travel_limiter`, `task_priority`, `pointing`,
+//! `dynamics`) builds without `std` when the default `std` feature is disabled, so it can
+//! run on a microcontroller driving the servos directly.
+//! Modules that need files, strings or printing are only available with `std`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod approach_retreat;
+#[cfg(feature = "std")]
+pub mod batch_ik;
+#[cfg(feature = "std")]
+pub mod cartesian_executor;
+#[cfg(feature = "std")]
+pub mod command_arbiter;
 pub mod dh;
 pub mod dh_arm_model;
+pub mod dynamics;
+pub mod elastic_joint;
+#[cfg(feature = "std")]
+pub mod frequency_response;
+pub mod gain_analysis;
+pub mod hand_eye;
+#[cfg(feature = "std")]
+pub mod hardware;
+#[cfg(feature = "std")]
+pub mod ik_cache;
 pub mod inverse_kinematics_solvers;
 pub mod joint;
+#[cfg(feature = "std")]
+pub mod keyframe_script;
+pub mod manipulability;
+#[cfg(feature = "std")]
+pub mod manifest;
+#[cfg(feature = "std")]
+pub mod motion_history;
+#[cfg(feature = "std")]
+pub mod parameters;
+pub mod path_ik;
+pub mod pointing;
+#[cfg(feature = "std")]
+pub mod pose_library;
+#[cfg(feature = "std")]
+pub mod pose_snapshot;
+pub mod position_integrator;
+#[cfg(feature = "std")]
+pub mod profiling;
+#[cfg(feature = "std")]
+pub mod safety;
+#[cfg(feature = "std")]
+pub mod settings;
+#[cfg(feature = "std")]
+pub mod state_interpolator;
+pub mod task_priority;
+pub mod task_space_limits;
 pub mod task_space_pid_controller;
+#[cfg(feature = "std")]
+pub mod telemetry;
+#[cfg(feature = "std")]
+pub mod thumbnail;
+pub mod time_parameterization;
+pub mod travel_limiter;
+pub mod units;
+pub mod urt;
+pub mod velocity_filter;
 
 
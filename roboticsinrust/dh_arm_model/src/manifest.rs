@@ -0,0 +1,262 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::dh::FrameType;
+use crate::dh_arm_model::DHArmModel;
+use crate::inverse_kinematics_solvers::IkSolver;
+use crate::joint::JointType;
+use crate::parameters::{Param, ParamValue};
+
+const HEADER: &str = "# dh_arm_model experiment manifest v1";
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher`, its output is fixed across platforms and
+/// Rust releases, so hashes recorded in old manifests stay comparable.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= b as u64;
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn u64(&mut self, v: u64) {
+        self.bytes(&v.to_le_bytes());
+    }
+
+    fn f64(&mut self, v: f64) {
+        self.u64(v.to_bits());
+    }
+
+    fn option(&mut self, v: Option<f64>) {
+        match v {
+            Some(v) => {
+                self.u64(1);
+                self.f64(v);
+            }
+            None => self.u64(0),
+        }
+    }
+}
+
+impl<const F: usize, const J: usize, S: IkSolver<J>> DHArmModel<F, J, S> {
+    /// Fingerprint of the robot's configuration: DH table, joint types and limits, IK
+    /// link parameters, length unit and link mass properties. Tunable settings (gains,
+    /// damping) and the joint state do not enter it; two runs with the same hash used
+    /// the same robot.
+    pub fn config_hash(&self) -> u64 {
+        let mut hash = Fnv1a::new();
+        hash.u64(F as u64);
+        hash.u64(J as u64);
+        for row in self.dh_table().rows() {
+            for v in row.parameters() {
+                hash.f64(v);
+            }
+            match row.frame_type() {
+                Some(FrameType::Joint(index)) => hash.u64(index as u64),
+                Some(FrameType::Fixed) => hash.u64(u64::MAX),
+                None => hash.u64(u64::MAX - 1),
+            }
+        }
+        for joint in self.joints() {
+            hash.u64(match joint.joint_type {
+                JointType::Revolute => 0,
+                JointType::Prismatic => 1,
+            });
+            hash.option(joint.limit_min);
+            hash.option(joint.limit_max);
+        }
+        for &v in self.ik_link_parameters() {
+            hash.f64(v);
+        }
+        hash.bytes(self.length_unit().symbol().as_bytes());
+        // Only arms with a mass model hash one, so older hashes stay valid
+        for (frame, link) in self.link_inertias().iter().enumerate() {
+            let Some(link) = link else { continue };
+            hash.u64(frame as u64);
+            hash.f64(link.mass);
+            for &v in link.center_of_mass.iter().chain(link.inertia.iter()) {
+                hash.f64(v);
+            }
+        }
+        hash.0
+    }
+}
+
+/// Configuration of one arm during a run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArmManifest {
+    /// [`DHArmModel::config_hash`] of the arm.
+    pub config_hash: u64,
+    /// Controller gains, limits, damping, ... by name (see [`crate::parameters::Tunable`]).
+    pub params: Vec<(String, ParamValue)>,
+}
+
+/// What a run was and what produced it, written next to its data files so results
+/// can still be interpreted (or reproduced) long after.
+///
+/// Stored as plain text, one `key value` per line:
+/// ```text
+/// # dh_arm_model experiment manifest v1
+/// scenario pick and place demo
+/// software kiss3d_sim 0.1.0
+/// dh_arm_model 0.1.0
+/// created 1760572800
+/// dt 0.05
+/// data run.log
+/// arm 0 config 5d1f0c2e8a9b7c41
+/// param 0 kp.x float 1
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExperimentManifest {
+    pub scenario: String,
+    /// Program that ran the experiment, with its version.
+    pub software: String,
+    /// Version of this library the program was built with.
+    pub library_version: String,
+    /// Start of the run, in seconds since the Unix epoch (UTC).
+    pub created: u64,
+    /// Nominal control period (s).
+    pub dt: f64,
+    /// Data files of the run, relative to the manifest.
+    pub data_files: Vec<String>,
+    pub arms: Vec<ArmManifest>,
+}
+
+impl ExperimentManifest {
+    /// A manifest for a run starting now, built with this version of the library.
+    pub fn new(scenario: &str, software: &str, dt: f64) -> Self {
+        Self {
+            scenario: scenario.to_string(),
+            software: software.to_string(),
+            library_version: env!("CARGO_PKG_VERSION").to_string(),
+            created: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+            dt,
+            data_files: Vec::new(),
+            arms: Vec::new(),
+        }
+    }
+
+    /// Records an arm's configuration and its current parameter values.
+    pub fn add_arm(&mut self, config_hash: u64, params: &[Param]) {
+        let params = params.iter().map(|p| (p.spec.name.clone(), p.value)).collect();
+        self.arms.push(ArmManifest { config_hash, params });
+    }
+
+    /// Where the manifest of `data_file` goes: alongside it, with extension `.manifest`.
+    pub fn path_for<P: AsRef<Path>>(data_file: P) -> PathBuf {
+        data_file.as_ref().with_extension("manifest")
+    }
+
+    /// `created` as an ISO 8601 UTC timestamp.
+    pub fn created_utc(&self) -> String {
+        let days = (self.created / 86_400) as i64;
+        let secs = self.created % 86_400;
+        // Civil date from days since 1970-01-01 (H. Hinnant's algorithm)
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            year, month, day, secs / 3600, secs / 60 % 60, secs % 60
+        )
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let mut text = format!("{}\n", HEADER);
+        text.push_str(&format!("scenario {}\n", self.scenario));
+        text.push_str(&format!("software {}\n", self.software));
+        text.push_str(&format!("dh_arm_model {}\n", self.library_version));
+        text.push_str(&format!("created {}\n# {}\n", self.created, self.created_utc()));
+        text.push_str(&format!("dt {}\n", self.dt));
+        for file in &self.data_files {
+            text.push_str(&format!("data {}\n", file));
+        }
+        for (i, arm) in self.arms.iter().enumerate() {
+            text.push_str(&format!("arm {} config {:016x}\n", i, arm.config_hash));
+            for (name, value) in &arm.params {
+                let kind = match value {
+                    ParamValue::Float(_) => "float",
+                    ParamValue::Int(_) => "int",
+                };
+                text.push_str(&format!("param {} {} {} {}\n", i, name, kind, value));
+            }
+        }
+        fs::write(path.as_ref(), text)
+            .map_err(|e| format!("Failed to write manifest {}: {}", path.as_ref().display(), e))
+    }
+
+    /// Reads a manifest written by [`ExperimentManifest::save`].
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let text = fs::read_to_string(path.as_ref())
+            .map_err(|e| format!("Failed to read manifest {}: {}", path.as_ref().display(), e))?;
+        let mut lines = text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+        if lines.next().map(|(_, l)| l) != Some(HEADER) {
+            return Err("Manifest is missing the expected header".into());
+        }
+
+        let mut manifest = Self {
+            scenario: String::new(),
+            software: String::new(),
+            library_version: String::new(),
+            created: 0,
+            dt: 0.0,
+            data_files: Vec::new(),
+            arms: Vec::new(),
+        };
+        for (line_no, line) in lines {
+            let line_no = line_no + 1;
+            if line.starts_with('#') {
+                continue;
+            }
+            let (key, rest) = line.split_once(' ').unwrap_or((line, ""));
+            let invalid = || format!("Invalid '{}' entry on line {}", key, line_no);
+            match key {
+                "scenario" => manifest.scenario = rest.to_string(),
+                "software" => manifest.software = rest.to_string(),
+                "dh_arm_model" => manifest.library_version = rest.to_string(),
+                "created" => manifest.created = rest.parse().map_err(|_| invalid())?,
+                "dt" => manifest.dt = rest.parse().map_err(|_| invalid())?,
+                "data" => manifest.data_files.push(rest.to_string()),
+                "arm" => {
+                    let fields: Vec<&str> = rest.split_whitespace().collect();
+                    let [index, "config", hash] = fields[..] else { return Err(invalid()) };
+                    if index.parse::<usize>().ok() != Some(manifest.arms.len()) {
+                        return Err(format!("Arm on line {} is out of order", line_no));
+                    }
+                    let config_hash = u64::from_str_radix(hash, 16).map_err(|_| invalid())?;
+                    manifest.arms.push(ArmManifest { config_hash, params: Vec::new() });
+                }
+                "param" => {
+                    let fields: Vec<&str> = rest.split_whitespace().collect();
+                    let [index, name, kind, value] = fields[..] else { return Err(invalid()) };
+                    let value = match kind {
+                        "float" => value.parse().map(ParamValue::Float).map_err(|_| invalid())?,
+                        "int" => value.parse().map(ParamValue::Int).map_err(|_| invalid())?,
+                        _ => return Err(invalid()),
+                    };
+                    let arm = index
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|i| manifest.arms.get_mut(i))
+                        .ok_or_else(|| format!("Parameter on line {} belongs to no listed arm", line_no))?;
+                    arm.params.push((name.to_string(), value));
+                }
+                other => return Err(format!("Unknown entry '{}' on line {}", other, line_no)),
+            }
+        }
+        Ok(manifest)
+    }
+}
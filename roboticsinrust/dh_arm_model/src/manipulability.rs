@@ -0,0 +1,79 @@
+use crate::dh_arm_model::DHArmModel;
+use crate::inverse_kinematics_solvers::IkSolver;
+
+use nalgebra::{Matrix3, SMatrix, Vector3};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// Velocity manipulability ellipsoid: the end-effector velocities reachable with
+/// joint velocities of unit norm, `{ J q̇ : |q̇| ≤ 1 }` for the linear or angular
+/// rows of the Jacobian.
+///
+/// Its principal axes are the eigenvectors of `J Jᵀ` and its radii the square
+/// roots of the eigenvalues. A radius shrinking towards zero is a direction the
+/// end-effector can barely move in: the arm is approaching a singularity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ManipulabilityEllipsoid {
+    /// Unit principal axes as columns (base frame), longest first.
+    pub axes: Matrix3<f64>,
+    /// Semi-axis lengths matching `axes`: linear units/s (or rad/s for the angular
+    /// ellipsoid) per rad/s of joint speed.
+    pub radii: Vector3<f64>,
+}
+
+impl ManipulabilityEllipsoid {
+    /// Ellipsoid of the three rows of `jacobian` starting at `first_row`
+    /// (0 for linear velocity, 3 for angular).
+    fn from_rows<const J: usize>(jacobian: &SMatrix<f64, 6, J>, first_row: usize) -> Self {
+        let rows = jacobian.fixed_rows::<3>(first_row);
+        let eigen = (rows * rows.transpose()).symmetric_eigen();
+
+        let mut order = [0, 1, 2];
+        order.sort_by(|&a, &b| eigen.eigenvalues[b].total_cmp(&eigen.eigenvalues[a]));
+        let axes = Matrix3::from_fn(|r, c| eigen.eigenvectors[(r, order[c])]);
+        // Round-off can leave the eigenvalue of a lost direction slightly negative
+        let radii = Vector3::from_fn(|i, _| eigen.eigenvalues[order[i]].max(0.0).sqrt());
+        Self { axes, radii }
+    }
+
+    /// Ellipsoid of end-effector linear velocities.
+    pub fn linear<const J: usize>(jacobian: &SMatrix<f64, 6, J>) -> Self {
+        Self::from_rows(jacobian, 0)
+    }
+
+    /// Ellipsoid of end-effector angular velocities.
+    pub fn angular<const J: usize>(jacobian: &SMatrix<f64, 6, J>) -> Self {
+        Self::from_rows(jacobian, 3)
+    }
+
+    /// Yoshikawa's manipulability measure: the ellipsoid's volume up to a constant,
+    /// zero at a singularity of these rows.
+    pub fn measure(&self) -> f64 {
+        self.radii.product()
+    }
+
+    /// Shortest over longest radius: 1 when the end-effector moves equally well
+    /// in every direction, 0 at a singularity.
+    pub fn isotropy(&self) -> f64 {
+        if self.radii[0] > 0.0 { self.radii[2] / self.radii[0] } else { 0.0 }
+    }
+
+    /// Direction the end-effector can move in least easily (unit vector, base frame).
+    pub fn weakest_direction(&self) -> Vector3<f64> {
+        self.axes.column(2).into()
+    }
+}
+
+impl<const F: usize, const J: usize, S: IkSolver<J>> DHArmModel<F, J, S> {
+    /// Linear velocity manipulability ellipsoid at the current joint positions.
+    pub fn manipulability_ellipsoid(&mut self) -> ManipulabilityEllipsoid {
+        ManipulabilityEllipsoid::linear(self.jacobian())
+    }
+
+    /// How much each joint moves the end-effector: the norm of its Jacobian column
+    /// (linear and angular rows together) at the current joint positions.
+    pub fn joint_contributions(&mut self) -> [f64; J] {
+        let jacobian = self.jacobian();
+        core::array::from_fn(|i| jacobian.column(i).norm())
+    }
+}
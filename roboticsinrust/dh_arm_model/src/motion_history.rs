@@ -0,0 +1,223 @@
+use std::collections::VecDeque;
+
+use crate::dh_arm_model::DHArmModel;
+use crate::inverse_kinematics_solvers::IkSolver;
+use crate::task_space_limits::WorkspaceBox;
+use crate::units::JointAngles;
+
+/// Largest joint step (deg / linear units) between the configurations a planned move
+/// is checked at.
+const CHECK_STEP: f64 = 1.0;
+
+/// One executed operation (a jog, a nudge, ...) and the configurations around it
+/// (deg / linear units).
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry<const J: usize> {
+    pub label: String,
+    pub before: [f64; J],
+    pub after: [f64; J],
+}
+
+/// Undo/redo history of the operations used to teach an arm, for recovering from
+/// mis-jogs while programming.
+///
+/// Keeps the last `capacity` operations. Undoing plans a joint-space move back
+/// through the configurations the arm had before each of them, most recent first,
+/// rather than jumping straight to the oldest one; redoing replays them forwards.
+/// Recording a new operation discards whatever could have been redone.
+#[derive(Debug, Clone)]
+pub struct MotionHistory<const J: usize> {
+    capacity: usize,
+    undo: VecDeque<HistoryEntry<J>>,
+    redo: Vec<HistoryEntry<J>>,
+}
+
+impl<const J: usize> MotionHistory<J> {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, undo: VecDeque::new(), redo: Vec::new() }
+    }
+
+    /// Records an operation that took the arm from `before` to `after`. Operations
+    /// that did not move the arm are dropped.
+    pub fn record(&mut self, label: &str, before: [f64; J], after: [f64; J]) {
+        if before == after || self.capacity == 0 {
+            return;
+        }
+        self.redo.clear();
+        if self.undo.len() == self.capacity {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(HistoryEntry { label: label.to_string(), before, after });
+    }
+
+    /// Operations that can be undone, oldest first.
+    pub fn undo_entries(&self) -> impl Iterator<Item = &HistoryEntry<J>> {
+        self.undo.iter()
+    }
+
+    /// Operations that can be redone, next one first.
+    pub fn redo_entries(&self) -> impl Iterator<Item = &HistoryEntry<J>> {
+        self.redo.iter().rev()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Plans undoing the last `steps` operations from `joint_pos`, moving back through
+    /// the configuration before each at up to `joint_speed` (deg/s or linear units/s).
+    ///
+    /// The path is checked before anything changes: it must respect the joint limits
+    /// and, if given, keep the end-effector inside `workspace`. On success the
+    /// operations move to the redo list; on failure the history is left as it was.
+    pub fn plan_undo<const F: usize, S: IkSolver<J>>(
+        &mut self,
+        steps: usize,
+        arm: &mut DHArmModel<F, J, S>,
+        joint_pos: &[f64; J],
+        joint_speed: f64,
+        workspace: Option<&WorkspaceBox>,
+    ) -> Result<JointSpaceMove<J>, String> {
+        if steps == 0 || steps > self.undo.len() {
+            return Err(format!("Cannot undo {} operations, {} recorded", steps, self.undo.len()));
+        }
+        let waypoints: Vec<[f64; J]> = self.undo.iter().rev().take(steps).map(|e| e.before).collect();
+        let path = JointSpaceMove::new(*joint_pos, waypoints, joint_speed);
+        path.check(arm, workspace)?;
+
+        for _ in 0..steps {
+            self.redo.extend(self.undo.pop_back());
+        }
+        Ok(path)
+    }
+
+    /// Plans redoing the next `steps` undone operations, the reverse of [`MotionHistory::plan_undo`].
+    pub fn plan_redo<const F: usize, S: IkSolver<J>>(
+        &mut self,
+        steps: usize,
+        arm: &mut DHArmModel<F, J, S>,
+        joint_pos: &[f64; J],
+        joint_speed: f64,
+        workspace: Option<&WorkspaceBox>,
+    ) -> Result<JointSpaceMove<J>, String> {
+        if steps == 0 || steps > self.redo.len() {
+            return Err(format!("Cannot redo {} operations, {} undone", steps, self.redo.len()));
+        }
+        let waypoints: Vec<[f64; J]> = self.redo.iter().rev().take(steps).map(|e| e.after).collect();
+        let path = JointSpaceMove::new(*joint_pos, waypoints, joint_speed);
+        path.check(arm, workspace)?;
+
+        for _ in 0..steps {
+            self.undo.extend(self.redo.pop());
+        }
+        Ok(path)
+    }
+}
+
+/// Move through a list of joint configurations (deg / linear units), each segment a
+/// straight line in joint space along which all joints arrive together, with the
+/// fastest joint at `joint_speed`.
+///
+/// Call [`JointSpaceMove::step`] once per control cycle; it returns joint velocity
+/// commands in the same units as the task-space controller.
+#[derive(Debug, Clone)]
+pub struct JointSpaceMove<const J: usize> {
+    start: [f64; J],
+    waypoints: Vec<[f64; J]>,
+    joint_speed: f64,
+    /// Waypoint being approached, and how far into the segment towards it (s).
+    segment: usize,
+    elapsed: f64,
+}
+
+impl<const J: usize> JointSpaceMove<J> {
+    pub fn new(start: [f64; J], waypoints: Vec<[f64; J]>, joint_speed: f64) -> Self {
+        Self { start, waypoints, joint_speed, segment: 0, elapsed: 0.0 }
+    }
+
+    pub fn waypoints(&self) -> &[[f64; J]] {
+        &self.waypoints
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.segment >= self.waypoints.len()
+    }
+
+    /// Configuration a segment starts from.
+    fn segment_start(&self, segment: usize) -> [f64; J] {
+        if segment == 0 { self.start } else { self.waypoints[segment - 1] }
+    }
+
+    /// Time the segment takes at `joint_speed` (s).
+    fn segment_duration(&self, segment: usize) -> f64 {
+        let from = self.segment_start(segment);
+        let largest_move = self.waypoints[segment].iter().zip(from).map(|(to, q)| (to - q).abs()).fold(0.0, f64::max);
+        largest_move / self.joint_speed
+    }
+
+    /// Checks every segment at joint steps of at most [`CHECK_STEP`] against the arm's
+    /// joint limits and, if given, the end-effector `workspace` (base frame). Leaves
+    /// `arm` at whichever configuration was checked last.
+    pub fn check<const F: usize, S: IkSolver<J>>(
+        &self,
+        arm: &mut DHArmModel<F, J, S>,
+        workspace: Option<&WorkspaceBox>,
+    ) -> Result<(), String> {
+        for (segment, to) in self.waypoints.iter().enumerate() {
+            let from = self.segment_start(segment);
+            let largest_move = to.iter().zip(from).map(|(to, q)| (to - q).abs()).fold(0.0, f64::max);
+            let samples = (largest_move / CHECK_STEP).ceil().max(1.0) as usize;
+            for k in 0..=samples {
+                let s = k as f64 / samples as f64;
+                let q: [f64; J] = core::array::from_fn(|i| from[i] + s * (to[i] - from[i]));
+
+                let internal = JointAngles::from_user_units(&q, arm.joints()).radians();
+                for (i, (joint, value)) in arm.joints().iter().zip(internal).enumerate() {
+                    let below = joint.limit_min.is_some_and(|min| value < min);
+                    let above = joint.limit_max.is_some_and(|max| value > max);
+                    if below || above {
+                        return Err(format!("Joint {} would leave its limits on the way to waypoint {}", i, segment));
+                    }
+                }
+                if let Some(workspace) = workspace {
+                    arm.set_joint_positions(&q);
+                    if !workspace.contains(&arm.end_effector_pose().position) {
+                        return Err(format!("End-effector would leave the workspace on the way to waypoint {}", segment));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Joint velocity command for one control cycle of length `dt`, from the measured
+    /// `joint_pos`; zero once the last configuration is reached.
+    pub fn step(&mut self, joint_pos: &[f64; J], dt: f64) -> [f64; J] {
+        while !self.is_finished() {
+            let duration = self.segment_duration(self.segment);
+            let elapsed = self.elapsed + dt;
+            if elapsed < duration {
+                let (from, to) = (self.segment_start(self.segment), self.waypoints[self.segment]);
+                self.elapsed = elapsed;
+                return core::array::from_fn(|i| (to[i] - from[i]) / duration);
+            }
+            // Land exactly on the waypoint, then carry on from there next cycle
+            let to = self.waypoints[self.segment];
+            self.segment += 1;
+            self.elapsed = 0.0;
+            if duration > 0.0 {
+                return core::array::from_fn(|i| (to[i] - joint_pos[i]) / dt);
+            }
+        }
+        [0.0; J]
+    }
+}
@@ -0,0 +1,320 @@
+use std::fmt;
+
+use crate::dh_arm_model::DHArmModel;
+use crate::inverse_kinematics_solvers::IkSolver;
+use crate::task_space_pid_controller::TaskSpacePidController;
+use crate::units::{AngleUnit, AngularVelocityUnit};
+
+/// Type and valid range of a tunable parameter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParamKind {
+    Float { min: f64, max: f64 },
+    Int { min: i64, max: i64 },
+}
+
+/// Value of a tunable parameter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParamValue {
+    Float(f64),
+    Int(i64),
+}
+
+impl ParamValue {
+    pub fn as_f64(&self) -> f64 {
+        match *self {
+            ParamValue::Float(v) => v,
+            ParamValue::Int(v) => v as f64,
+        }
+    }
+}
+
+impl fmt::Display for ParamValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamValue::Float(v) => write!(f, "{}", v),
+            ParamValue::Int(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+/// Quantity a parameter measures, for files that write a unit next to each value.
+/// Parameters hold it in the crate's user-facing unit (deg, deg/s).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamUnit {
+    Angle,
+    AngularVelocity,
+}
+
+impl ParamUnit {
+    /// Symbol of the unit parameter values are held in.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            ParamUnit::Angle => AngleUnit::Degree.symbol(),
+            ParamUnit::AngularVelocity => AngularVelocityUnit::DegreesPerSecond.symbol(),
+        }
+    }
+
+    /// Reads `value` given in the unit with symbol `symbol`, returning the quantity it
+    /// measures and the value in the unit parameters hold it in.
+    pub fn parse(value: f64, symbol: &str) -> Result<(Self, f64), String> {
+        if let Ok(unit) = symbol.parse::<AngleUnit>() {
+            return Ok((ParamUnit::Angle, unit.convert(value, AngleUnit::Degree)));
+        }
+        match symbol.parse::<AngularVelocityUnit>() {
+            Ok(unit) => Ok((ParamUnit::AngularVelocity, unit.convert(value, AngularVelocityUnit::DegreesPerSecond))),
+            Err(_) => Err(format!("Unknown unit '{}' (expected deg, rad, deg/s, rad/s or rpm)", symbol)),
+        }
+    }
+}
+
+impl fmt::Display for ParamUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamUnit::Angle => write!(f, "angle"),
+            ParamUnit::AngularVelocity => write!(f, "angular velocity"),
+        }
+    }
+}
+
+/// Name, type and range of a tunable parameter.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamSpec {
+    /// Dotted name, e.g. `kp.x` or `limits.max_linear_speed`.
+    pub name: String,
+    pub kind: ParamKind,
+    pub description: &'static str,
+    /// Quantity the value measures, if it has a unit that files may give it in.
+    pub unit: Option<ParamUnit>,
+}
+
+impl ParamSpec {
+    pub fn float(name: impl Into<String>, min: f64, max: f64, description: &'static str) -> Self {
+        Self { name: name.into(), kind: ParamKind::Float { min, max }, description, unit: None }
+    }
+
+    pub fn int(name: impl Into<String>, min: i64, max: i64, description: &'static str) -> Self {
+        Self { name: name.into(), kind: ParamKind::Int { min, max }, description, unit: None }
+    }
+
+    pub fn with_unit(mut self, unit: ParamUnit) -> Self {
+        self.unit = Some(unit);
+        self
+    }
+
+    /// Checks that `value` has this parameter's type and lies in its range. Integers
+    /// are accepted for float parameters.
+    pub fn check(&self, value: ParamValue) -> Result<ParamValue, String> {
+        let value = match (self.kind, value) {
+            (ParamKind::Float { .. }, ParamValue::Int(v)) => ParamValue::Float(v as f64),
+            (ParamKind::Int { .. }, ParamValue::Float(_)) => {
+                return Err(format!("Parameter '{}' takes an integer, got {}", self.name, value));
+            }
+            _ => value,
+        };
+        let in_range = match (self.kind, value) {
+            (ParamKind::Float { min, max }, ParamValue::Float(v)) => v >= min && v <= max,
+            (ParamKind::Int { min, max }, ParamValue::Int(v)) => (min..=max).contains(&v),
+            _ => false,
+        };
+        if in_range {
+            Ok(value)
+        } else {
+            Err(format!("Parameter '{}' must lie in {}, got {}", self.name, self.range(), value))
+        }
+    }
+
+    /// Parses a value of this parameter's type from text (`inf` for an unbounded float).
+    pub fn parse(&self, text: &str) -> Result<ParamValue, String> {
+        let value = match self.kind {
+            ParamKind::Float { .. } => text.parse::<f64>().map(ParamValue::Float).map_err(|e| e.to_string()),
+            ParamKind::Int { .. } => text.parse::<i64>().map(ParamValue::Int).map_err(|e| e.to_string()),
+        }
+        .map_err(|e| format!("Invalid value '{}' for parameter '{}': {}", text, self.name, e))?;
+        self.check(value)
+    }
+
+    fn range(&self) -> String {
+        match self.kind {
+            ParamKind::Float { min, max } => format!("[{}, {}]", min, max),
+            ParamKind::Int { min, max } => format!("[{}, {}]", min, max),
+        }
+    }
+}
+
+impl fmt::Display for ParamSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let type_name = match self.kind {
+            ParamKind::Float { .. } => "float",
+            ParamKind::Int { .. } => "int",
+        };
+        write!(f, "{} {} {} - {}", self.name, type_name, self.range(), self.description)
+    }
+}
+
+/// A parameter's spec together with its current value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub spec: ParamSpec,
+    pub value: ParamValue,
+}
+
+impl fmt::Display for Param {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} = {}", self.spec, self.value)
+    }
+}
+
+/// Something whose settings can be listed, read and changed by name while it runs,
+/// e.g. from a tuning GUI over a remote interface.
+///
+/// `set_param` rejects unknown names and values of the wrong type or out of range,
+/// leaving the parameter unchanged.
+pub trait Tunable {
+    fn param_specs(&self) -> Vec<ParamSpec>;
+    fn get_param(&self, name: &str) -> Result<ParamValue, String>;
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<(), String>;
+
+    /// Every parameter with its current value.
+    fn params(&self) -> Vec<Param> {
+        self.param_specs()
+            .into_iter()
+            .filter_map(|spec| self.get_param(&spec.name).ok().map(|value| Param { spec, value }))
+            .collect()
+    }
+
+    fn param_spec(&self, name: &str) -> Result<ParamSpec, String> {
+        self.param_specs()
+            .into_iter()
+            .find(|spec| spec.name == name)
+            .ok_or_else(|| format!("Unknown parameter '{}'", name))
+    }
+}
+
+/// Task-space axes in the order of the controller's gain vectors.
+const AXES: [&str; 6] = ["x", "y", "z", "roll", "pitch", "yaw"];
+/// Upper bound on each PID gain.
+const MAX_GAIN: f64 = 1000.0;
+
+/// Gain vector (0 = kp, 1 = ki, 2 = kd) and axis named by e.g. `kp.x`.
+fn gain_index(name: &str) -> Option<(usize, usize)> {
+    let (gain, axis) = name.split_once('.')?;
+    let gain = ["kp", "ki", "kd"].iter().position(|g| *g == gain)?;
+    let axis = AXES.iter().position(|a| *a == axis)?;
+    Some((gain, axis))
+}
+
+/// Upper bound on the re-orthonormalization interval (cycles).
+const MAX_ORTHONORM_INTERVAL: i64 = 10_000;
+
+/// PID gains `kp|ki|kd.<x|y|z|roll|pitch|yaw>`, the speed limits
+/// `limits.max_linear_speed` / `limits.max_angular_speed`, where `inf` lifts the limit,
+/// and `orthonorm_interval`.
+impl Tunable for TaskSpacePidController {
+    fn param_specs(&self) -> Vec<ParamSpec> {
+        let mut specs = Vec::new();
+        for (gain, description) in [
+            ("kp", "proportional gain"),
+            ("ki", "integral gain"),
+            ("kd", "derivative gain"),
+        ] {
+            for axis in AXES {
+                specs.push(ParamSpec::float(format!("{}.{}", gain, axis), 0.0, MAX_GAIN, description));
+            }
+        }
+        specs.push(ParamSpec::float(
+            "limits.max_linear_speed",
+            0.0,
+            f64::INFINITY,
+            "maximum end-effector linear speed (linear units/s)",
+        ));
+        specs.push(ParamSpec::float(
+            "limits.max_angular_speed",
+            0.0,
+            f64::INFINITY,
+            "maximum end-effector angular speed (deg/s)",
+        ).with_unit(ParamUnit::AngularVelocity));
+        specs.push(ParamSpec::int(
+            "orthonorm_interval",
+            1,
+            MAX_ORTHONORM_INTERVAL,
+            "cycles between re-orthonormalizations of the reference orientation",
+        ));
+        specs
+    }
+
+    fn get_param(&self, name: &str) -> Result<ParamValue, String> {
+        let value = match name {
+            "orthonorm_interval" => return Ok(ParamValue::Int(self.orthonorm_interval() as i64)),
+            "limits.max_linear_speed" => self.limits.max_linear_speed.unwrap_or(f64::INFINITY),
+            "limits.max_angular_speed" => self.limits.max_angular_speed.unwrap_or(f64::INFINITY),
+            _ => match gain_index(name) {
+                Some((gain, axis)) => [&self.kp, &self.ki, &self.kd][gain][axis],
+                None => return Err(format!("Unknown parameter '{}'", name)),
+            },
+        };
+        Ok(ParamValue::Float(value))
+    }
+
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<(), String> {
+        let value = self.param_spec(name)?.check(value)?;
+        if let ParamValue::Int(interval) = value {
+            self.set_orthonorm_interval(interval as usize);
+            return Ok(());
+        }
+        let value = value.as_f64();
+        let limit = value.is_finite().then_some(value);
+        match name {
+            "limits.max_linear_speed" => self.limits.max_linear_speed = limit,
+            "limits.max_angular_speed" => self.limits.max_angular_speed = limit,
+            _ => {
+                let (gain, axis) = gain_index(name).ok_or_else(|| format!("Unknown parameter '{}'", name))?;
+                [&mut self.kp, &mut self.ki, &mut self.kd][gain][axis] = value;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `damping`: the pseudo-inverse's damping factor λ.
+impl<const F: usize, const J: usize, S: IkSolver<J>> Tunable for DHArmModel<F, J, S> {
+    fn param_specs(&self) -> Vec<ParamSpec> {
+        vec![ParamSpec::float("damping", 0.0, 1.0, "pseudo-inverse damping factor (lambda)")]
+    }
+
+    fn get_param(&self, name: &str) -> Result<ParamValue, String> {
+        match name {
+            "damping" => Ok(ParamValue::Float(self.damping())),
+            _ => Err(format!("Unknown parameter '{}'", name)),
+        }
+    }
+
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<(), String> {
+        let value = self.param_spec(name)?.check(value)?;
+        self.set_damping(value.as_f64());
+        Ok(())
+    }
+}
+
+/// The parameters of `A` followed by those of `B`, e.g. an arm model and its
+/// controller tuned together. Names are looked up in `A` first.
+impl<A: Tunable, B: Tunable> Tunable for (A, B) {
+    fn param_specs(&self) -> Vec<ParamSpec> {
+        let mut specs = self.0.param_specs();
+        specs.extend(self.1.param_specs());
+        specs
+    }
+
+    fn get_param(&self, name: &str) -> Result<ParamValue, String> {
+        match self.0.param_spec(name) {
+            Ok(_) => self.0.get_param(name),
+            Err(_) => self.1.get_param(name),
+        }
+    }
+
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<(), String> {
+        match self.0.param_spec(name) {
+            Ok(_) => self.0.set_param(name, value),
+            Err(_) => self.1.set_param(name, value),
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use core::fmt;
+
+use crate::dh::Pose;
+use crate::dh_arm_model::DHArmModel;
+use crate::inverse_kinematics_solvers::{IkError, IkSolver, IkTolerance};
+use crate::joint::JointType;
+use crate::units::JointAngles;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use nalgebra::{Rotation3, UnitQuaternion};
+
+/// How far consecutive solutions of a Cartesian path may be apart in joint space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathIkLimits<const J: usize> {
+    /// Largest change of each joint between consecutive samples (deg / linear units).
+    pub max_joint_step: [f64; J],
+    /// A revolute joint jumping by more than this between samples (deg) means the
+    /// solver switched branches, e.g. the wrist flipping through its singularity.
+    pub branch_flip: f64,
+    /// Every solution must reproduce its sample this closely under FK.
+    pub tolerance: IkTolerance,
+}
+
+impl<const J: usize> PathIkLimits<J> {
+    /// The same step limit for every joint, flips above 90°.
+    pub fn uniform(max_joint_step: f64) -> Self {
+        Self { max_joint_step: [max_joint_step; J], branch_flip: 90.0, tolerance: IkTolerance::default() }
+    }
+}
+
+/// Why a sample of a Cartesian path could not be followed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathIkFailure {
+    /// No IK solution reproduces the sample.
+    Ik(IkError),
+    /// The solution is on a different branch than the previous sample's: `joint`
+    /// would jump by `delta` (deg).
+    BranchFlip { joint: usize, delta: f64 },
+    /// `joint` would move by `delta`, more than its `limit` per sample (deg / linear units).
+    StepTooLarge { joint: usize, delta: f64, limit: f64 },
+}
+
+impl fmt::Display for PathIkFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathIkFailure::Ik(e) => write!(f, "{}", e),
+            PathIkFailure::BranchFlip { joint, delta } => write!(
+                f, "IK switches branch: joint {} would jump by {:.1} deg", joint + 1, delta
+            ),
+            PathIkFailure::StepTooLarge { joint, delta, limit } => write!(
+                f, "Joint {} would move {:.3} in one sample, limit is {:.3}", joint + 1, delta, limit
+            ),
+        }
+    }
+}
+
+/// First sample of a path that cannot be followed, with the solutions before it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathIkError<const J: usize> {
+    pub sample: usize,
+    pub failure: PathIkFailure,
+    /// Solutions of samples `0..sample`, which can still be executed.
+    pub solved: Vec<JointAngles<J>>,
+}
+
+impl<const J: usize> fmt::Display for PathIkError<J> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Path sample {} is infeasible: {}", self.sample, self.failure)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<const J: usize> std::error::Error for PathIkError<J> {}
+
+/// Samples the straight line from `from` to `to` (a MoveL): positions interpolated
+/// linearly, orientations by slerp, with consecutive samples at most `max_linear_step`
+/// (linear units) and `max_angular_step` (rad) apart. Includes both ends.
+pub fn sample_line(from: &Pose, to: &Pose, max_linear_step: f64, max_angular_step: f64) -> Vec<Pose> {
+    let distance = (to.position - from.position).norm();
+    let angle = from.rotation_angle_to(to);
+    let steps = ((distance / max_linear_step).ceil().max((angle / max_angular_step).ceil()) as usize).max(1);
+
+    let q_from = UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix_unchecked(from.rotation));
+    let q_to = UnitQuaternion::from_rotation_matrix(&Rotation3::from_matrix_unchecked(to.rotation));
+    (0..=steps)
+        .map(|k| {
+            let s = k as f64 / steps as f64;
+            let rotation = q_from.slerp(&q_to, s).to_rotation_matrix().into_inner();
+            Pose::new(from.position + (to.position - from.position) * s, rotation)
+        })
+        .collect()
+}
+
+impl<const F: usize, const J: usize, S: IkSolver<J>> DHArmModel<F, J, S> {
+    /// Solves IK for every sample of a dense Cartesian path, starting from the
+    /// configuration `start`, so that the joints move continuously along it.
+    ///
+    /// Each sample is solved seeded with the previous solution (see
+    /// [`IkSolver::solve_ik_seeded`]) and FK-checked; a solution that flips branch or
+    /// moves a joint further than `limits` allows is rejected rather than executed.
+    /// Returns the solution of every sample, or the first infeasible sample.
+    pub fn solve_path_ik(
+        &self,
+        path: &[Pose],
+        start: &JointAngles<J>,
+        limits: &PathIkLimits<J>,
+    ) -> Result<Vec<JointAngles<J>>, PathIkError<J>> {
+        let joints = self.joints();
+        let mut solved: Vec<JointAngles<J>> = Vec::with_capacity(path.len());
+        let mut previous = *start;
+
+        for (sample, pose) in path.iter().enumerate() {
+            let result = self
+                .solve_ik_seeded(pose, &previous.radians())
+                .and_then(|q| self.verify_ik_solution(&q, pose, &limits.tolerance).map(|_| q))
+                .map_err(PathIkFailure::Ik)
+                .and_then(|q| {
+                    let q = JointAngles::from_radians(q);
+                    let (from, to) = (previous.to_user_units(joints), q.to_user_units(joints));
+                    for (joint, (a, b)) in from.iter().zip(to).enumerate() {
+                        let delta = (b - a).abs();
+                        if joints[joint].joint_type == JointType::Revolute && delta > limits.branch_flip {
+                            return Err(PathIkFailure::BranchFlip { joint, delta });
+                        }
+                        let limit = limits.max_joint_step[joint];
+                        if delta > limit {
+                            return Err(PathIkFailure::StepTooLarge { joint, delta, limit });
+                        }
+                    }
+                    Ok(q)
+                });
+
+            match result {
+                Ok(q) => {
+                    solved.push(q);
+                    previous = q;
+                }
+                Err(failure) => return Err(PathIkError { sample, failure, solved }),
+            }
+        }
+        Ok(solved)
+    }
+}
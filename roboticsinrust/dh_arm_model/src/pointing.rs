@@ -0,0 +1,140 @@
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use nalgebra::{Matrix3, SMatrix, SVector, Vector2, Vector3};
+
+use crate::dh::Pose;
+use crate::dh_arm_model::DHArmModel;
+use crate::inverse_kinematics_solvers::IkSolver;
+use crate::task_priority::TaskPriority;
+
+/// Keeps the tool's Z axis pointing at a target point, e.g. a wrist camera looking at
+/// the part it inspects, as a task for [`TaskPriority`].
+///
+/// Only the two rotations that tilt the line of sight are constrained; the roll
+/// about it and the tool's position stay free for lower-priority tasks. The task
+/// is the direction to the target expressed along the tool's X and Y axes, which is
+/// zero when the target lies straight ahead on Z. Its Jacobian accounts for the
+/// direction changing as the tool moves, so the target stays centred while the tool
+/// translates.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointingTask {
+    /// Point to look at, in the arm's base frame (linear units).
+    pub target: Vector3<f64>,
+    /// Rate (1/s) at which the pointing error decays.
+    pub gain: f64,
+}
+
+impl PointingTask {
+    pub fn new(target: Vector3<f64>, gain: f64) -> Self {
+        Self { target, gain }
+    }
+
+    /// Angle (rad) between the tool's Z axis and the direction from the tool to the
+    /// target; zero if the tool is at the target.
+    pub fn error_angle(&self, tool: &Pose) -> f64 {
+        let to_target = self.target - tool.position;
+        let z = tool.z_axis();
+        z.cross(&to_target).norm().atan2(z.dot(&to_target))
+    }
+
+    /// Task Jacobian (2 x J) and desired rate for `tool` (the end-effector pose) and
+    /// the arm's `jacobian` there, both in the base frame.
+    pub fn task<const J: usize>(&self, tool: &Pose, jacobian: &SMatrix<f64, 6, J>) -> (SMatrix<f64, 2, J>, SVector<f64, 2>) {
+        let to_target = self.target - tool.position;
+        let distance = to_target.norm();
+        if distance < f64::EPSILON {
+            return (SMatrix::zeros(), SVector::zeros());
+        }
+        let d = to_target / distance;
+        let (x, y) = (tool.x_axis(), tool.y_axis());
+        let j_v = jacobian.fixed_rows::<3>(0);
+        let j_w = jacobian.fixed_rows::<3>(3);
+
+        // s = [x·d, y·d]; ṡ_x = (x × d)·ω + x·ḋ, with ḋ = -(I - ddᵀ)ṗ / distance
+        let across = (Matrix3::identity() - d * d.transpose()) / distance;
+        let mut task = SMatrix::<f64, 2, J>::zeros();
+        for (row, axis) in [x, y].iter().enumerate() {
+            let r = axis.cross(&d).transpose() * j_w - (axis.transpose() * across) * j_v;
+            task.row_mut(row).copy_from(&r);
+        }
+
+        let s = Vector2::new(x.dot(&d), y.dot(&d));
+        let rate = if tool.z_axis().dot(&d) >= 0.0 {
+            -self.gain * s
+        } else {
+            // Facing away, |s| = sin of the error grows towards 90° before it shrinks;
+            // push it up at full rate (any direction will do if exactly opposite)
+            let direction = s.try_normalize(f64::EPSILON).unwrap_or(Vector2::x());
+            self.gain * direction
+        };
+        (task, rate)
+    }
+}
+
+/// What a [`GazeController`] does with the tool's position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GazePosition {
+    /// Left to the minimum joint motion that points the tool, which generally moves
+    /// it as well.
+    Free,
+    /// The tool is held at this point (base frame). Turning the tool about a fixed
+    /// point takes a dexterous wrist: on the URT arm even small re-aims this way pass
+    /// close to singularities, so prefer `Free` or `Velocity` there.
+    Hold(Vector3<f64>),
+    /// The tool moves at this velocity (base frame, linear units/s), e.g. jogged by
+    /// an operator while the camera keeps the target in view.
+    Velocity(Vector3<f64>),
+}
+
+/// Points the tool at a target while holding, moving or freeing its position, for
+/// inspection motions with a camera on the wrist.
+///
+/// Pointing has the higher priority: near a singularity, or when the position asked
+/// for cannot be reached while looking at the target, the position gives way.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GazeController {
+    pub pointing: PointingTask,
+    pub position: GazePosition,
+    /// Rate (1/s) at which errors from a held position decay.
+    pub position_gain: f64,
+    /// Damping of the task pseudo-inverses (see [`TaskPriority`]).
+    pub damping: f64,
+}
+
+impl GazeController {
+    /// Looks at `target` (base frame), position free.
+    pub fn new(target: Vector3<f64>, gain: f64) -> Self {
+        Self { pointing: PointingTask::new(target, gain), position: GazePosition::Free, position_gain: gain, damping: 1e-3 }
+    }
+
+    /// Joint velocity commands (deg/s / linear units/s) for the measured joint positions
+    /// (deg / linear units), the same convention as `TaskSpacePidController::compute`.
+    pub fn compute<const F: usize, const J: usize, S: IkSolver<J>>(
+        &self,
+        arm: &mut DHArmModel<F, J, S>,
+        joint_pos: &[f64; J],
+    ) -> [f64; J] {
+        arm.set_joint_positions(joint_pos);
+        let tool = arm.end_effector_pose();
+        let jacobian = *arm.jacobian();
+
+        let mut tasks = TaskPriority::new(self.damping);
+        let (pointing, rate) = self.pointing.task(&tool, &jacobian);
+        tasks.add_task(&pointing, &rate);
+
+        let linear = jacobian.fixed_rows::<3>(0).into_owned();
+        match self.position {
+            GazePosition::Free => {}
+            GazePosition::Hold(point) => {
+                tasks.add_task(&linear, &((point - tool.position) * self.position_gain));
+            }
+            GazePosition::Velocity(velocity) => {
+                tasks.add_task(&linear, &velocity);
+            }
+        }
+
+        let qd = tasks.joint_velocity();
+        core::array::from_fn(|i| arm.joints()[i].joint_type.to_user_units(qd[i]))
+    }
+}
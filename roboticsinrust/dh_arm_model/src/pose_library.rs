@@ -0,0 +1,150 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::pose_snapshot::PoseSnapshot;
+use crate::thumbnail::{self, ThumbnailStyle};
+use crate::units::AngleUnit;
+
+/// A directory of named, saved arm configurations.
+///
+/// Each entry is stored as `<name>.pose` in the [`PoseSnapshot`] text format,
+/// with a rendered `<name>.svg` preview next to it so operators can identify
+/// entries without executing them.
+pub struct PoseLibrary {
+    dir: PathBuf,
+    thumbnail_style: ThumbnailStyle,
+}
+
+/// A single saved configuration as returned by [`PoseLibrary::list`].
+#[derive(Debug, Clone)]
+pub struct PoseLibraryEntry {
+    pub name: String,
+    pub path: PathBuf,
+    /// Preview image, `None` if it could not be rendered.
+    pub thumbnail: Option<PathBuf>,
+    /// Joint positions of the entry (internal units: rad / linear units).
+    pub joint_positions: Vec<f64>,
+}
+
+impl PoseLibrary {
+    const POSE_EXT: &'static str = "pose";
+    const THUMBNAIL_EXT: &'static str = "svg";
+
+    /// Opens (and creates if needed) a library rooted at `dir`.
+    pub fn open<P: AsRef<Path>>(dir: P) -> Result<Self, String> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create pose library {}: {}", dir.display(), e))?;
+        Ok(Self { dir, thumbnail_style: ThumbnailStyle::default() })
+    }
+
+    pub fn set_thumbnail_style(&mut self, style: ThumbnailStyle) {
+        self.thumbnail_style = style;
+    }
+
+    /// Path of entry `name`'s file with extension `ext`. Names that are empty or hold
+    /// a path separator are rejected, so no entry reaches outside the library.
+    fn entry_path(&self, name: &str, ext: &str) -> Result<PathBuf, String> {
+        if name.is_empty() || name.contains(['/', '\\']) {
+            return Err(format!("Invalid pose name '{}'", name));
+        }
+        Ok(self.dir.join(format!("{}.{}", name, ext)))
+    }
+
+    /// Saves `snapshot` under `name`, replacing any existing entry, and renders its preview.
+    pub fn save(&self, name: &str, snapshot: &PoseSnapshot) -> Result<(), String> {
+        let path = self.entry_path(name, Self::POSE_EXT)?;
+        snapshot.save(&path)?;
+        self.write_thumbnail(name, snapshot)?;
+        Ok(())
+    }
+
+    pub fn load(&self, name: &str) -> Result<PoseSnapshot, String> {
+        PoseSnapshot::load(self.entry_path(name, Self::POSE_EXT)?)
+    }
+
+    pub fn remove(&self, name: &str) -> Result<(), String> {
+        let path = self.entry_path(name, Self::POSE_EXT)?;
+        fs::remove_file(&path)
+            .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        // The preview is only a cache; ignore it being absent
+        let _ = fs::remove_file(self.entry_path(name, Self::THUMBNAIL_EXT)?);
+        Ok(())
+    }
+
+    /// Lists all entries sorted by name, (re)rendering previews that are missing
+    /// or older than their pose file.
+    pub fn list(&self) -> Result<Vec<PoseLibraryEntry>, String> {
+        let read_dir = fs::read_dir(&self.dir)
+            .map_err(|e| format!("Failed to read pose library {}: {}", self.dir.display(), e))?;
+
+        let mut entries = Vec::new();
+        for item in read_dir.flatten() {
+            let path = item.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(Self::POSE_EXT) {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|s| s.to_str()).map(str::to_string) else {
+                continue;
+            };
+
+            let snapshot = match PoseSnapshot::load(&path) {
+                Ok(s) => s,
+                Err(e) => {
+                    eprintln!("Warning: skipping unreadable pose '{}': {}", name, e);
+                    continue;
+                }
+            };
+
+            let thumbnail_path = self.entry_path(&name, Self::THUMBNAIL_EXT)?;
+            let thumbnail = if is_stale(&thumbnail_path, &path) {
+                self.write_thumbnail(&name, &snapshot).ok()
+            } else {
+                Some(thumbnail_path)
+            };
+
+            entries.push(PoseLibraryEntry {
+                name,
+                path,
+                thumbnail,
+                joint_positions: snapshot.joint_positions,
+            });
+        }
+
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(entries)
+    }
+
+    /// Prints the listing with joint angles in degrees and preview locations.
+    pub fn print_listing(&self) -> Result<(), String> {
+        println!("============= POSE LIBRARY =============");
+        for entry in self.list()? {
+            let q: Vec<String> = entry.joint_positions.iter()
+                .map(|v| format!("{:.1}", AngleUnit::Degree.from_radians(*v)))
+                .collect();
+            let preview = entry.thumbnail
+                .map(|p| p.display().to_string())
+                .unwrap_or_else(|| "<no preview>".into());
+            println!("{:<20} [{}] deg | preview: {}", entry.name, q.join(", "), preview);
+        }
+        println!("========================================");
+        Ok(())
+    }
+
+    fn write_thumbnail(&self, name: &str, snapshot: &PoseSnapshot) -> Result<PathBuf, String> {
+        let path = self.entry_path(name, Self::THUMBNAIL_EXT)?;
+        let svg = thumbnail::render_svg(&snapshot.poses, &self.thumbnail_style);
+        fs::write(&path, svg)
+            .map_err(|e| format!("Failed to write preview {}: {}", path.display(), e))?;
+        Ok(path)
+    }
+}
+
+/// True if `derived` is missing or older than `source`.
+fn is_stale(derived: &Path, source: &Path) -> bool {
+    let modified = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+    match (modified(derived), modified(source)) {
+        (Some(d), Some(s)) => d < s,
+        _ => true,
+    }
+}
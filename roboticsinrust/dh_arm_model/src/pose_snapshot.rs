@@ -0,0 +1,192 @@
+use std::fs;
+use std::path::Path;
+
+use crate::dh::{DHTable, Pose};
+use crate::joint::Joint;
+
+use nalgebra::{Matrix3, Vector3};
+
+/// A "golden" record of every frame pose for one joint configuration.
+///
+/// Snapshots are captured from a known-good build of the kinematics, written
+/// to disk, and later diffed against a fresh capture so that refactors of the
+/// DH / FK code can be verified numerically rather than by eye.
+#[derive(Debug, Clone)]
+pub struct PoseSnapshot {
+    /// Joint positions the snapshot was captured at (internal units: rad / linear units).
+    pub joint_positions: Vec<f64>,
+    /// Pose of each frame relative to the base frame, in table order.
+    pub poses: Vec<Pose>,
+}
+
+/// Error of a single frame between two snapshots.
+#[derive(Debug, Clone)]
+pub struct FrameDiff {
+    pub frame_index: usize,
+    /// Euclidean distance between the frame origins.
+    pub position_error: f64,
+    /// Angle (radians) of the relative rotation between the two frames.
+    pub rotation_error: f64,
+}
+
+/// Result of comparing two snapshots with tolerances.
+#[derive(Debug, Clone)]
+pub struct SnapshotDiff {
+    /// Per-frame errors for every frame present in both snapshots.
+    pub frames: Vec<FrameDiff>,
+    /// Frames whose position or rotation error exceeds the tolerance.
+    pub failures: Vec<usize>,
+    /// Set if the snapshots do not describe the same chain / configuration.
+    pub structure_mismatch: Option<String>,
+}
+
+impl SnapshotDiff {
+    /// True if the structure matches and every frame is within tolerance.
+    pub fn passed(&self) -> bool {
+        self.structure_mismatch.is_none() && self.failures.is_empty()
+    }
+
+    /// Largest position error over all frames (NaN if any error is NaN).
+    pub fn max_position_error(&self) -> f64 {
+        self.frames.iter().map(|f| f.position_error).fold(0.0, max_or_nan)
+    }
+
+    /// Largest rotation error (radians) over all frames (NaN if any error is NaN).
+    pub fn max_rotation_error(&self) -> f64 {
+        self.frames.iter().map(|f| f.rotation_error).fold(0.0, max_or_nan)
+    }
+
+    pub fn print_report(&self) {
+        println!("============ SNAPSHOT DIFF ============");
+        if let Some(reason) = &self.structure_mismatch {
+            println!("Structure mismatch: {}", reason);
+        }
+        for f in &self.frames {
+            let flag = if self.failures.contains(&f.frame_index) { "FAIL" } else { "ok" };
+            println!("Frame {}: pos_err={:.3e}, rot_err={:.3e} rad [{}]",
+                f.frame_index, f.position_error, f.rotation_error, flag);
+        }
+        println!("=======================================");
+    }
+}
+
+/// Like `f64::max`, but a NaN wins instead of being skipped.
+fn max_or_nan(max: f64, value: f64) -> f64 {
+    if max.is_nan() || value.is_nan() { f64::NAN } else { max.max(value) }
+}
+
+impl PoseSnapshot {
+    const HEADER: &'static str = "# dh_arm_model pose snapshot v1";
+
+    /// Captures all frame poses of `table` for the given joint states.
+    pub fn capture<const F: usize, const J: usize>(table: &DHTable<F, J>, joints: &[Joint; J]) -> Self {
+        Self {
+            joint_positions: joints.iter().map(|j| j.position).collect(),
+            poses: table.all_poses(joints).to_vec(),
+        }
+    }
+
+    /// Writes the snapshot as a plain-text file (one frame per line).
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let mut out = String::new();
+        out.push_str(Self::HEADER);
+        out.push('\n');
+
+        let q: Vec<String> = self.joint_positions.iter().map(|v| v.to_string()).collect();
+        out.push_str(&format!("joints {}\n", q.join(" ")));
+
+        for (i, pose) in self.poses.iter().enumerate() {
+            let p = &pose.position;
+            let r = &pose.rotation;
+            out.push_str(&format!("frame {} {} {} {}", i, p.x, p.y, p.z));
+            for row in 0..3 {
+                for col in 0..3 {
+                    out.push_str(&format!(" {}", r[(row, col)]));
+                }
+            }
+            out.push('\n');
+        }
+
+        fs::write(path.as_ref(), out)
+            .map_err(|e| format!("Failed to write snapshot {}: {}", path.as_ref().display(), e))
+    }
+
+    /// Reads a snapshot previously written by [`PoseSnapshot::save`].
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let text = fs::read_to_string(path.as_ref())
+            .map_err(|e| format!("Failed to read snapshot {}: {}", path.as_ref().display(), e))?;
+
+        // Number the lines before dropping blanks so errors point at the real line.
+        let mut lines = text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+        if lines.next().map(|(_, l)| l) != Some(Self::HEADER) {
+            return Err("Snapshot file is missing the expected header".into());
+        }
+
+        let mut joint_positions = Vec::new();
+        let mut poses = Vec::new();
+
+        for (index, line) in lines {
+            let line_no = index + 1;
+            let mut fields = line.split_whitespace();
+            let parse = |s: &str| s.parse::<f64>()
+                .map_err(|_| format!("Invalid number '{}' on line {}", s, line_no));
+
+            match fields.next() {
+                Some("joints") => {
+                    joint_positions = fields.map(parse).collect::<Result<_, _>>()?;
+                }
+                Some("frame") => {
+                    let frame_index = fields.next().map(|s| s.parse::<usize>());
+                    if frame_index != Some(Ok(poses.len())) {
+                        return Err(format!("Frame on line {} should be frame {}", line_no, poses.len()));
+                    }
+                    let values: Vec<f64> = fields.map(parse).collect::<Result<_, _>>()?;
+                    if values.len() != 12 {
+                        return Err(format!("Frame on line {} needs 12 values, found {}", line_no, values.len()));
+                    }
+                    let position = Vector3::new(values[0], values[1], values[2]);
+                    let rotation = Matrix3::from_row_slice(&values[3..12]);
+                    poses.push(Pose::new(position, rotation));
+                }
+                Some(other) => return Err(format!("Unknown record '{}' on line {}", other, line_no)),
+                None => {}
+            }
+        }
+
+        Ok(Self { joint_positions, poses })
+    }
+
+    /// Compares `actual` against this (golden) snapshot.
+    ///
+    /// * `pos_tol`: maximum allowed distance between frame origins.
+    /// * `rot_tol`: maximum allowed relative rotation angle in radians.
+    pub fn diff(&self, actual: &PoseSnapshot, pos_tol: f64, rot_tol: f64) -> SnapshotDiff {
+        let mut structure_mismatch = None;
+
+        if self.poses.len() != actual.poses.len() {
+            structure_mismatch = Some(format!(
+                "frame count differs: golden={}, actual={}", self.poses.len(), actual.poses.len()
+            ));
+        } else if self.joint_positions.len() != actual.joint_positions.len()
+            || !self.joint_positions.iter().zip(&actual.joint_positions).all(|(a, b)| (a - b).abs() <= 1e-12)
+        {
+            structure_mismatch = Some("snapshots were captured at different joint positions".into());
+        }
+
+        let mut frames = Vec::new();
+        let mut failures = Vec::new();
+
+        for (i, (golden, pose)) in self.poses.iter().zip(&actual.poses).enumerate() {
+            let position_error = (golden.position - pose.position).norm();
+            let rotation_error = golden.rotation_angle_to(pose);
+
+            // Written so that a NaN error counts as a failure.
+            if !(position_error <= pos_tol && rotation_error <= rot_tol) {
+                failures.push(i);
+            }
+            frames.push(FrameDiff { frame_index: i, position_error, rotation_error });
+        }
+
+        SnapshotDiff { frames, failures, structure_mismatch }
+    }
+}
@@ -0,0 +1,117 @@
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String};
+
+use crate::joint::Joint;
+
+/// Converts joint velocity commands into position setpoints for position-only servos.
+///
+/// `TaskSpacePidController` (and the other velocity controllers) emit degrees/s,
+/// while the URT servos only accept absolute positions. This integrator runs at
+/// the servo rate and keeps its own setpoint, which it:
+/// * advances by `q̇·dt` every step,
+/// * clamps to the joint limits (so it never winds up past a hard stop),
+/// * pulls back towards the measured position (drift correction) and never lets
+///   it lead the measurement by more than `max_lead`, so a stalled or slow servo
+///   does not accumulate a large hidden error.
+///
+/// Units match [`crate::dh_arm_model::DHArmModel::set_joint_positions`]:
+/// degrees for revolute joints, linear units for prismatic joints.
+#[derive(Debug, Clone)]
+pub struct PositionIntegrator<const J: usize> {
+    setpoint: [f64; J],
+    limit_min: [Option<f64>; J],
+    limit_max: [Option<f64>; J],
+    /// Rate (1/s) at which the setpoint is blended towards feedback. 0 disables.
+    drift_gain: f64,
+    /// Maximum allowed |setpoint - measured| per joint. `None` disables.
+    max_lead: Option<f64>,
+    initialized: bool,
+}
+
+impl<const J: usize> PositionIntegrator<J> {
+    /// Creates an integrator without joint limits. Fails if `max_lead` is negative or
+    /// not finite.
+    pub fn new(drift_gain: f64, max_lead: Option<f64>) -> Result<Self, String> {
+        if let Some(lead) = max_lead.filter(|lead| !(lead.is_finite() && *lead >= 0.0)) {
+            return Err(format!("max_lead must be a non-negative number, got {}", lead));
+        }
+        Ok(Self {
+            setpoint: [0.0; J],
+            limit_min: [None; J],
+            limit_max: [None; J],
+            drift_gain,
+            max_lead,
+            initialized: false,
+        })
+    }
+
+    /// Creates an integrator that clamps to the limits configured on `joints`; fails
+    /// as [`PositionIntegrator::new`] does.
+    pub fn from_joints(joints: &[Joint; J], drift_gain: f64, max_lead: Option<f64>) -> Result<Self, String> {
+        let mut integrator = Self::new(drift_gain, max_lead)?;
+        for (i, joint) in joints.iter().enumerate() {
+            // Joint limits are stored internally in radians for revolute joints
+            let to_user = |v: f64| joint.joint_type.to_user_units(v);
+            integrator.limit_min[i] = joint.limit_min.map(to_user);
+            integrator.limit_max[i] = joint.limit_max.map(to_user);
+        }
+        Ok(integrator)
+    }
+
+    /// Re-seeds the setpoint from measured positions (e.g. after enabling the servos).
+    /// Joints whose reading is not finite keep their previous setpoint.
+    pub fn reset(&mut self, measured: &[f64; J]) {
+        for (i, &q) in measured.iter().enumerate() {
+            if q.is_finite() {
+                self.setpoint[i] = self.clamp_to_limits(i, q);
+            }
+        }
+        self.initialized = true;
+    }
+
+    /// Current position setpoint.
+    pub fn setpoint(&self) -> &[f64; J] {
+        &self.setpoint
+    }
+
+    /// Advances the setpoint by one servo period and returns the new position command.
+    ///
+    /// `measured` is the latest position feedback if available; without it the
+    /// integrator runs open-loop, as it does for a joint whose reading is not finite.
+    /// The first call seeds from `measured` (or zero).
+    pub fn step(&mut self, velocities: &[f64; J], measured: Option<&[f64; J]>, dt: f64) -> [f64; J] {
+        if !self.initialized {
+            self.reset(measured.unwrap_or(&[0.0; J]));
+        }
+
+        for i in 0..J {
+            let mut q = self.setpoint[i] + velocities[i] * dt;
+
+            // A bad reading must not poison the setpoint or stop the servo loop
+            if let Some(meas) = measured.map(|m| m[i]).filter(|m| m.is_finite()) {
+                // Leaky blend towards feedback: removes slow drift between model and servo
+                let blend = (self.drift_gain * dt).clamp(0.0, 1.0);
+                q += blend * (meas - q);
+
+                if let Some(lead) = self.max_lead {
+                    q = q.max(meas - lead).min(meas + lead);
+                }
+            }
+
+            self.setpoint[i] = self.clamp_to_limits(i, q);
+        }
+
+        self.setpoint
+    }
+
+    fn clamp_to_limits(&self, i: usize, q: f64) -> f64 {
+        let mut q = q;
+        if let Some(min) = self.limit_min[i] {
+            q = q.max(min);
+        }
+        if let Some(max) = self.limit_max[i] {
+            q = q.min(max);
+        }
+        q
+    }
+}
@@ -0,0 +1,158 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Stages of one control/visualization step that are timed separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Fk,
+    Jacobian,
+    PseudoInverse,
+    /// Controller work excluding the FK/Jacobian it triggers.
+    Controller,
+    Collision,
+    Render,
+}
+
+impl Phase {
+    pub const COUNT: usize = 6;
+    pub const ALL: [Phase; Phase::COUNT] = [
+        Phase::Fk,
+        Phase::Jacobian,
+        Phase::PseudoInverse,
+        Phase::Controller,
+        Phase::Collision,
+        Phase::Render,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Fk => "fk",
+            Phase::Jacobian => "jacobian",
+            Phase::PseudoInverse => "pinv",
+            Phase::Controller => "controller",
+            Phase::Collision => "collision",
+            Phase::Render => "render",
+        }
+    }
+}
+
+/// Time spent in each phase, accumulated over one step.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PhaseTimings {
+    durations: [Duration; Phase::COUNT],
+}
+
+impl PhaseTimings {
+    pub fn add(&mut self, phase: Phase, elapsed: Duration) {
+        self.durations[phase as usize] += elapsed;
+    }
+
+    pub fn get(&self, phase: Phase) -> Duration {
+        self.durations[phase as usize]
+    }
+
+    pub fn total(&self) -> Duration {
+        self.durations.iter().sum()
+    }
+
+    pub fn merge(&mut self, other: &PhaseTimings) {
+        for phase in Phase::ALL {
+            self.add(phase, other.get(phase));
+        }
+    }
+}
+
+impl fmt::Display for PhaseTimings {
+    /// One-line breakdown, skipping phases that were never timed.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for phase in Phase::ALL {
+            let d = self.get(phase);
+            if d.is_zero() {
+                continue;
+            }
+            if !first {
+                write!(f, " | ")?;
+            }
+            write!(f, "{} {:.1}us", phase.name(), d.as_secs_f64() * 1e6)?;
+            first = false;
+        }
+        write!(f, "{}total {:.1}us", if first { "" } else { " | " }, self.total().as_secs_f64() * 1e6)
+    }
+}
+
+/// Accumulates per-phase timings for each step and keeps a smoothed per-step breakdown.
+#[derive(Debug, Clone)]
+pub struct StepProfiler {
+    current: PhaseTimings,
+    last: PhaseTimings,
+    average: PhaseTimings,
+    steps: u64,
+    /// Weight of the newest step in the moving average (0..=1].
+    smoothing: f64,
+}
+
+impl StepProfiler {
+    pub fn new(smoothing: f64) -> Self {
+        Self {
+            current: PhaseTimings::default(),
+            last: PhaseTimings::default(),
+            average: PhaseTimings::default(),
+            steps: 0,
+            smoothing: smoothing.clamp(f64::EPSILON, 1.0),
+        }
+    }
+
+    /// Runs `f`, charging its wall time to `phase`.
+    pub fn time<R>(&mut self, phase: Phase, f: impl FnOnce() -> R) -> R {
+        let start = Instant::now();
+        let result = f();
+        self.record(phase, start.elapsed());
+        result
+    }
+
+    pub fn record(&mut self, phase: Phase, elapsed: Duration) {
+        self.current.add(phase, elapsed);
+    }
+
+    /// Adds timings gathered elsewhere (e.g. by `DHArmModel`) to the current step.
+    pub fn merge(&mut self, timings: &PhaseTimings) {
+        self.current.merge(timings);
+    }
+
+    /// Charges `elapsed` to `phase` minus the time the `nested` timings already account for,
+    /// so a caller's own work can be separated from the work it triggered.
+    pub fn record_excluding(&mut self, phase: Phase, elapsed: Duration, nested: &PhaseTimings) {
+        self.record(phase, elapsed.saturating_sub(nested.total()));
+    }
+
+    /// Closes the current step and folds it into the moving average.
+    pub fn end_step(&mut self) {
+        for phase in Phase::ALL {
+            let avg = self.average.get(phase).as_secs_f64();
+            let cur = self.current.get(phase).as_secs_f64();
+            let next = if self.steps == 0 { cur } else { avg + self.smoothing * (cur - avg) };
+            self.average.durations[phase as usize] = Duration::from_secs_f64(next);
+        }
+        self.last = std::mem::take(&mut self.current);
+        self.steps += 1;
+    }
+
+    pub fn last(&self) -> &PhaseTimings {
+        &self.last
+    }
+
+    pub fn average(&self) -> &PhaseTimings {
+        &self.average
+    }
+
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+}
+
+impl Default for StepProfiler {
+    fn default() -> Self {
+        Self::new(0.05)
+    }
+}
@@ -0,0 +1,187 @@
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::dh_arm_model::DHArmModel;
+use crate::inverse_kinematics_solvers::IkSolver;
+use crate::task_space_limits::WorkspaceBox;
+
+/// Software emergency stop shared between everything that may need to halt the arm.
+///
+/// Clones are handles to the same latch, so a UI, a remote client and the control
+/// loop can each hold one. Once triggered it stays triggered until released.
+#[derive(Debug, Clone, Default)]
+pub struct EStop(Arc<AtomicBool>);
+
+impl EStop {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn trigger(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn release(&self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Expires when no fresh command has been fed within `timeout`.
+#[derive(Debug, Clone, Copy)]
+pub struct CommandWatchdog {
+    pub timeout: Duration,
+    last_command: Option<Instant>,
+}
+
+impl CommandWatchdog {
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout, last_command: None }
+    }
+
+    /// Records that a command arrived at `now`.
+    pub fn feed(&mut self, now: Instant) {
+        self.last_command = Some(now);
+    }
+
+    /// Forgets the last command, so the watchdog stays expired until fed again.
+    pub fn clear(&mut self) {
+        self.last_command = None;
+    }
+
+    /// True if no command was ever fed, or the last one is older than `timeout`.
+    pub fn is_expired(&self, now: Instant) -> bool {
+        self.last_command.is_none_or(|t| now.saturating_duration_since(t) > self.timeout)
+    }
+}
+
+/// Why [`SafetyLayer`] is holding the arm still.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StopReason {
+    EStop,
+    WatchdogTimeout,
+    /// The end-effector is outside the workspace box and the command would take it further out.
+    OutsideWorkspace,
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StopReason::EStop => write!(f, "e-stop"),
+            StopReason::WatchdogTimeout => write!(f, "command watchdog timeout"),
+            StopReason::OutsideWorkspace => write!(f, "end-effector outside workspace"),
+        }
+    }
+}
+
+/// Gate between the controllers and the hardware: passes the latest joint velocity
+/// command through only while it is fresh, the e-stop is released and the
+/// end-effector is inside the workspace box; otherwise it commands zero velocity.
+///
+/// Controllers hand their output to [`SafetyLayer::command`]; the servo loop sends
+/// whatever [`SafetyLayer::output`] returns, e.g. through
+/// [`CommandConverter::send`](crate::hardware::CommandConverter::send), where a zero
+/// velocity holds the position setpoint for position-mode servos.
+#[derive(Debug, Clone)]
+pub struct SafetyLayer<const J: usize> {
+    pub watchdog: CommandWatchdog,
+    /// Box the end-effector must stay inside; `None` disables the check.
+    pub workspace: Option<WorkspaceBox>,
+    estop: EStop,
+    command: [f64; J],
+    stop_reason: Option<StopReason>,
+}
+
+impl<const J: usize> SafetyLayer<J> {
+    /// Zeroes the output once commands are older than `command_timeout`.
+    pub fn new(command_timeout: Duration) -> Self {
+        Self {
+            watchdog: CommandWatchdog::new(command_timeout),
+            workspace: None,
+            estop: EStop::new(),
+            command: [0.0; J],
+            stop_reason: None,
+        }
+    }
+
+    /// Uses `estop` instead of the layer's own latch, e.g. one e-stop shared by several arms.
+    pub fn with_estop(mut self, estop: EStop) -> Self {
+        self.estop = estop;
+        self
+    }
+
+    /// A handle to this layer's e-stop.
+    pub fn estop(&self) -> EStop {
+        self.estop.clone()
+    }
+
+    /// Why the last [`SafetyLayer::output`] was zeroed, `None` if it passed the command through.
+    pub fn stop_reason(&self) -> Option<StopReason> {
+        self.stop_reason
+    }
+
+    /// Accepts a new joint velocity command (deg/s / linear units per s) issued at `now`.
+    pub fn command(&mut self, now: Instant, joint_velocities: &[f64; J]) {
+        self.command = *joint_velocities;
+        self.watchdog.feed(now);
+    }
+
+    /// The joint velocities to send at `now`, given the measured joint positions
+    /// (deg / linear units).
+    ///
+    /// While the e-stop is triggered, commands are discarded, so after a release the
+    /// arm stays still until a controller issues a fresh one. Outside the workspace
+    /// box, only commands that move the end-effector back towards it are passed.
+    pub fn output<const F: usize, S: IkSolver<J>>(
+        &mut self,
+        arm: &mut DHArmModel<F, J, S>,
+        joint_pos: &[f64; J],
+        now: Instant,
+    ) -> [f64; J] {
+        self.stop_reason = self.check(arm, joint_pos, now);
+        if self.stop_reason == Some(StopReason::EStop) {
+            self.command = [0.0; J];
+            self.watchdog.clear();
+        }
+        match self.stop_reason {
+            Some(_) => [0.0; J],
+            None => self.command,
+        }
+    }
+
+    fn check<const F: usize, S: IkSolver<J>>(
+        &self,
+        arm: &mut DHArmModel<F, J, S>,
+        joint_pos: &[f64; J],
+        now: Instant,
+    ) -> Option<StopReason> {
+        if self.estop.is_triggered() {
+            return Some(StopReason::EStop);
+        }
+        if self.watchdog.is_expired(now) {
+            return Some(StopReason::WatchdogTimeout);
+        }
+
+        let workspace = self.workspace.as_ref()?;
+        arm.set_joint_positions(joint_pos);
+        let ee = arm.end_effector_pose().position;
+        if workspace.contains(&ee) {
+            return None;
+        }
+
+        // Predicted end-effector velocity of the command; the Jacobian works in radians
+        let qd = nalgebra::SVector::<f64, J>::from_fn(|i, _| arm.joints()[i].joint_type.from_user_units(self.command[i]));
+        let ee_vel = arm.jacobian().fixed_rows::<3>(0) * qd;
+        let outward = workspace.outward(&ee);
+        if (0..3).any(|i| outward[i] * ee_vel[i] > 0.0) {
+            Some(StopReason::OutsideWorkspace)
+        } else {
+            None
+        }
+    }
+}
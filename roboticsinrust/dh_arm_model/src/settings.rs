@@ -0,0 +1,129 @@
+use std::fs;
+use std::path::Path;
+
+use crate::parameters::{ParamUnit, ParamValue, Tunable};
+
+const HEADER: &str = "# dh_arm_model settings v1";
+
+/// Tuned parameter values and control period, saved to disk and applied again on
+/// startup, so gains tuned at runtime (remote `param set`, hotkeys) survive a restart
+/// without editing and recompiling the program.
+///
+/// Stored as plain text, one `name value` per line in the names of
+/// [`crate::parameters::Tunable`], which makes the file easy to edit by hand too.
+/// Parameters with a [`ParamUnit`] are written with their unit, and may be given in
+/// any unit of the same quantity (`1.5 rad/s`, `15 rpm`):
+/// ```text
+/// # dh_arm_model settings v1
+/// dt 0.05
+/// damping 0.0001
+/// kp.x 1.5
+/// limits.max_angular_speed 90 deg/s
+/// orthonorm_interval 50
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TuningSettings {
+    /// Control period (s).
+    pub dt: f64,
+    /// Parameter values by name, in the order they are written, with the quantity
+    /// of those written with a unit. Values are held in the parameter's own unit.
+    pub params: Vec<(String, ParamValue, Option<ParamUnit>)>,
+}
+
+impl TuningSettings {
+    /// The current values of every parameter of `target`.
+    pub fn capture<T: Tunable + ?Sized>(target: &T, dt: f64) -> Self {
+        let params = target.params().into_iter().map(|p| (p.spec.name, p.value, p.spec.unit)).collect();
+        Self { dt, params }
+    }
+
+    /// Sets every stored parameter on `target`. All values are checked against the
+    /// target's parameters first, so a file with an unknown name, an out-of-range
+    /// value or a unit of the wrong quantity changes nothing. Parameters the file does
+    /// not mention keep their values.
+    pub fn apply<T: Tunable + ?Sized>(&self, target: &mut T) -> Result<(), String> {
+        let checked = self
+            .params
+            .iter()
+            .map(|(name, value, unit)| {
+                let spec = target.param_spec(name)?;
+                match unit {
+                    Some(unit) if spec.unit != Some(*unit) => Err(format!("Parameter '{}' is not an {}", name, unit)),
+                    _ => spec.check(*value).map(|value| (name, value)),
+                }
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        for (name, value) in checked {
+            target.set_param(name, value)?;
+        }
+        Ok(())
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let mut text = format!("{}\ndt {}\n", HEADER, self.dt);
+        for (name, value, unit) in &self.params {
+            match unit {
+                Some(unit) => text.push_str(&format!("{} {} {}\n", name, value, unit.symbol())),
+                None => text.push_str(&format!("{} {}\n", name, value)),
+            }
+        }
+        fs::write(path.as_ref(), text)
+            .map_err(|e| format!("Failed to write settings {}: {}", path.as_ref().display(), e))
+    }
+
+    /// Reads settings written by [`TuningSettings::save`]. Whole numbers are read as
+    /// integers, which float parameters accept too; values with a unit are converted
+    /// to the unit parameters hold them in.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let text = fs::read_to_string(path.as_ref())
+            .map_err(|e| format!("Failed to read settings {}: {}", path.as_ref().display(), e))?;
+        let mut lines = text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+        if lines.next().map(|(_, l)| l) != Some(HEADER) {
+            return Err("Settings file is missing the expected header".into());
+        }
+
+        let mut settings = Self { dt: 0.0, params: Vec::new() };
+        let mut has_dt = false;
+        for (line_no, line) in lines {
+            let line_no = line_no + 1;
+            if line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (name, value, unit) = match fields[..] {
+                [name, value] => (name, value, None),
+                [name, value, unit] => (name, value, Some(unit)),
+                _ => return Err(format!("Expected 'name value [unit]' on line {}", line_no)),
+            };
+            let invalid = || format!("Invalid value '{}' for '{}' on line {}", value, name, line_no);
+            if name == "dt" {
+                // Always seconds
+                if unit.is_some() {
+                    return Err(invalid());
+                }
+                settings.dt = value.parse().ok().filter(|dt: &f64| *dt > 0.0).ok_or_else(invalid)?;
+                has_dt = true;
+                continue;
+            }
+            let (value, unit) = match unit {
+                Some(symbol) => {
+                    let number = value.parse::<f64>().map_err(|_| invalid())?;
+                    let (unit, number) = ParamUnit::parse(number, symbol).map_err(|e| format!("{} on line {}", e, line_no))?;
+                    (ParamValue::Float(number), Some(unit))
+                }
+                None => match value.parse::<i64>() {
+                    Ok(v) => (ParamValue::Int(v), None),
+                    Err(_) => (value.parse::<f64>().map(ParamValue::Float).map_err(|_| invalid())?, None),
+                },
+            };
+            if settings.params.iter().any(|(n, _, _)| n == name) {
+                return Err(format!("'{}' is set twice (line {})", name, line_no));
+            }
+            settings.params.push((name.to_string(), value, unit));
+        }
+        if !has_dt {
+            return Err("Settings file does not set 'dt'".into());
+        }
+        Ok(settings)
+    }
+}
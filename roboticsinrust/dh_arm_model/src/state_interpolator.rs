@@ -0,0 +1,148 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::hardware::{ArmHardware, JointState};
+
+/// Feedback packets kept for interpolation; only the two around the control instant
+/// are used, the rest absorb packets arriving out of order.
+const HISTORY: usize = 8;
+
+/// How a [`StateEstimate`] was obtained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EstimateKind {
+    /// Between two feedback packets.
+    Interpolated,
+    /// Past the newest packet, projected along its velocity.
+    Extrapolated,
+    /// Before the oldest packet kept; its state is returned as is.
+    Held,
+}
+
+/// Joint state at a control instant, estimated from the feedback around it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StateEstimate<const J: usize> {
+    pub state: JointState<J>,
+    pub kind: EstimateKind,
+    /// Time from the newest packet to the control instant (zero if the packet is newer).
+    pub age: Duration,
+    /// The instant lies further past the newest packet than `max_extrapolation`; the
+    /// projection stops there and the feedback has likely dropped out.
+    pub stale: bool,
+}
+
+/// Turns joint feedback arriving at irregular times into a state estimate at the
+/// controller's own instants.
+///
+/// Feeding the controller whichever packet arrived last makes its error jitter with
+/// the packet timing, and a late packet looks like a stalled arm. Instead, push each
+/// packet with the time it was sampled (or received) and ask for the state at the
+/// control instant: between packets the positions follow the cubic through both
+/// packets' positions and velocities, and past the newest packet they continue along
+/// its velocity for at most `max_extrapolation`.
+///
+/// Units are those of [`JointState`] (deg, deg/s / linear units).
+#[derive(Debug, Clone)]
+pub struct JointStateInterpolator<const J: usize> {
+    /// Packets in time order.
+    samples: VecDeque<(Instant, JointState<J>)>,
+    pub max_extrapolation: Duration,
+    /// Driver sequence number of the last packet taken by [`JointStateInterpolator::poll_stamped`].
+    last_sequence: Option<u64>,
+}
+
+impl<const J: usize> JointStateInterpolator<J> {
+    /// `max_extrapolation` is typically two or three nominal feedback periods.
+    pub fn new(max_extrapolation: Duration) -> Self {
+        Self { samples: VecDeque::with_capacity(HISTORY), max_extrapolation, last_sequence: None }
+    }
+
+    /// Adds a feedback packet sampled at `stamp`. Packets may arrive out of order; one
+    /// with the same stamp as a kept packet replaces it.
+    pub fn push(&mut self, stamp: Instant, state: JointState<J>) {
+        let index = self.samples.partition_point(|(t, _)| *t < stamp);
+        match self.samples.get_mut(index) {
+            Some((t, s)) if *t == stamp => *s = state,
+            _ => self.samples.insert(index, (stamp, state)),
+        }
+        if self.samples.len() > HISTORY {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Reads `hardware` and pushes the state stamped `received`, for drivers that
+    /// return a fresh reading on every call.
+    pub fn poll<H: ArmHardware<J>>(&mut self, hardware: &mut H, received: Instant) -> Result<(), String> {
+        let state = hardware.read_state()?;
+        self.push(received, state);
+        Ok(())
+    }
+
+    /// Like [`JointStateInterpolator::poll`] for drivers that return the last packet
+    /// again until the next one arrives: `sequence` is the driver's sequence number (or
+    /// sample counter) of the packet `read_state` returns, and the state is only pushed
+    /// when it changes. A repeated packet would otherwise be restamped and hide a
+    /// dropout; comparing values instead would take an arm standing still for one.
+    pub fn poll_stamped<H: ArmHardware<J>>(
+        &mut self,
+        hardware: &mut H,
+        sequence: u64,
+        received: Instant,
+    ) -> Result<(), String> {
+        let state = hardware.read_state()?;
+        if self.last_sequence != Some(sequence) {
+            self.last_sequence = Some(sequence);
+            self.push(received, state);
+        }
+        Ok(())
+    }
+
+    /// Newest packet and its stamp.
+    pub fn latest(&self) -> Option<(Instant, &JointState<J>)> {
+        self.samples.back().map(|(t, s)| (*t, s))
+    }
+
+    /// Forgets all packets, e.g. after the drives were re-enabled.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.last_sequence = None;
+    }
+
+    /// Estimated joint state at `at`, or `None` before any feedback arrived.
+    pub fn estimate(&self, at: Instant) -> Option<StateEstimate<J>> {
+        let (newest_t, newest) = self.samples.back()?;
+        let age = at.saturating_duration_since(*newest_t);
+
+        if at >= *newest_t {
+            let stale = age > self.max_extrapolation;
+            let tau = age.min(self.max_extrapolation).as_secs_f64();
+            let mut state = *newest;
+            for (q, v) in state.positions.iter_mut().zip(&newest.velocities) {
+                *q += v * tau;
+            }
+            return Some(StateEstimate { state, kind: EstimateKind::Extrapolated, age, stale });
+        }
+
+        let after = self.samples.partition_point(|(t, _)| *t <= at);
+        if after == 0 {
+            let (_, oldest) = self.samples.front()?;
+            return Some(StateEstimate { state: *oldest, kind: EstimateKind::Held, age, stale: false });
+        }
+        let (t0, s0) = &self.samples[after - 1];
+        let (t1, s1) = &self.samples[after];
+        let h = t1.duration_since(*t0).as_secs_f64();
+        let s = at.duration_since(*t0).as_secs_f64() / h;
+
+        // Cubic Hermite basis and its derivative (per unit s)
+        let (s2, s3) = (s * s, s * s * s);
+        let (h00, h10, h01, h11) = (2.0 * s3 - 3.0 * s2 + 1.0, s3 - 2.0 * s2 + s, -2.0 * s3 + 3.0 * s2, s3 - s2);
+        let (d00, d10, d01, d11) = (6.0 * s2 - 6.0 * s, 3.0 * s2 - 4.0 * s + 1.0, -6.0 * s2 + 6.0 * s, 3.0 * s2 - 2.0 * s);
+
+        let mut state = *s0;
+        for i in 0..J {
+            let (p0, v0, p1, v1) = (s0.positions[i], s0.velocities[i], s1.positions[i], s1.velocities[i]);
+            state.positions[i] = h00 * p0 + h10 * h * v0 + h01 * p1 + h11 * h * v1;
+            state.velocities[i] = (d00 * p0 + d10 * h * v0 + d01 * p1 + d11 * h * v1) / h;
+        }
+        Some(StateEstimate { state, kind: EstimateKind::Interpolated, age, stale: false })
+    }
+}
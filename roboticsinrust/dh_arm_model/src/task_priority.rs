@@ -0,0 +1,62 @@
+use nalgebra::{SMatrix, SVector};
+
+/// Resolves several task-space objectives into one joint velocity by strict priority.
+///
+/// Add tasks from the highest priority down. Each is met as well as it can be using
+/// only joint motions that leave every task added before it unaffected (the null
+/// space of those tasks), so a lower-priority task gives way where they conflict
+/// instead of dragging the others off. With the six joints of the URT arm, a
+/// 2-row pointing task and a 3-row position task both fit, leaving one degree of
+/// freedom (the roll about the line of sight) to the minimum-norm solution.
+///
+/// Each task is a Jacobian mapping joint velocities to its rate, with the same
+/// conventions as [`crate::dh_arm_model::DHArmModel::jacobian`] (rad/s for revolute
+/// joints), and the rate wanted from it. The pseudo-inverses are damped by `damping`
+/// like the arm's own, so a task that is singular, or fully blocked by the ones
+/// above it, yields small motions instead of large ones.
+#[derive(Debug, Clone)]
+pub struct TaskPriority<const J: usize> {
+    joint_velocity: SVector<f64, J>,
+    /// Projector onto the joint motions no task added so far is sensitive to.
+    null_space: SMatrix<f64, J, J>,
+    damping: f64,
+}
+
+impl<const J: usize> TaskPriority<J> {
+    pub fn new(damping: f64) -> Self {
+        Self { joint_velocity: SVector::zeros(), null_space: SMatrix::identity(), damping }
+    }
+
+    /// Adds the next task, below every task added before it.
+    pub fn add_task<const M: usize>(&mut self, jacobian: &SMatrix<f64, M, J>, rate: &SVector<f64, M>) -> &mut Self {
+        let projected = jacobian * self.null_space;
+        let pinv = damped_pseudo_inverse(&projected, self.damping);
+        // Only ask for what the higher-priority motion has not already produced
+        self.joint_velocity += pinv * (rate - jacobian * self.joint_velocity);
+        self.null_space -= pinv * projected;
+        self
+    }
+
+    /// Joint velocity meeting the tasks added so far (rad/s / linear units/s).
+    pub fn joint_velocity(&self) -> &SVector<f64, J> {
+        &self.joint_velocity
+    }
+
+    /// Joint motions still free after the tasks added so far.
+    pub fn null_space(&self) -> &SMatrix<f64, J, J> {
+        &self.null_space
+    }
+}
+
+/// Aᵀ(AAᵀ + λ²I)⁻¹, zero if that is not invertible (only possible with no damping).
+fn damped_pseudo_inverse<const M: usize, const J: usize>(a: &SMatrix<f64, M, J>, damping: f64) -> SMatrix<f64, J, M> {
+    let mut inner = a * a.transpose();
+    for i in 0..M {
+        inner[(i, i)] += damping * damping;
+    }
+    // AAᵀ + λ²I is symmetric, so Aᵀ(AAᵀ + λ²I)⁻¹ = ((AAᵀ + λ²I)⁻¹A)ᵀ
+    match inner.cholesky() {
+        Some(cholesky) => cholesky.solve(a).transpose(),
+        None => SMatrix::zeros(),
+    }
+}
@@ -0,0 +1,86 @@
+use nalgebra::Vector3;
+
+use crate::units::AngularVelocityUnit;
+
+/// Axis-aligned box the end-effector must stay inside, in the base frame (linear units).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkspaceBox {
+    min: Vector3<f64>,
+    max: Vector3<f64>,
+}
+
+impl WorkspaceBox {
+    /// The box spanned by two opposite corners, in either order.
+    pub fn new(a: Vector3<f64>, b: Vector3<f64>) -> Self {
+        Self { min: a.zip_map(&b, f64::min), max: a.zip_map(&b, f64::max) }
+    }
+
+    /// Corner with the lowest coordinates.
+    pub fn min(&self) -> &Vector3<f64> {
+        &self.min
+    }
+
+    /// Corner with the highest coordinates.
+    pub fn max(&self) -> &Vector3<f64> {
+        &self.max
+    }
+
+    pub fn contains(&self, point: &Vector3<f64>) -> bool {
+        (0..3).all(|i| point[i] >= self.min[i] && point[i] <= self.max[i])
+    }
+
+    /// The point of the box nearest to `point`.
+    pub fn clamp(&self, point: &Vector3<f64>) -> Vector3<f64> {
+        Vector3::from_fn(|i, _| point[i].max(self.min[i]).min(self.max[i]))
+    }
+
+    /// Per axis: +1 if `point` is past `max`, -1 if past `min`, 0 if within bounds.
+    pub fn outward(&self, point: &Vector3<f64>) -> Vector3<f64> {
+        Vector3::from_fn(|i, _| {
+            if point[i] > self.max[i] {
+                1.0
+            } else if point[i] < self.min[i] {
+                -1.0
+            } else {
+                0.0
+            }
+        })
+    }
+}
+
+/// Cartesian limits a task-space controller enforces on its commands.
+///
+/// Every limit is optional; the default enforces nothing.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TaskSpaceLimits {
+    /// References outside this box are refused (clamped onto the box).
+    pub workspace: Option<WorkspaceBox>,
+    /// Maximum end-effector linear speed (linear units/s).
+    pub max_linear_speed: Option<f64>,
+    /// Maximum end-effector angular speed (deg/s).
+    pub max_angular_speed: Option<f64>,
+}
+
+impl TaskSpaceLimits {
+    /// Scales a linear velocity down to `max_linear_speed`, keeping its direction.
+    pub fn limit_linear(&self, v: Vector3<f64>) -> Vector3<f64> {
+        match self.max_linear_speed {
+            Some(max) => limit_norm(v, max),
+            None => v,
+        }
+    }
+
+    /// Scales an angular velocity in rad/s down to `max_angular_speed`, keeping its axis.
+    pub fn limit_angular(&self, w: Vector3<f64>) -> Vector3<f64> {
+        match self.max_angular_speed {
+            Some(max) => limit_norm(w, AngularVelocityUnit::DegreesPerSecond.to_radians_per_second(max)),
+            None => w,
+        }
+    }
+}
+
+/// Scales `v` down to length `max` if it is longer.
+pub(crate) fn limit_norm(v: Vector3<f64>, max: f64) -> Vector3<f64> {
+    let norm = v.norm();
+    if norm > max { v * (max / norm) } else { v }
+}
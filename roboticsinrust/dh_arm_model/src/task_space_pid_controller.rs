@@ -2,12 +2,17 @@ use crate::dh_arm_model::DHArmModel;
 
 use nalgebra::{SVector, Vector3, Matrix3};
 use crate::inverse_kinematics_solvers::IkSolver;
+use crate::task_space_limits::TaskSpaceLimits;
+use crate::units::AngularVelocityUnit;
 
 pub struct TaskSpacePidController {
     pub kp: SVector<f64, 6>,
     pub ki: SVector<f64, 6>,
     pub kd: SVector<f64, 6>,
 
+    /// Workspace and speed limits applied to the reference and the commanded EE velocity.
+    pub limits: TaskSpaceLimits,
+
     // PID state
     integral_error: SVector<f64, 6>,
     prev_error: SVector<f64, 6>,
@@ -35,6 +40,7 @@ impl TaskSpacePidController {
             kp,
             ki,
             kd,
+            limits: TaskSpaceLimits::default(),
             integral_error: SVector::zeros(),
             prev_error: SVector::zeros(),
             x_ref: Vector3::zeros(),
@@ -45,6 +51,28 @@ impl TaskSpacePidController {
         }
     }
 
+    /// Controller cycles between re-orthonormalizations of the reference orientation.
+    pub fn orthonorm_interval(&self) -> usize {
+        self.orthonorm_interval
+    }
+
+    /// Re-orthonormalizes the reference orientation every `interval` tracking cycles
+    /// (at least 1); longer intervals save an SVD, shorter ones limit numerical drift.
+    pub fn set_orthonorm_interval(&mut self, interval: usize) {
+        self.orthonorm_interval = interval.max(1);
+    }
+
+    /// Clears the PID state and pose reference, as if freshly constructed
+    /// (e.g. before replaying a logged session from its start).
+    pub fn reset(&mut self) {
+        self.integral_error = SVector::zeros();
+        self.prev_error = SVector::zeros();
+        self.x_ref = Vector3::zeros();
+        self.r_ref = Matrix3::identity();
+        self.holding = false;
+        self.cycle_count = 0;
+    }
+
     /// Helper: small-angle orientation integration directly (world-frame)
     fn integrate_orientation(&self, r: &Matrix3<f64>, w: &Vector3<f64>, dt: f64) -> Matrix3<f64> {
         let w_x = w[0];
@@ -79,6 +107,9 @@ impl TaskSpacePidController {
     /// - dt: Time step for integration
     /// Output:
     /// - Joint velocity commands to send to motors in degrees/s
+    ///
+    /// The desired velocity and the resulting EE velocity command are capped at the
+    /// speeds in `limits`, and the reference is never moved outside `limits.workspace`.
     pub fn compute<const F: usize, const J: usize, S: IkSolver<J>>(
         &mut self,
         arm: &mut DHArmModel<F, J, S>,
@@ -91,17 +122,18 @@ impl TaskSpacePidController {
         arm.set_joint_positions(motor_pos);
         arm.set_joint_velocities(motor_vels);
 
-        // --- 2️ Current end-effector pose
-        let wrist_pose = arm.frame_pose(F - 1); // Pose { position, rotation }
-        let r_curr = wrist_pose.rotation; // Current 3x3 Rotation Matrix (R_world_ee)
+        // --- 2️ Current end-effector pose (the point the Jacobian maps to)
+        let ee_pose = arm.end_effector_pose(); // Pose { position, rotation }
+        let r_curr = ee_pose.rotation; // Current 3x3 Rotation Matrix (R_world_ee)
 
         // --- 3️ Parse desired task-space velocity directly from array
         // Linear (World)
-        let v_des_world = Vector3::new(xd_des_arr[0], xd_des_arr[1], xd_des_arr[2]);
+        let v_des_world = self.limits.limit_linear(Vector3::new(xd_des_arr[0], xd_des_arr[1], xd_des_arr[2]));
         // Angular (End-Effector) in rad/s, will transform to World next
-        let w_des_ee = Vector3::new(xd_des_arr[3].to_radians(),
-                                                                         xd_des_arr[4].to_radians(),
-                                                                         xd_des_arr[5].to_radians());
+        let to_rad = |w| AngularVelocityUnit::DegreesPerSecond.to_radians_per_second(w);
+        let w_des_ee = self.limits.limit_angular(Vector3::new(to_rad(xd_des_arr[3]),
+                                                                         to_rad(xd_des_arr[4]),
+                                                                         to_rad(xd_des_arr[5])));
 
         // --- 4️ TRANSFORM: Map EE rotation to World Frame
         let w_des_world = r_curr * w_des_ee;
@@ -123,6 +155,18 @@ impl TaskSpacePidController {
             // Position integration (World Frame)
             self.x_ref += v_des_world * dt;
 
+            // Refuse references outside the workspace: stop the reference at the boundary
+            // and drop the feedforward along the blocked axes
+            if let Some(workspace) = &self.limits.workspace {
+                let clamped = workspace.clamp(&self.x_ref);
+                for i in 0..3 {
+                    if clamped[i] != self.x_ref[i] {
+                        xd_des_world[i] = 0.0;
+                    }
+                }
+                self.x_ref = clamped;
+            }
+
             // Orientation integration (Now using the World-transformed w_des)
             self.r_ref = self.integrate_orientation(&self.r_ref, &w_des_world, dt);
 
@@ -137,20 +181,24 @@ impl TaskSpacePidController {
             // HOLD MODE: freeze reference
             if !self.holding {
                 // Capture reference ONCE at release
-                self.x_ref = wrist_pose.position;
-                self.r_ref = wrist_pose.rotation;
+                // (if released outside the workspace, hold at the nearest point inside it)
+                self.x_ref = match &self.limits.workspace {
+                    Some(workspace) => workspace.clamp(&ee_pose.position),
+                    None => ee_pose.position,
+                };
+                self.r_ref = ee_pose.rotation;
                 self.holding = true;
                 //println!(">>> JOYSTICK RELEASED | HOLDING POSITION");
             }
         }
 
         // --- 6️ Compute position error
-        let e_pos = self.x_ref - wrist_pose.position;
+        let e_pos = self.x_ref - ee_pose.position;
 
         // --- 7️ Compute orientation error using cross-product method
-        let x_e = wrist_pose.x_axis();
-        let y_e = wrist_pose.y_axis();
-        let z_e = wrist_pose.z_axis();
+        let x_e = ee_pose.x_axis();
+        let y_e = ee_pose.y_axis();
+        let z_e = ee_pose.z_axis();
 
         let x_r: Vector3<f64> = self.r_ref.column(0).into();
         let y_r: Vector3<f64> = self.r_ref.column(1).into();
@@ -168,7 +216,7 @@ impl TaskSpacePidController {
         let d_error = (error - self.prev_error) / dt;
 
         // Feedforward (xd_des_world) + PID correction
-        let u_task =
+        let mut u_task =
             xd_des_world
             + self.kp.component_mul(&error)
             + self.ki.component_mul(&self.integral_error)
@@ -176,14 +224,20 @@ impl TaskSpacePidController {
 
         self.prev_error = error;
 
+        // Cap the commanded EE speed, feedback included
+        let v_cmd = self.limits.limit_linear(u_task.fixed_rows::<3>(0).into_owned());
+        let w_cmd = self.limits.limit_angular(u_task.fixed_rows::<3>(3).into_owned());
+        u_task.fixed_rows_mut::<3>(0).copy_from(&v_cmd);
+        u_task.fixed_rows_mut::<3>(3).copy_from(&w_cmd);
+
         // --- 10 Map to joint velocities
         let qd_task = arm.inv_jacobian() * u_task;
 
-        // --- 11 Convert to array for motor output (with Rad to Deg conversion)
+        // --- 11 Convert to array for motor output (rad/s to deg/s for revolute joints)
         let mut qd_array = [0.0f64; J];
 
-        for (i, &rad_val) in qd_task.as_slice().iter().enumerate() {
-            qd_array[i] = rad_val.to_degrees();
+        for (i, &val) in qd_task.as_slice().iter().enumerate() {
+            qd_array[i] = arm.joints()[i].joint_type.to_user_units(val);
         }
 
         qd_array
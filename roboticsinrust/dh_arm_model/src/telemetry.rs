@@ -0,0 +1,282 @@
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::joint::{Joint, JointType};
+use crate::units::{AngleUnit, AngularVelocityUnit, LengthUnit};
+
+/// Source of the current time for a control loop.
+///
+/// Loops read "now" through a `Clock` instead of calling `Instant::now()` directly,
+/// so a log replay or a test can drive them with recorded or synthetic time.
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// Wall-clock time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// A clock that only moves when told to, e.g. to the timestamps of a replayed log.
+#[derive(Debug, Clone, Copy)]
+pub struct ManualClock {
+    origin: Instant,
+    elapsed: Duration,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self { origin: Instant::now(), elapsed: Duration::ZERO }
+    }
+
+    /// Time since the clock was created, as far as the clock is concerned.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn set_elapsed(&mut self, elapsed: Duration) {
+        self.elapsed = elapsed;
+    }
+
+    pub fn advance(&mut self, by: Duration) {
+        self.elapsed += by;
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.origin + self.elapsed
+    }
+}
+
+/// One control step of one arm: the state the controller started from and the
+/// command it was given, enough to either re-drive the joints or re-run the controller.
+///
+/// The units below are the ones [`TelemetryLogger`] writes; records loaded from a log
+/// written in other units keep those (see [`TelemetryLog::units`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetryRecord {
+    /// Seconds since the log was started.
+    pub time: f64,
+    pub arm: usize,
+    /// Step length handed to the controller (s).
+    pub dt: f64,
+    /// Task velocity handed to the controller: linear in the base frame,
+    /// angular in the end-effector frame (deg/s).
+    pub task_vel: [f64; 6],
+    /// Joint positions at the start of the step, in controller units (deg / linear units).
+    pub joint_pos: Vec<f64>,
+    /// Joint velocities at the start of the step (deg/s / linear units per s).
+    pub joint_vel: Vec<f64>,
+}
+
+impl TelemetryRecord {
+    /// This record in controller units (deg, deg/s) and `length_unit`, converted from the
+    /// `units` of its log; `joints` are those of the arm it belongs to.
+    pub fn to_controller_units(&self, units: &TelemetryUnits, joints: &[Joint], length_unit: LengthUnit) -> Self {
+        let length = |v: f64| units.length.map_or(v, |unit| unit.convert(v, length_unit));
+        let angular_velocity = |v: f64| units.angular_velocity.convert(v, AngularVelocityUnit::DegreesPerSecond);
+        let mut task_vel = self.task_vel;
+        for (i, v) in task_vel.iter_mut().enumerate() {
+            *v = if i < 3 { length(*v) } else { angular_velocity(*v) };
+        }
+        Self {
+            task_vel,
+            joint_pos: convert_joints(&self.joint_pos, joints, |v| units.angle.convert(v, AngleUnit::Degree), length),
+            joint_vel: convert_joints(&self.joint_vel, joints, angular_velocity, length),
+            ..self.clone()
+        }
+    }
+}
+
+/// Converts joint values with `angular` for revolute joints and `length` for prismatic ones.
+fn convert_joints(values: &[f64], joints: &[Joint], angular: impl Fn(f64) -> f64, length: impl Fn(f64) -> f64) -> Vec<f64> {
+    values.iter().zip(joints).map(|(v, joint)| match joint.joint_type {
+        JointType::Revolute => angular(*v),
+        JointType::Prismatic => length(*v),
+    }).collect()
+}
+
+/// Units the values of a telemetry log are written in, recorded in its `units` line:
+/// angles for revolute joint positions, angular velocities for revolute joint
+/// velocities and the angular task velocity, lengths for everything linear.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TelemetryUnits {
+    pub angle: AngleUnit,
+    pub angular_velocity: AngularVelocityUnit,
+    /// `None` for logs that do not record it; their lengths are taken to be the arm's.
+    pub length: Option<LengthUnit>,
+}
+
+impl TelemetryUnits {
+    /// The controllers' units (deg, deg/s) with lengths in `length`, which is what
+    /// [`TelemetryLogger`] writes.
+    pub fn controller(length: LengthUnit) -> Self {
+        Self { length: Some(length), ..Self::default() }
+    }
+}
+
+/// Degrees and degrees/s with no length unit, for logs without a `units` line.
+impl Default for TelemetryUnits {
+    fn default() -> Self {
+        Self { angle: AngleUnit::Degree, angular_velocity: AngularVelocityUnit::DegreesPerSecond, length: None }
+    }
+}
+
+const HEADER: &str = "# dh_arm_model telemetry v1";
+
+/// Appends timestamped [`TelemetryRecord`]s to a plain-text log, one step per line.
+pub struct TelemetryLogger {
+    out: BufWriter<File>,
+    start: Instant,
+    num_joints: usize,
+    records: usize,
+}
+
+impl TelemetryLogger {
+    /// Creates (or truncates) `path` for records in controller units with lengths in
+    /// `length_unit`; record times are measured from `start`.
+    pub fn create<P: AsRef<Path>>(path: P, num_joints: usize, length_unit: LengthUnit, start: Instant) -> Result<Self, String> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .map_err(|e| format!("Failed to create telemetry log {}: {}", path.display(), e))?;
+        let mut out = BufWriter::new(file);
+        let units = TelemetryUnits::controller(length_unit);
+        writeln!(
+            out,
+            "{}\njoints {}\nunits {} {} {}",
+            HEADER, num_joints, units.angle.symbol(), units.angular_velocity.symbol(), length_unit.symbol()
+        )
+        .map_err(|e| format!("Failed to write telemetry log: {}", e))?;
+        Ok(Self { out, start, num_joints, records: 0 })
+    }
+
+    /// Logs one controller step of arm `arm` taken at `now`.
+    pub fn record(
+        &mut self,
+        now: Instant,
+        arm: usize,
+        dt: f64,
+        task_vel: &[f64; 6],
+        joint_pos: &[f64],
+        joint_vel: &[f64],
+    ) -> Result<(), String> {
+        if joint_pos.len() != self.num_joints || joint_vel.len() != self.num_joints {
+            return Err(format!("Telemetry log expects {} joints", self.num_joints));
+        }
+        let time = now.saturating_duration_since(self.start).as_secs_f64();
+        let mut line = format!("step {} {} {}", time, arm, dt);
+        for v in task_vel.iter().chain(joint_pos).chain(joint_vel) {
+            line.push_str(&format!(" {}", v));
+        }
+        writeln!(self.out, "{}", line).map_err(|e| format!("Failed to write telemetry log: {}", e))?;
+        self.records += 1;
+        Ok(())
+    }
+
+    pub fn records(&self) -> usize {
+        self.records
+    }
+
+    /// Flushes buffered records and closes the log, returning the number of records written.
+    pub fn finish(mut self) -> Result<usize, String> {
+        self.out.flush().map_err(|e| format!("Failed to flush telemetry log: {}", e))?;
+        Ok(self.records)
+    }
+}
+
+/// A telemetry log read back from disk, records in the order they were taken.
+#[derive(Debug, Clone, Default)]
+pub struct TelemetryLog {
+    pub num_joints: usize,
+    /// Units the records are in, as written; see [`TelemetryRecord::to_controller_units`].
+    pub units: TelemetryUnits,
+    pub records: Vec<TelemetryRecord>,
+}
+
+impl TelemetryLog {
+    /// Reads a log written by [`TelemetryLogger`]. Step times must be non-negative and
+    /// must not go backwards.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let text = fs::read_to_string(path.as_ref())
+            .map_err(|e| format!("Failed to read telemetry log {}: {}", path.as_ref().display(), e))?;
+
+        let mut lines = text.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+        if lines.next().map(|(_, l)| l) != Some(HEADER) {
+            return Err("Telemetry log is missing the expected header".into());
+        }
+
+        let mut log = TelemetryLog::default();
+        for (line_no, line) in lines {
+            let line_no = line_no + 1;
+            let mut fields = line.split_whitespace();
+            match fields.next() {
+                Some("joints") => {
+                    log.num_joints = fields.next().and_then(|s| s.parse().ok())
+                        .ok_or_else(|| format!("Invalid joint count on line {}", line_no))?;
+                }
+                Some("units") => {
+                    if !log.records.is_empty() {
+                        return Err(format!("Units on line {} come after the first step", line_no));
+                    }
+                    let [angle, angular_velocity, length] = fields.collect::<Vec<_>>()[..] else {
+                        return Err(format!("Expected 'units <angle> <angular velocity> <length>' on line {}", line_no));
+                    };
+                    let unit_error = |e: String| format!("{} on line {}", e, line_no);
+                    log.units = TelemetryUnits {
+                        angle: angle.parse().map_err(unit_error)?,
+                        angular_velocity: angular_velocity.parse().map_err(unit_error)?,
+                        length: Some(length.parse().map_err(unit_error)?),
+                    };
+                }
+                Some("step") => {
+                    let values: Vec<f64> = fields
+                        .map(|s| s.parse::<f64>().map_err(|_| format!("Invalid number '{}' on line {}", s, line_no)))
+                        .collect::<Result<_, _>>()?;
+                    let expected = 9 + 2 * log.num_joints;
+                    if values.len() != expected {
+                        return Err(format!("Step on line {} needs {} values, found {}", line_no, expected, values.len()));
+                    }
+                    // Replay turns times into durations, so they must be usable as such
+                    let time = values[0];
+                    if !time.is_finite() || time < 0.0 {
+                        return Err(format!("Invalid time {} on line {}", time, line_no));
+                    }
+                    if time < log.duration() {
+                        return Err(format!("Time goes backwards on line {} ({} after {})", line_no, time, log.duration()));
+                    }
+                    let (pos, vel) = values[9..].split_at(log.num_joints);
+                    log.records.push(TelemetryRecord {
+                        time,
+                        arm: values[1] as usize,
+                        dt: values[2],
+                        task_vel: values[3..9].try_into().unwrap(),
+                        joint_pos: pos.to_vec(),
+                        joint_vel: vel.to_vec(),
+                    });
+                }
+                Some(other) => return Err(format!("Unknown record '{}' on line {}", other, line_no)),
+                None => {}
+            }
+        }
+        Ok(log)
+    }
+
+    /// Time of the last record (s).
+    pub fn duration(&self) -> f64 {
+        self.records.last().map_or(0.0, |r| r.time)
+    }
+}
@@ -0,0 +1,108 @@
+use std::fmt::Write;
+
+use crate::dh::Pose;
+
+/// Size and styling of a rendered arm preview.
+#[derive(Debug, Clone)]
+pub struct ThumbnailStyle {
+    /// Size in pixels of each of the two square views (front and top).
+    pub view_size: u32,
+    /// Fraction of each view left empty around the arm.
+    pub margin: f64,
+    pub background: &'static str,
+    pub link_color: &'static str,
+    pub joint_color: &'static str,
+    pub end_effector_color: &'static str,
+}
+
+impl Default for ThumbnailStyle {
+    fn default() -> Self {
+        Self {
+            view_size: 96,
+            margin: 0.1,
+            background: "#202020",
+            link_color: "#4a90e2",
+            joint_color: "#e24a4a",
+            end_effector_color: "#f5d033",
+        }
+    }
+}
+
+/// Renders a small SVG preview of an arm configuration from its frame poses.
+///
+/// The chain is drawn from the base origin through every frame position as a
+/// stick figure, side by side in a front view (X right, Z up) and a top view
+/// (X right, Y up). Both views share one scale so link lengths are comparable.
+pub fn render_svg(poses: &[Pose], style: &ThumbnailStyle) -> String {
+    // Base origin followed by every frame origin
+    let mut points: Vec<[f64; 3]> = vec![[0.0, 0.0, 0.0]];
+    points.extend(poses.iter().map(|p| [p.position.x, p.position.y, p.position.z]));
+
+    let size = style.view_size as f64;
+    let front: Vec<(f64, f64)> = points.iter().map(|p| (p[0], p[2])).collect();
+    let top: Vec<(f64, f64)> = points.iter().map(|p| (p[0], p[1])).collect();
+
+    // Common scale: largest extent over both views
+    let extent = |pts: &[(f64, f64)]| {
+        let (min_u, max_u) = min_max(pts.iter().map(|p| p.0));
+        let (min_v, max_v) = min_max(pts.iter().map(|p| p.1));
+        ((max_u - min_u).max(max_v - min_v), (min_u + max_u) / 2.0, (min_v + max_v) / 2.0)
+    };
+    let (front_span, front_cu, front_cv) = extent(&front);
+    let (top_span, top_cu, top_cv) = extent(&top);
+    let span = front_span.max(top_span).max(1e-9);
+    let scale = size * (1.0 - 2.0 * style.margin) / span;
+
+    let mut svg = String::new();
+    let _ = write!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n\
+         <rect width=\"{w}\" height=\"{h}\" fill=\"{bg}\"/>\n",
+        w = 2 * style.view_size,
+        h = style.view_size,
+        bg = style.background,
+    );
+
+    for (offset_x, pts, cu, cv) in [(0.0, &front, front_cu, front_cv), (size, &top, top_cu, top_cv)] {
+        // Map model coordinates to pixels; SVG y grows downwards
+        let to_px = |(u, v): (f64, f64)| {
+            (offset_x + size / 2.0 + (u - cu) * scale, size / 2.0 - (v - cv) * scale)
+        };
+
+        let path: Vec<String> = pts.iter()
+            .map(|&p| {
+                let (x, y) = to_px(p);
+                format!("{:.1},{:.1}", x, y)
+            })
+            .collect();
+        let _ = writeln!(
+            svg,
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>",
+            path.join(" "),
+            style.link_color
+        );
+
+        for (i, &p) in pts.iter().enumerate() {
+            let (x, y) = to_px(p);
+            let (color, radius) = if i == pts.len() - 1 {
+                (style.end_effector_color, 3.0)
+            } else {
+                (style.joint_color, 2.0)
+            };
+            let _ = writeln!(svg, "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{}\" fill=\"{}\"/>", x, y, radius, color);
+        }
+    }
+
+    // Divider between the two views
+    let _ = writeln!(
+        svg,
+        "<line x1=\"{s}\" y1=\"0\" x2=\"{s}\" y2=\"{s}\" stroke=\"#555555\" stroke-width=\"1\"/>",
+        s = style.view_size
+    );
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn min_max(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)))
+}
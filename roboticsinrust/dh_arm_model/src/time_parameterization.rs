@@ -0,0 +1,264 @@
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec, vec::Vec};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// Bisection steps when searching the largest feasible path speed at a grid point.
+const BISECTION_STEPS: usize = 60;
+/// Path tangent components below this are treated as zero (the joint is not moving).
+const TANGENT_EPSILON: f64 = 1e-12;
+
+/// Per-joint limits a time parameterization must respect (deg/s and deg/s² for
+/// revolute joints, linear units/s and /s² for prismatic ones).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointMotionLimits<const J: usize> {
+    pub max_velocity: [f64; J],
+    pub max_acceleration: [f64; J],
+}
+
+/// State of a timed path at one instant (deg / linear units, and their rates).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimedSample<const J: usize> {
+    pub time: f64,
+    pub position: [f64; J],
+    pub velocity: [f64; J],
+    pub acceleration: [f64; J],
+}
+
+/// A geometric joint path with a timing attached, densely sampled.
+///
+/// [`TimedPath::time_optimal`] finds the fastest timing that keeps every joint
+/// within its velocity and acceleration limits ("TOPP-lite"): the path is
+/// resampled by arc length `s` in joint space, and the path speed `ṡ` is pushed
+/// as high as the limits allow by the classic numerical integration scheme,
+/// working on `x = ṡ²`:
+///
+/// * the velocity limits and, through the path curvature `q''(s)`, the
+///   acceleration limits bound `x` at each grid point (the maximum velocity curve);
+/// * a backward pass from rest at the end decelerates as hard as allowed,
+///   a forward pass from rest at the start accelerates as hard as allowed, and
+///   the speed is the lower of the two.
+///
+/// Unlike scaling a uniform timing, the arm runs at its limits wherever the path
+/// allows, and only slows down where the curvature or a joint's limit demands it.
+/// Corners of a coarse waypoint list are taken nearly at rest; pass a densely
+/// sampled (e.g. spline) path to keep speed through them.
+#[derive(Debug, Clone)]
+pub struct TimedPath<const J: usize> {
+    samples: Vec<TimedSample<J>>,
+}
+
+impl<const J: usize> TimedPath<J> {
+    /// Time-optimal timing of the polyline through `path` (deg / linear units),
+    /// starting and ending at rest, on a grid of arc length `resolution` in joint space.
+    ///
+    /// Use a resolution no finer than the spacing of the points in `path`: a finer
+    /// grid resolves the kink at every input point as a sharp bend and slows down there.
+    ///
+    /// Fails if `path` is empty, has zero length or a point that is not finite, or if
+    /// a limit or the resolution is not positive and finite.
+    pub fn time_optimal(path: &[[f64; J]], limits: &JointMotionLimits<J>, resolution: f64) -> Result<Self, String> {
+        if path.is_empty() {
+            return Err("Cannot time an empty path".into());
+        }
+        if let Some(k) = path.iter().position(|q| !q.iter().all(|v| v.is_finite())) {
+            return Err(format!("Path point {} is not finite: {:?}", k, path[k]));
+        }
+        let positive = |v: &f64| v.is_finite() && *v > 0.0;
+        if !limits.max_velocity.iter().chain(&limits.max_acceleration).all(positive) {
+            return Err(format!("Joint limits must be positive and finite: {:?}", limits));
+        }
+        if !positive(&resolution) {
+            return Err(format!("Path resolution must be positive and finite, got {}", resolution));
+        }
+
+        let grid = resample(path, resolution);
+        let n = grid.len() - 1;
+        if n == 0 {
+            return Err("Cannot time a path of zero length".into());
+        }
+        let ds = arc_length(path) / n as f64;
+        let (tangent, curvature) = derivatives(&grid, ds);
+        let constraints = |k: usize| PathConstraints { tangent: &tangent[k], curvature: &curvature[k], limits };
+
+        // Maximum velocity curve, then the backward (braking) and forward (accelerating) passes
+        let mut x: Vec<f64> = (0..=n).map(|k| constraints(k).max_speed_squared()).collect();
+        x[n] = 0.0;
+        for k in (0..n).rev() {
+            let next = x[k + 1];
+            let c = constraints(k);
+            // Largest x_k from which braking at the limit still reaches x_{k+1}
+            x[k] = largest_feasible(x[k], |xk| xk + 2.0 * ds * c.acceleration_bounds(xk).0 <= next);
+        }
+        x[0] = 0.0;
+        for k in 0..n {
+            let (_, max_accel) = constraints(k).acceleration_bounds(x[k]);
+            x[k + 1] = x[k + 1].min(x[k] + 2.0 * ds * max_accel).max(0.0);
+        }
+
+        let mut samples = Vec::with_capacity(n + 1);
+        let mut time = 0.0;
+        for k in 0..=n {
+            let speed = x[k].sqrt();
+            let path_accel = if k < n { (x[k + 1] - x[k]) / (2.0 * ds) } else { 0.0 };
+            samples.push(TimedSample {
+                time,
+                position: grid[k],
+                velocity: core::array::from_fn(|i| tangent[k][i] * speed),
+                acceleration: core::array::from_fn(|i| tangent[k][i] * path_accel + curvature[k][i] * x[k]),
+            });
+            if k < n {
+                let mean_speed = 0.5 * (speed + x[k + 1].sqrt());
+                if mean_speed <= 0.0 {
+                    return Err(format!("Path cannot be traversed at grid point {}: no feasible speed", k));
+                }
+                time += ds / mean_speed;
+            }
+        }
+        Ok(Self { samples })
+    }
+
+    pub fn samples(&self) -> &[TimedSample<J>] {
+        &self.samples
+    }
+
+    /// Total time from start to end (s).
+    pub fn duration(&self) -> f64 {
+        self.samples.last().map_or(0.0, |s| s.time)
+    }
+
+    /// State at `time` (s), interpolated linearly between samples and held at the
+    /// ends outside `[0, duration]`.
+    pub fn sample(&self, time: f64) -> TimedSample<J> {
+        let next = self.samples.partition_point(|s| s.time <= time);
+        if next == 0 {
+            return self.samples[0];
+        }
+        if next == self.samples.len() {
+            return self.samples[next - 1];
+        }
+        let (a, b) = (&self.samples[next - 1], &self.samples[next]);
+        let f = (time - a.time) / (b.time - a.time);
+        let lerp = |p: &[f64; J], q: &[f64; J]| core::array::from_fn(|i| p[i] + f * (q[i] - p[i]));
+        TimedSample { time, position: lerp(&a.position, &b.position), velocity: lerp(&a.velocity, &b.velocity), acceleration: a.acceleration }
+    }
+}
+
+/// Joint limits at one grid point, in terms of the path speed `x = ṡ²` and
+/// path acceleration `u = s̈`: `q̇ = q'·ṡ` and `q̈ = q'·u + q''·x`.
+struct PathConstraints<'a, const J: usize> {
+    tangent: &'a [f64; J],
+    curvature: &'a [f64; J],
+    limits: &'a JointMotionLimits<J>,
+}
+
+impl<const J: usize> PathConstraints<'_, J> {
+    /// Range of path accelerations `u` keeping every joint within its acceleration
+    /// limit at path speed `x`; empty (lower above upper) if there is none.
+    fn acceleration_bounds(&self, x: f64) -> (f64, f64) {
+        let (mut lower, mut upper) = (f64::NEG_INFINITY, f64::INFINITY);
+        for i in 0..J {
+            let (b, a, max) = (self.tangent[i], self.curvature[i] * x, self.limits.max_acceleration[i]);
+            if b.abs() < TANGENT_EPSILON {
+                if a.abs() > max {
+                    return (f64::INFINITY, f64::NEG_INFINITY);
+                }
+                continue;
+            }
+            let (l, u) = ((-max - a) / b, (max - a) / b);
+            let (l, u) = if b > 0.0 { (l, u) } else { (u, l) };
+            lower = lower.max(l);
+            upper = upper.min(u);
+        }
+        (lower, upper)
+    }
+
+    /// Largest `x` at which the velocity limits hold and some path acceleration
+    /// satisfies the acceleration limits.
+    fn max_speed_squared(&self) -> f64 {
+        let velocity_bound = (0..J)
+            .filter(|&i| self.tangent[i].abs() >= TANGENT_EPSILON)
+            .map(|i| (self.limits.max_velocity[i] / self.tangent[i].abs()).powi(2))
+            .fold(f64::INFINITY, f64::min);
+        largest_feasible(velocity_bound, |x| {
+            let (lower, upper) = self.acceleration_bounds(x);
+            lower <= upper
+        })
+    }
+}
+
+/// Largest `x` in `[0, upper]` satisfying `feasible`, given that the feasible values
+/// form an interval containing 0 (the constraints are convex in `x`).
+fn largest_feasible(upper: f64, feasible: impl Fn(f64) -> bool) -> f64 {
+    if upper.is_finite() && feasible(upper) {
+        return upper;
+    }
+    // Unbounded from the velocity limits: find a finite infeasible bound first
+    let mut high = if upper.is_finite() { upper } else { 1.0 };
+    while !upper.is_finite() && feasible(high) {
+        high *= 2.0;
+    }
+    let mut low = 0.0;
+    for _ in 0..BISECTION_STEPS {
+        let mid = 0.5 * (low + high);
+        if feasible(mid) { low = mid } else { high = mid }
+    }
+    low
+}
+
+fn distance<const J: usize>(a: &[f64; J], b: &[f64; J]) -> f64 {
+    a.iter().zip(b).map(|(a, b)| (b - a) * (b - a)).sum::<f64>().sqrt()
+}
+
+fn arc_length<const J: usize>(path: &[[f64; J]]) -> f64 {
+    path.windows(2).map(|w| distance(&w[0], &w[1])).sum()
+}
+
+/// Points at equal arc length along the polyline, at most `resolution` apart.
+fn resample<const J: usize>(path: &[[f64; J]], resolution: f64) -> Vec<[f64; J]> {
+    let length = arc_length(path);
+    let n = (length / resolution).ceil() as usize;
+    if n == 0 {
+        return vec![path[0]];
+    }
+    let ds = length / n as f64;
+
+    let mut grid = Vec::with_capacity(n + 1);
+    let (mut segment, mut segment_start) = (0, 0.0);
+    for k in 0..n {
+        let s = k as f64 * ds;
+        // Skip to the segment containing s (zero-length segments are passed over)
+        while segment + 2 < path.len() && segment_start + distance(&path[segment], &path[segment + 1]) <= s {
+            segment_start += distance(&path[segment], &path[segment + 1]);
+            segment += 1;
+        }
+        let (a, b) = (&path[segment], &path[segment + 1]);
+        let len = distance(a, b);
+        let f = if len > 0.0 { ((s - segment_start) / len).min(1.0) } else { 0.0 };
+        grid.push(core::array::from_fn(|i| a[i] + f * (b[i] - a[i])));
+    }
+    grid.push(path[path.len() - 1]);
+    grid
+}
+
+/// Path tangent `q'(s)` and curvature `q''(s)` at each grid point, by finite differences.
+fn derivatives<const J: usize>(grid: &[[f64; J]], ds: f64) -> (Vec<[f64; J]>, Vec<[f64; J]>) {
+    let n = grid.len() - 1;
+    let tangent = (0..=n)
+        .map(|k| {
+            let (lo, hi) = (k.saturating_sub(1), (k + 1).min(n));
+            core::array::from_fn(|i| (grid[hi][i] - grid[lo][i]) / ((hi - lo) as f64 * ds))
+        })
+        .collect();
+    let curvature = (0..=n)
+        .map(|k| {
+            if n < 2 {
+                return [0.0; J];
+            }
+            // The ends take the curvature of their neighbour
+            let k = k.clamp(1, n - 1);
+            core::array::from_fn(|i| (grid[k + 1][i] - 2.0 * grid[k][i] + grid[k - 1][i]) / (ds * ds))
+        })
+        .collect();
+    (tangent, curvature)
+}
@@ -0,0 +1,134 @@
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use crate::joint::Joint;
+
+/// A joint that, at its current velocity, cannot stop before its limit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LimitPrediction {
+    pub joint: usize,
+    /// Travel left to the limit in the direction of motion (deg / linear units).
+    pub remaining: f64,
+    /// Travel needed to brake to rest at the maximum deceleration.
+    pub stopping_distance: f64,
+}
+
+/// Slows joint velocity commands down ahead of the joint limits, so each joint can
+/// still brake to rest at `max_deceleration` before its hard stop.
+///
+/// Without it a command driving a joint into its limit is only caught by
+/// [`Joint::set_position`] clamping the position, after the fact: the model stops
+/// dead while the drive is still commanded at full speed, and the end-effector
+/// leaves the path the controller was following. Here every joint heading towards a
+/// limit is held to the speed from which it can stop in the travel left, one control
+/// period of reaction included, so it decelerates smoothly onto the limit.
+///
+/// With `preserve_direction` (the default) the whole command is scaled by the factor
+/// of the most restricted joint: the end-effector slows down along its path instead of
+/// veering off it, but stops altogether once any joint reaches its limit, until the
+/// command backs that joint off. Without it only the joints heading into a limit are
+/// slowed.
+///
+/// Units match [`crate::dh_arm_model::DHArmModel::set_joint_positions`]: degrees for
+/// revolute joints, linear units for prismatic joints (and their rates).
+#[derive(Debug, Clone)]
+pub struct TravelLimiter<const J: usize> {
+    limit_min: [Option<f64>; J],
+    limit_max: [Option<f64>; J],
+    /// Deceleration each joint can brake with (deg/s² / linear units/s²); must be positive.
+    pub max_deceleration: [f64; J],
+    pub preserve_direction: bool,
+    limiting_joint: Option<usize>,
+}
+
+impl<const J: usize> TravelLimiter<J> {
+    pub fn new(limit_min: [Option<f64>; J], limit_max: [Option<f64>; J], max_deceleration: [f64; J]) -> Self {
+        Self { limit_min, limit_max, max_deceleration, preserve_direction: true, limiting_joint: None }
+    }
+
+    /// A limiter for the limits configured on `joints`, with the same deceleration for
+    /// every joint.
+    pub fn from_joints(joints: &[Joint; J], max_deceleration: f64) -> Self {
+        // Joint limits are stored internally in radians for revolute joints
+        let to_user = |i: usize, limit: Option<f64>| limit.map(|v| joints[i].joint_type.to_user_units(v));
+        Self::new(
+            core::array::from_fn(|i| to_user(i, joints[i].limit_min)),
+            core::array::from_fn(|i| to_user(i, joints[i].limit_max)),
+            [max_deceleration; J],
+        )
+    }
+
+    /// True if any joint has a limit to slow down for.
+    pub fn has_limits(&self) -> bool {
+        self.limit_min.iter().chain(&self.limit_max).any(Option::is_some)
+    }
+
+    /// Joint the last [`TravelLimiter::limit`] slowed down, the most restricted one if
+    /// several; `None` if the command passed unchanged.
+    pub fn limiting_joint(&self) -> Option<usize> {
+        self.limiting_joint
+    }
+
+    /// Travel left from `position` to the limit of `joint` that `velocity` heads
+    /// towards, zero if already past it; `None` if the joint is not moving or has no
+    /// limit on that side.
+    pub fn remaining_travel(&self, joint: usize, position: f64, velocity: f64) -> Option<f64> {
+        if velocity > 0.0 {
+            self.limit_max[joint].map(|max| (max - position).max(0.0))
+        } else if velocity < 0.0 {
+            self.limit_min[joint].map(|min| (position - min).max(0.0))
+        } else {
+            None
+        }
+    }
+
+    /// Travel `joint` needs to brake from `velocity` to rest.
+    pub fn stopping_distance(&self, joint: usize, velocity: f64) -> f64 {
+        velocity * velocity / (2.0 * self.max_deceleration[joint])
+    }
+
+    /// The joint that will overrun its limit by the most if it keeps its current
+    /// velocity until it has to brake, or `None` if every joint can still stop in time.
+    pub fn predict(&self, positions: &[f64; J], velocities: &[f64; J]) -> Option<LimitPrediction> {
+        (0..J)
+            .filter_map(|joint| {
+                let remaining = self.remaining_travel(joint, positions[joint], velocities[joint])?;
+                let stopping_distance = self.stopping_distance(joint, velocities[joint]);
+                (stopping_distance > remaining).then_some(LimitPrediction { joint, remaining, stopping_distance })
+            })
+            .max_by(|a, b| (a.stopping_distance - a.remaining).total_cmp(&(b.stopping_distance - b.remaining)))
+    }
+
+    /// Highest speed of `joint` that moves for one period `dt` and then still stops
+    /// within `remaining`: v·dt + v²/(2·a) <= remaining.
+    fn max_speed(&self, joint: usize, remaining: f64, dt: f64) -> f64 {
+        let a = self.max_deceleration[joint];
+        ((a * dt) * (a * dt) + 2.0 * a * remaining).sqrt() - a * dt
+    }
+
+    /// Limits one control period's velocity command (deg/s / linear units/s), given the
+    /// measured joint positions.
+    pub fn limit(&mut self, positions: &[f64; J], command: &[f64; J], dt: f64) -> [f64; J] {
+        let mut limited = *command;
+        let mut scale = 1.0;
+        self.limiting_joint = None;
+        for i in 0..J {
+            let Some(remaining) = self.remaining_travel(i, positions[i], command[i]) else { continue };
+            let max_speed = self.max_speed(i, remaining, dt.max(0.0)).max(0.0);
+            if command[i].abs() <= max_speed {
+                continue;
+            }
+            let ratio = max_speed / command[i].abs();
+            limited[i] = command[i] * ratio;
+            if ratio < scale {
+                scale = ratio;
+                self.limiting_joint = Some(i);
+            }
+        }
+        if self.preserve_direction {
+            command.map(|v| v * scale)
+        } else {
+            limited
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use core::str::FromStr;
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String};
+
+use crate::joint::{Joint, JointType};
+
+/// Length unit of a DH table (and so of every position, linear velocity and
+/// gravity derived from it). The table itself carries no unit; this records it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LengthUnit {
+    Millimetre,
+    Centimetre,
+    #[default]
+    Metre,
+}
+
+impl LengthUnit {
+    /// How many of this unit make one metre.
+    pub fn per_metre(self) -> f64 {
+        match self {
+            LengthUnit::Millimetre => 1000.0,
+            LengthUnit::Centimetre => 100.0,
+            LengthUnit::Metre => 1.0,
+        }
+    }
+
+    pub fn from_metres(self, metres: f64) -> f64 {
+        metres * self.per_metre()
+    }
+
+    pub fn to_metres(self, value: f64) -> f64 {
+        value / self.per_metre()
+    }
+
+    /// Converts `value` from this unit to `unit`.
+    pub fn convert(self, value: f64, unit: LengthUnit) -> f64 {
+        unit.from_metres(self.to_metres(value))
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            LengthUnit::Millimetre => "mm",
+            LengthUnit::Centimetre => "cm",
+            LengthUnit::Metre => "m",
+        }
+    }
+}
+
+impl FromStr for LengthUnit {
+    type Err = String;
+
+    /// Parses a unit symbol (`mm`, `cm`, `m`), e.g. from a config file.
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "mm" => Ok(LengthUnit::Millimetre),
+            "cm" => Ok(LengthUnit::Centimetre),
+            "m" => Ok(LengthUnit::Metre),
+            _ => Err(format!("Unknown length unit '{}' (expected mm, cm or m)", s)),
+        }
+    }
+}
+
+/// Angle unit at an I/O boundary. The kinematics work in radians; users,
+/// controllers and most drives in degrees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AngleUnit {
+    Degree,
+    #[default]
+    Radian,
+}
+
+impl AngleUnit {
+    pub fn from_radians(self, radians: f64) -> f64 {
+        match self {
+            AngleUnit::Degree => radians.to_degrees(),
+            AngleUnit::Radian => radians,
+        }
+    }
+
+    pub fn to_radians(self, value: f64) -> f64 {
+        match self {
+            AngleUnit::Degree => value.to_radians(),
+            AngleUnit::Radian => value,
+        }
+    }
+
+    /// Converts `value` from this unit to `unit`.
+    pub fn convert(self, value: f64, unit: AngleUnit) -> f64 {
+        unit.from_radians(self.to_radians(value))
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            AngleUnit::Degree => "deg",
+            AngleUnit::Radian => "rad",
+        }
+    }
+}
+
+impl FromStr for AngleUnit {
+    type Err = String;
+
+    /// Parses a unit symbol (`deg`, `rad`).
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "deg" => Ok(AngleUnit::Degree),
+            "rad" => Ok(AngleUnit::Radian),
+            _ => Err(format!("Unknown angle unit '{}' (expected deg or rad)", s)),
+        }
+    }
+}
+
+/// Angular velocity unit at an I/O boundary. Drive datasheets and encoder feedback
+/// often give motor speeds in revolutions per minute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AngularVelocityUnit {
+    DegreesPerSecond,
+    #[default]
+    RadiansPerSecond,
+    RevolutionsPerMinute,
+}
+
+impl AngularVelocityUnit {
+    /// How many of this unit make one rad/s.
+    pub fn per_radian_per_second(self) -> f64 {
+        match self {
+            AngularVelocityUnit::DegreesPerSecond => 180.0 / core::f64::consts::PI,
+            AngularVelocityUnit::RadiansPerSecond => 1.0,
+            AngularVelocityUnit::RevolutionsPerMinute => 60.0 / core::f64::consts::TAU,
+        }
+    }
+
+    pub fn from_radians_per_second(self, rad_per_s: f64) -> f64 {
+        match self {
+            // Exactly as `f64::to_degrees`, so values round-trip with `AngleUnit`
+            AngularVelocityUnit::DegreesPerSecond => rad_per_s.to_degrees(),
+            unit => rad_per_s * unit.per_radian_per_second(),
+        }
+    }
+
+    pub fn to_radians_per_second(self, value: f64) -> f64 {
+        match self {
+            AngularVelocityUnit::DegreesPerSecond => value.to_radians(),
+            unit => value / unit.per_radian_per_second(),
+        }
+    }
+
+    /// Converts `value` from this unit to `unit`.
+    pub fn convert(self, value: f64, unit: AngularVelocityUnit) -> f64 {
+        unit.from_radians_per_second(self.to_radians_per_second(value))
+    }
+
+    pub fn symbol(self) -> &'static str {
+        match self {
+            AngularVelocityUnit::DegreesPerSecond => "deg/s",
+            AngularVelocityUnit::RadiansPerSecond => "rad/s",
+            AngularVelocityUnit::RevolutionsPerMinute => "rpm",
+        }
+    }
+}
+
+impl FromStr for AngularVelocityUnit {
+    type Err = String;
+
+    /// Parses a unit symbol (`deg/s`, `rad/s`, `rpm`).
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "deg/s" => Ok(AngularVelocityUnit::DegreesPerSecond),
+            "rad/s" => Ok(AngularVelocityUnit::RadiansPerSecond),
+            "rpm" => Ok(AngularVelocityUnit::RevolutionsPerMinute),
+            _ => Err(format!("Unknown angular velocity unit '{}' (expected deg/s, rad/s or rpm)", s)),
+        }
+    }
+}
+
+impl JointType {
+    /// Converts a joint value (position, velocity, limit, ...) from the internal
+    /// convention (radians for revolute joints, lengths for prismatic ones) to the
+    /// user-facing one (degrees, lengths).
+    pub fn to_user_units(self, internal: f64) -> f64 {
+        match self {
+            JointType::Revolute => AngleUnit::Degree.from_radians(internal),
+            JointType::Prismatic => internal,
+        }
+    }
+
+    /// Inverse of [`JointType::to_user_units`].
+    pub fn from_user_units(self, user: f64) -> f64 {
+        match self {
+            JointType::Revolute => AngleUnit::Degree.to_radians(user),
+            JointType::Prismatic => user,
+        }
+    }
+}
+
+/// Positions of all `J` joints of an arm, with the angle unit fixed at construction.
+///
+/// The crate mixes units at its boundaries: [`DHArmModel::set_joint_positions`] and
+/// the controllers work in degrees, while [`DHArmModel::joint_positions`], the
+/// [`Joint`] state and IK solutions are in radians. Passing a `JointAngles` instead
+/// of a bare array makes the caller say which one they have.
+///
+/// Stored in radians. Prismatic joints have no angle: [`JointAngles::from_degrees`] and
+/// [`JointAngles::degrees`] convert every entry, so on arms with prismatic joints use
+/// [`JointAngles::from_user_units`] / [`JointAngles::to_user_units`], which leave
+/// prismatic lengths as they are.
+///
+/// [`DHArmModel::set_joint_positions`]: crate::dh_arm_model::DHArmModel::set_joint_positions
+/// [`DHArmModel::joint_positions`]: crate::dh_arm_model::DHArmModel::joint_positions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointAngles<const J: usize>([f64; J]);
+
+impl<const J: usize> JointAngles<J> {
+    pub fn from_radians(radians: [f64; J]) -> Self {
+        Self(radians)
+    }
+
+    pub fn from_degrees(degrees: [f64; J]) -> Self {
+        Self(degrees.map(f64::to_radians))
+    }
+
+    /// From the crate's user-facing convention: degrees for revolute joints, lengths
+    /// for prismatic ones (what `set_joint_positions` and the controllers take).
+    pub fn from_user_units(values: &[f64; J], joints: &[Joint; J]) -> Self {
+        Self(core::array::from_fn(|i| joints[i].joint_type.from_user_units(values[i])))
+    }
+
+    pub fn radians(&self) -> [f64; J] {
+        self.0
+    }
+
+    pub fn degrees(&self) -> [f64; J] {
+        self.0.map(f64::to_degrees)
+    }
+
+    /// In the crate's user-facing convention (see [`JointAngles::from_user_units`]).
+    pub fn to_user_units(&self, joints: &[Joint; J]) -> [f64; J] {
+        core::array::from_fn(|i| joints[i].joint_type.to_user_units(self.0[i]))
+    }
+}
@@ -0,0 +1,187 @@
+use crate::dh::{DHRow, DHTable, FrameType};
+use crate::dh_arm_model::DHArmModel;
+use crate::inverse_kinematics_solvers::UrtIkSolver;
+use crate::joint::{Joint, JointType};
+use crate::units::LengthUnit;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+use nalgebra::{Matrix4, SMatrix};
+
+/// Number of frames in the URT arm's DH table (6 joints + end-effector).
+pub const NUM_FRAMES: usize = 7;
+/// Number of movable joints on the URT arm.
+pub const NUM_JOINTS: usize = 6;
+
+/// The URT 6-DOF arm model with its closed-form IK solver.
+pub type UrtArm = DHArmModel<NUM_FRAMES, NUM_JOINTS, UrtIkSolver>;
+
+/// Link parameters for [`UrtIkSolver`]: [l1, l2, l3, l4, l5] in cm, taken from the DH table.
+pub const URT_IK_LINK_PARAMETERS: [f64; 5] = [
+    BASE_HEIGHT,     // l1
+    UPPER_ARM,       // l2
+    FOREARM,         // l3
+    WRIST_TO_FLANGE, // l4
+    FLANGE_TO_TOOL,  // l5
+];
+
+// Link lengths of the DH table below (cm).
+const BASE_HEIGHT: f64 = 9.0;
+const UPPER_ARM: f64 = 24.0;
+const FOREARM: f64 = 22.0;
+const WRIST_TO_FLANGE: f64 = 15.0;
+const FLANGE_TO_TOOL: f64 = 15.0;
+
+/// DH table of the URT arm (lengths in cm, angles in degrees).
+pub fn urt_dh_table() -> DHTable<NUM_FRAMES, NUM_JOINTS> {
+    DHTable::new([
+        DHRow::from_frame(0.0, 0.0, BASE_HEIGHT, 0.0, FrameType::Joint(0)),
+        DHRow::from_frame(0.0, -90.0, 0.0, -90.0, FrameType::Joint(1)),
+        DHRow::from_frame(UPPER_ARM, 0.0, 0.0, 90.0, FrameType::Joint(2)),
+        DHRow::from_frame(0.0, 90.0, FOREARM, 0.0, FrameType::Joint(3)),
+        DHRow::from_frame(0.0, -90.0, 0.0, 0.0, FrameType::Joint(4)),
+        DHRow::from_frame(0.0, 90.0, WRIST_TO_FLANGE, 0.0, FrameType::Joint(5)),
+        // End-effector fixed frame (no joint)
+        DHRow::from_frame(0.0, 0.0, FLANGE_TO_TOOL, 0.0, FrameType::Fixed),
+    ])
+}
+
+/// Closed-form geometric Jacobian of the URT arm at joint positions `q` (radians).
+///
+/// Symbolically derived from [`urt_dh_table`], so it matches
+/// [`DHTable::jacobian_from_poses`](crate::dh::DHTable::jacobian_from_poses) on that
+/// table (linear part in cm/rad, angular part in rad/rad) without walking the chain.
+pub fn urt_jacobian(q: &[f64; NUM_JOINTS]) -> SMatrix<f64, 6, NUM_JOINTS> {
+    let trig: [(f64, f64); 5] = core::array::from_fn(|i| q[i].sin_cos());
+    jacobian_from_trig(trig)
+}
+
+/// [`urt_jacobian`] evaluated from the per-row transforms of an FK cache
+/// ([`FkCache::row_transforms`](crate::dh::FkCache::row_transforms)), reusing the
+/// joint sines and cosines already computed there. This is what [`urt_arm`] uses.
+pub fn urt_jacobian_from_rows(rows: &[Matrix4<f64>; NUM_FRAMES]) -> SMatrix<f64, 6, NUM_JOINTS> {
+    // Row i holds cos / -sin of (theta offset + q_i) in its first row
+    let c = |i: usize| rows[i][(0, 0)];
+    let s = |i: usize| -rows[i][(0, 1)];
+    jacobian_from_trig([
+        (s(0), c(0)),
+        (c(1), -s(1)), // theta offset -90
+        (-c(2), s(2)), // theta offset +90
+        (s(3), c(3)),
+        (s(4), c(4)),
+    ])
+}
+
+/// The symbolic Jacobian, from (sin, cos) of joints 1-5. Joint 6 spins about the
+/// tool axis, so it never moves the tool point and does not appear.
+#[inline]
+fn jacobian_from_trig(trig: [(f64, f64); 5]) -> SMatrix<f64, 6, NUM_JOINTS> {
+    let [(s1, c1), (s2, c2), (s3, c3), (s4, c4), (s5, c5)] = trig;
+    let (s23, c23) = (s2 * c3 + c2 * s3, c2 * c3 - s2 * s3);
+    let tool = WRIST_TO_FLANGE + FLANGE_TO_TOOL;
+
+    // Tool axis (z of joint 6): components along the arm plane (radial, vertical) and across it
+    let tool_radial = c23 * c4 * s5 + s23 * c5;
+    let tool_vertical = c23 * c5 - s23 * c4 * s5;
+    let tool_lateral = s4 * s5;
+    // Its derivative w.r.t. joint 5, split the same way
+    let tool_d5_radial = c23 * c4 * c5 - s23 * s5;
+    let tool_d5_vertical = -(s23 * c4 * c5 + c23 * s5);
+    let tool_d5_lateral = s4 * c5;
+
+    // Tool point relative to the elbow (joint 3) and shoulder (joint 2), in the arm plane
+    let elbow_reach = FOREARM * s23 + tool * tool_radial;
+    let elbow_height = FOREARM * c23 + tool * tool_vertical;
+    let shoulder_reach = UPPER_ARM * s2 + elbow_reach;
+    let shoulder_height = UPPER_ARM * c2 + elbow_height;
+    let lateral = tool * tool_lateral;
+
+    // Joint axes 4-6 in the base frame (1 is base z, 2 and 3 are (-s1, c1, 0))
+    let z4 = [c1 * s23, s1 * s23, c23];
+    let z5 = [-(c1 * c23 * s4 + s1 * c4), c1 * c4 - s1 * c23 * s4, s23 * s4];
+    let z6 = [
+        c1 * tool_radial - s1 * tool_lateral,
+        s1 * tool_radial + c1 * tool_lateral,
+        tool_vertical,
+    ];
+
+    SMatrix::from([
+        // joint 1
+        [
+            -(s1 * shoulder_reach + c1 * lateral),
+            c1 * shoulder_reach - s1 * lateral,
+            0.0,
+            0.0,
+            0.0,
+            1.0,
+        ],
+        // joint 2
+        [
+            c1 * shoulder_height,
+            s1 * shoulder_height,
+            -shoulder_reach,
+            -s1,
+            c1,
+            0.0,
+        ],
+        // joint 3
+        [
+            c1 * elbow_height,
+            s1 * elbow_height,
+            -elbow_reach,
+            -s1,
+            c1,
+            0.0,
+        ],
+        // joint 4: the tool point is `tool` along z6 from the wrist centre
+        [
+            tool * s5 * z5[0],
+            tool * s5 * z5[1],
+            tool * s5 * z5[2],
+            z4[0],
+            z4[1],
+            z4[2],
+        ],
+        // joint 5
+        [
+            tool * (c1 * tool_d5_radial - s1 * tool_d5_lateral),
+            tool * (s1 * tool_d5_radial + c1 * tool_d5_lateral),
+            tool * tool_d5_vertical,
+            z5[0],
+            z5[1],
+            z5[2],
+        ],
+        // joint 6
+        [
+            0.0,
+            0.0,
+            0.0,
+            z6[0],
+            z6[1],
+            z6[2],
+        ],
+    ])
+}
+
+/// The URT arm's joints: six unlimited revolute joints.
+pub fn urt_joints() -> [Joint; NUM_JOINTS] {
+    core::array::from_fn(|_| Joint::new(JointType::Revolute, None, None))
+}
+
+/// Builds the URT arm model with the given damping (defaults to 1e-4), using the
+/// closed-form Jacobian ([`urt_jacobian_from_rows`]).
+pub fn urt_arm(damping: Option<f64>) -> UrtArm {
+    let mut arm = DHArmModel::new(
+        urt_dh_table(),
+        urt_joints(),
+        damping,
+        UrtIkSolver,
+        Vec::from(URT_IK_LINK_PARAMETERS),
+    );
+    arm.set_analytic_jacobian(Some(urt_jacobian_from_rows));
+    arm.set_length_unit(LengthUnit::Centimetre);
+    arm
+}
@@ -0,0 +1,154 @@
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String};
+#[cfg(not(feature = "std"))]
+use num_traits::Float;
+
+/// Smooths joint velocity commands before they reach the drives.
+///
+/// Step changes in the commanded task velocity (a key pressed or released, a joystick
+/// snapped back) come out of `TaskSpacePidController` and the other velocity
+/// controllers as steps in joint velocity, which the servos follow as hard as they
+/// can and the gearboxes rattle. Each stage below is optional and applied in order:
+/// * a first-order low-pass with time constant `time_constant` (s),
+/// * a slew-rate limit: at most `max_acceleration` per joint (deg/s² or linear units/s²),
+/// * a jerk limit: the acceleration changes by at most `max_jerk` per joint
+///   (deg/s³ or linear units/s³), and is ramped down in time to reach the commanded
+///   velocity without overshooting it.
+///
+/// Every stage delays the response, including stopping: with limits, a released jog
+/// coasts to rest over `v / max_acceleration` seconds instead of stopping at once.
+///
+/// The settings are checked when they are set, so a bad value is an error there
+/// rather than a panic in the control loop.
+#[derive(Debug, Clone)]
+pub struct VelocityFilter<const J: usize> {
+    time_constant: Option<f64>,
+    max_acceleration: Option<[f64; J]>,
+    max_jerk: Option<[f64; J]>,
+    velocity: [f64; J],
+    acceleration: [f64; J],
+}
+
+impl<const J: usize> VelocityFilter<J> {
+    /// A filter with every stage disabled, which passes commands through unchanged.
+    pub fn new() -> Self {
+        Self {
+            time_constant: None,
+            max_acceleration: None,
+            max_jerk: None,
+            velocity: [0.0; J],
+            acceleration: [0.0; J],
+        }
+    }
+
+    /// First-order low-pass with time constant `time_constant` (s).
+    pub fn low_pass(time_constant: f64) -> Result<Self, String> {
+        let mut filter = Self::new();
+        filter.set_time_constant(Some(time_constant))?;
+        Ok(filter)
+    }
+
+    /// Slew-rate limiter with the same acceleration limit for every joint, plus a jerk
+    /// limit if `max_jerk` is given.
+    pub fn slew_rate(max_acceleration: f64, max_jerk: Option<f64>) -> Result<Self, String> {
+        let mut filter = Self::new();
+        filter.set_max_acceleration(Some([max_acceleration; J]))?;
+        filter.set_max_jerk(max_jerk.map(|jerk| [jerk; J]))?;
+        Ok(filter)
+    }
+
+    pub fn time_constant(&self) -> Option<f64> {
+        self.time_constant
+    }
+
+    /// Sets the low-pass time constant (s), non-negative; `None` disables the stage.
+    pub fn set_time_constant(&mut self, time_constant: Option<f64>) -> Result<(), String> {
+        if let Some(tau) = time_constant.filter(|tau| !(tau.is_finite() && *tau >= 0.0)) {
+            return Err(format!("time_constant must be a non-negative number, got {}", tau));
+        }
+        self.time_constant = time_constant;
+        Ok(())
+    }
+
+    pub fn max_acceleration(&self) -> Option<&[f64; J]> {
+        self.max_acceleration.as_ref()
+    }
+
+    /// Sets the per-joint acceleration limits, each positive; `None` disables the stage.
+    pub fn set_max_acceleration(&mut self, max_acceleration: Option<[f64; J]>) -> Result<(), String> {
+        check_limits("max_acceleration", max_acceleration.as_ref())?;
+        self.max_acceleration = max_acceleration;
+        Ok(())
+    }
+
+    pub fn max_jerk(&self) -> Option<&[f64; J]> {
+        self.max_jerk.as_ref()
+    }
+
+    /// Sets the per-joint jerk limits, each positive; `None` disables the stage.
+    pub fn set_max_jerk(&mut self, max_jerk: Option<[f64; J]>) -> Result<(), String> {
+        check_limits("max_jerk", max_jerk.as_ref())?;
+        self.max_jerk = max_jerk;
+        Ok(())
+    }
+
+    /// Restarts from `velocities` at rest acceleration, e.g. the drives' measured
+    /// velocities when the filter is switched in, or another source took over.
+    pub fn reset(&mut self, velocities: &[f64; J]) {
+        self.velocity = *velocities;
+        self.acceleration = [0.0; J];
+    }
+
+    /// Last filtered command.
+    pub fn velocity(&self) -> &[f64; J] {
+        &self.velocity
+    }
+
+    /// Filters one control period's velocity command (deg/s / linear units/s).
+    pub fn filter(&mut self, command: &[f64; J], dt: f64) -> [f64; J] {
+        if dt <= 0.0 {
+            return self.velocity;
+        }
+        for i in 0..J {
+            let v = self.velocity[i];
+            let target = match self.time_constant {
+                Some(tau) => v + (command[i] - v) * dt / (tau + dt),
+                None => command[i],
+            };
+
+            let error = target - v;
+            let mut a = error / dt;
+            if let Some(max) = self.max_acceleration {
+                a = a.max(-max[i]).min(max[i]);
+            }
+            if let Some(max) = self.max_jerk {
+                // Largest acceleration that can still be ramped down to zero, one jerk step
+                // per period, by the time the velocity reaches the target:
+                // a·dt + a²/(2·jerk) + a·dt/2 <= |error|
+                let jerk_dt = max[i] * dt;
+                let braking = ((2.25 * jerk_dt * jerk_dt + 2.0 * max[i] * error.abs()).sqrt() - 1.5 * jerk_dt).max(0.0);
+                let previous = self.acceleration[i];
+                // max/min rather than clamp: a NaN command must not panic the control loop
+                a = a.max(-braking).min(braking).max(previous - max[i] * dt).min(previous + max[i] * dt);
+            }
+
+            self.acceleration[i] = a;
+            self.velocity[i] = v + a * dt;
+        }
+        self.velocity
+    }
+}
+
+impl<const J: usize> Default for VelocityFilter<J> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Fails unless every entry of `limits` is a positive number.
+fn check_limits<const J: usize>(name: &str, limits: Option<&[f64; J]>) -> Result<(), String> {
+    match limits.into_iter().flatten().find(|v| !(v.is_finite() && **v > 0.0)) {
+        Some(v) => Err(format!("{} must be positive, got {}", name, v)),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,97 @@
+//! Hand-eye calibration round trip: views of a calibration target synthesized
+//! from a known camera mount must give that mount back.
+
+use dh_arm_model::dh::Pose;
+use dh_arm_model::hand_eye::{HandEyeSample, HandEyeSetup};
+use dh_arm_model::urt;
+
+/// Frame after the last joint, which the camera or the target is mounted on.
+const WRIST: usize = 6;
+const POSITION_TOLERANCE: f64 = 1e-6;
+const ROTATION_TOLERANCE: f64 = 1e-6;
+
+/// Arm configurations (deg) turning the wrist about several different axes.
+const CONFIGURATIONS: [[f64; 6]; 6] = [
+    [0.0, 10.0, 20.0, 0.0, 30.0, 0.0],
+    [15.0, -10.0, 30.0, 40.0, -20.0, 10.0],
+    [-20.0, 20.0, 10.0, -30.0, 45.0, 60.0],
+    [30.0, 0.0, -15.0, 60.0, 10.0, -40.0],
+    [-10.0, 30.0, 25.0, -60.0, -30.0, 20.0],
+    [5.0, -20.0, 40.0, 20.0, 60.0, -70.0],
+];
+
+fn pose(x: f64, y: f64, z: f64, yaw_deg: f64, pitch_deg: f64, roll_deg: f64) -> Pose {
+    Pose::from_components(x, y, z, yaw_deg.to_radians(), pitch_deg.to_radians(), roll_deg.to_radians())
+}
+
+fn assert_same_pose(actual: &Pose, expected: &Pose) {
+    let position_error = (actual.position - expected.position).norm();
+    let rotation_error = actual.rotation_angle_to(expected);
+    assert!(position_error < POSITION_TOLERANCE, "position off by {}: {:?}", position_error, actual.position);
+    assert!(rotation_error < ROTATION_TOLERANCE, "rotation off by {} rad", rotation_error);
+}
+
+#[test]
+fn eye_in_hand_recovers_the_camera_mount() {
+    let mut arm = urt::urt_arm(None);
+    let camera_in_wrist = pose(3.0, -2.0, 5.0, 20.0, -10.0, 30.0);
+    let target_in_base = pose(45.0, 10.0, 0.0, 90.0, 0.0, 180.0);
+
+    let samples: Vec<HandEyeSample<6>> = CONFIGURATIONS
+        .iter()
+        .map(|q| {
+            arm.set_joint_positions(q);
+            let camera = arm.frame_pose(WRIST).compose(&camera_in_wrist);
+            HandEyeSample { joint_positions: *q, target_in_camera: camera.inverse().compose(&target_in_base) }
+        })
+        .collect();
+
+    let (mount, solution) = arm.calibrate_camera(HandEyeSetup::EyeInHand { frame: WRIST }, &samples).unwrap();
+    assert_eq!(mount.frame, Some(WRIST));
+    assert_same_pose(&mount.camera_in_frame, &camera_in_wrist);
+    assert!(solution.rotation_residual < ROTATION_TOLERANCE);
+    assert!(solution.translation_residual < POSITION_TOLERANCE);
+
+    // The recovered mount maps the target back to where it is
+    let detected = arm.camera_to_base(&mount, &samples[5].target_in_camera);
+    assert_same_pose(&detected, &target_in_base);
+}
+
+#[test]
+fn eye_to_hand_recovers_the_camera_pose() {
+    let mut arm = urt::urt_arm(None);
+    let camera_in_base = pose(60.0, -30.0, 50.0, 150.0, 35.0, -170.0);
+    let target_in_wrist = pose(0.0, 4.0, 8.0, 0.0, 15.0, 5.0);
+
+    let samples: Vec<HandEyeSample<6>> = CONFIGURATIONS
+        .iter()
+        .map(|q| {
+            arm.set_joint_positions(q);
+            let target = arm.frame_pose(WRIST).compose(&target_in_wrist);
+            HandEyeSample { joint_positions: *q, target_in_camera: camera_in_base.inverse().compose(&target) }
+        })
+        .collect();
+
+    let (mount, _) = arm.calibrate_camera(HandEyeSetup::EyeToHand { frame: WRIST }, &samples).unwrap();
+    assert_eq!(mount.frame, None);
+    assert_same_pose(&mount.camera_in_frame, &camera_in_base);
+}
+
+#[test]
+fn rotations_about_one_axis_are_rejected() {
+    let mut arm = urt::urt_arm(None);
+    let camera_in_wrist = pose(3.0, -2.0, 5.0, 20.0, -10.0, 30.0);
+    let target_in_base = pose(45.0, 10.0, 0.0, 90.0, 0.0, 180.0);
+    // Only the wrist roll changes, so every motion turns about the same axis
+    let samples: Vec<HandEyeSample<6>> = [-40.0, 0.0, 30.0, 70.0]
+        .iter()
+        .map(|roll| {
+            let q = [10.0, 20.0, 30.0, 0.0, 40.0, *roll];
+            arm.set_joint_positions(&q);
+            let camera = arm.frame_pose(WRIST).compose(&camera_in_wrist);
+            HandEyeSample { joint_positions: q, target_in_camera: camera.inverse().compose(&target_in_base) }
+        })
+        .collect();
+
+    assert!(arm.calibrate_camera(HandEyeSetup::EyeInHand { frame: WRIST }, &samples).is_err());
+}
@@ -0,0 +1,194 @@
+//! Invariants and golden values for the kinematics of the URT arm: forward
+//! kinematics, Jacobians, the damped pseudo-inverse and the closed-form IK.
+//!
+//! Properties are checked with `proptest` over random joint configurations; the
+//! golden values are hand-derived from the DH table in `urt.rs` (lengths in cm).
+
+use nalgebra::{Matrix3, SMatrix, SVector, Vector3};
+use proptest::prelude::*;
+
+use dh_arm_model::dh::Pose;
+use dh_arm_model::joint::Joint;
+use dh_arm_model::urt::{self, NUM_JOINTS};
+
+/// Step for central differences (rad).
+const DIFF_STEP: f64 = 1e-6;
+
+/// URT joints at `q` (radians).
+fn joints_at(q: &[f64; NUM_JOINTS]) -> [Joint; NUM_JOINTS] {
+    let mut joints = urt::urt_joints();
+    for (joint, &pos) in joints.iter_mut().zip(q) {
+        joint.set_position(pos.to_degrees());
+    }
+    joints
+}
+
+/// Tool pose at `q` (radians), walking the DH table directly.
+fn tool_pose(q: &[f64; NUM_JOINTS]) -> Pose {
+    let [.., tool] = urt::urt_dh_table().all_poses(&joints_at(q));
+    tool
+}
+
+/// Jacobian by central differences of [`tool_pose`]: linear part from the position,
+/// angular part from the skew-symmetric dR/dq · Rᵀ.
+fn numerical_jacobian(q: &[f64; NUM_JOINTS]) -> SMatrix<f64, 6, NUM_JOINTS> {
+    let rotation = tool_pose(q).rotation;
+    let mut jacobian = SMatrix::<f64, 6, NUM_JOINTS>::zeros();
+    for i in 0..NUM_JOINTS {
+        let (mut plus, mut minus) = (*q, *q);
+        plus[i] += DIFF_STEP;
+        minus[i] -= DIFF_STEP;
+        let (p, m) = (tool_pose(&plus), tool_pose(&minus));
+
+        let dp = (p.position - m.position) / (2.0 * DIFF_STEP);
+        let w = (p.rotation - m.rotation) / (2.0 * DIFF_STEP) * rotation.transpose();
+        jacobian.fixed_slice_mut::<3, 1>(0, i).copy_from(&dp);
+        jacobian.fixed_slice_mut::<3, 1>(3, i).copy_from(&Vector3::new(w[(2, 1)], w[(0, 2)], w[(1, 0)]));
+    }
+    jacobian
+}
+
+fn assert_orthonormal(rotation: &Matrix3<f64>) {
+    let error = (rotation.transpose() * rotation - Matrix3::identity()).abs().max();
+    assert!(error < 1e-12, "RᵀR deviates from I by {}", error);
+    let det = rotation.determinant();
+    assert!((det - 1.0).abs() < 1e-12, "det R = {}", det);
+}
+
+fn assert_vec_eq(actual: &Vector3<f64>, expected: [f64; 3]) {
+    let expected = Vector3::from(expected);
+    assert!((actual - expected).norm() < 1e-9, "expected {:?}, got {:?}", expected, actual);
+}
+
+/// Any configuration of the arm (radians).
+fn any_configuration() -> impl Strategy<Value = [f64; NUM_JOINTS]> {
+    prop::array::uniform6(-std::f64::consts::PI..std::f64::consts::PI)
+}
+
+/// Configurations the closed-form IK recovers uniquely: elbow and wrist kept clear
+/// of their straight (singular) positions, shoulder and base within a half turn.
+fn ik_configuration() -> impl Strategy<Value = [f64; NUM_JOINTS]> {
+    let away_from_zero = |min: f64, max: f64| {
+        prop_oneof![(-max.to_radians()..-min.to_radians()), (min.to_radians()..max.to_radians())]
+    };
+    (
+        (-170f64..170.0).prop_map(f64::to_radians),
+        (-80f64..80.0).prop_map(f64::to_radians),
+        away_from_zero(10.0, 150.0),
+        (-170f64..170.0).prop_map(f64::to_radians),
+        away_from_zero(10.0, 150.0),
+        (-170f64..170.0).prop_map(f64::to_radians),
+    )
+        .prop_map(|(q1, q2, q3, q4, q5, q6)| [q1, q2, q3, q4, q5, q6])
+}
+
+proptest! {
+    #[test]
+    fn fk_of_ik_reproduces_target(q in ik_configuration()) {
+        let arm = urt::urt_arm(None);
+        let target = tool_pose(&q);
+        let solution = arm.solve_ik_from_pose(&target).expect("reachable target");
+
+        let reached = tool_pose(&solution);
+        let position_error = (reached.position - target.position).norm();
+        prop_assert!(position_error < 1e-6, "position error {} cm", position_error);
+        let orientation_error = reached.rotation_angle_to(&target);
+        prop_assert!(orientation_error < 1e-6, "orientation error {} rad", orientation_error);
+    }
+
+    #[test]
+    fn jacobians_match_numerical_differentiation(q in any_configuration()) {
+        let numerical = numerical_jacobian(&q);
+        let generic = urt::urt_dh_table().compute_jacobian(&joints_at(&q));
+        let analytic = urt::urt_jacobian(&q);
+
+        let generic_error = (generic - numerical).abs().max();
+        prop_assert!(generic_error < 1e-6, "generic Jacobian off by {}", generic_error);
+        let analytic_error = (analytic - numerical).abs().max();
+        prop_assert!(analytic_error < 1e-6, "closed-form Jacobian off by {}", analytic_error);
+    }
+
+    #[test]
+    fn frame_rotations_stay_orthonormal(q in any_configuration()) {
+        for pose in urt::urt_dh_table().all_poses(&joints_at(&q)) {
+            assert_orthonormal(&pose.rotation);
+        }
+    }
+
+    #[test]
+    fn cached_fk_stays_orthonormal_over_many_updates(
+        steps in prop::collection::vec(prop::array::uniform6(-5.0f64..5.0), 200),
+    ) {
+        // Incremental cache refreshes must not accumulate drift
+        let mut arm = urt::urt_arm(None);
+        let mut q_deg = [0.0; NUM_JOINTS];
+        for step in &steps {
+            for (q, dq) in q_deg.iter_mut().zip(step) {
+                *q += dq;
+            }
+            arm.set_joint_positions(&q_deg);
+        }
+        for pose in arm.frame_poses() {
+            assert_orthonormal(&pose.rotation);
+        }
+        let fresh = tool_pose(&q_deg.map(f64::to_radians));
+        prop_assert!((arm.end_effector_pose().position - fresh.position).norm() < 1e-9);
+    }
+
+    #[test]
+    fn pseudo_inverse_solves_damped_least_squares(
+        q in any_configuration(),
+        v in prop::array::uniform6(-10.0f64..10.0),
+        lambda in 1e-3f64..1.0,
+    ) {
+        // x = J⁺v minimises |Jx - v|² + λ²|x|², so Jᵀ(Jx - v) + λ²x = 0
+        let table = urt::urt_dh_table();
+        let joints = joints_at(&q);
+        let jacobian = table.compute_jacobian(&joints);
+        let pinv = table.damped_moore_penrose_pseudo_inverse(&joints, Some(&jacobian), Some(lambda));
+
+        let v = SVector::<f64, 6>::from(v);
+        let x = pinv * v;
+        let gradient = jacobian.transpose() * (jacobian * x - v) + lambda * lambda * x;
+        let scale = jacobian.norm().powi(2) * v.norm().max(1.0);
+        prop_assert!(gradient.norm() < 1e-9 * scale, "optimality residual {}", gradient.norm());
+    }
+}
+
+#[test]
+fn golden_tool_poses() {
+    // Home: every link stacked straight up, tool frame aligned with the base
+    let home = tool_pose(&[0.0; NUM_JOINTS]);
+    assert_vec_eq(&home.position, [0.0, 0.0, 85.0]);
+    assert!((home.rotation - Matrix3::identity()).abs().max() < 1e-12);
+
+    // Shoulder at 90°: the arm lies along +x at shoulder height, tool pointing along +x
+    let reach = tool_pose(&[0.0, 90f64.to_radians(), 0.0, 0.0, 0.0, 0.0]);
+    assert_vec_eq(&reach.position, [76.0, 0.0, 9.0]);
+    assert_vec_eq(&reach.z_axis(), [1.0, 0.0, 0.0]);
+
+    // Wrist pitched 90°: wrist centre at 55 cm, flange and tool 30 cm out along +x
+    let wrist = tool_pose(&[0.0, 0.0, 0.0, 0.0, 90f64.to_radians(), 0.0]);
+    assert_vec_eq(&wrist.position, [30.0, 0.0, 55.0]);
+    assert_vec_eq(&wrist.z_axis(), [1.0, 0.0, 0.0]);
+
+    // Base turned to +y, elbow at 90°: upper arm vertical, forearm and tool level along +y
+    let elbow = tool_pose(&[90f64.to_radians(), 0.0, 90f64.to_radians(), 0.0, 0.0, 0.0]);
+    assert_vec_eq(&elbow.position, [0.0, 52.0, 33.0]);
+    assert_vec_eq(&elbow.z_axis(), [0.0, 1.0, 0.0]);
+}
+
+#[test]
+fn golden_home_jacobian() {
+    let jacobian = urt::urt_jacobian(&[0.0; NUM_JOINTS]);
+    let expected = SMatrix::<f64, 6, NUM_JOINTS>::from([
+        [0.0, 0.0, 0.0, 0.0, 0.0, 1.0],  // base: the tool is on its axis
+        [76.0, 0.0, 0.0, 0.0, 1.0, 0.0], // shoulder, 76 cm below the tool
+        [52.0, 0.0, 0.0, 0.0, 1.0, 0.0], // elbow, 52 cm below
+        [0.0, 0.0, 0.0, 0.0, 0.0, 1.0],  // forearm roll about the tool axis
+        [30.0, 0.0, 0.0, 0.0, 1.0, 0.0], // wrist pitch, 30 cm below
+        [0.0, 0.0, 0.0, 0.0, 0.0, 1.0],  // tool roll
+    ]);
+    let error = (jacobian - expected).abs().max();
+    assert!(error < 1e-12, "home Jacobian off by {}:\n{}", error, jacobian);
+}
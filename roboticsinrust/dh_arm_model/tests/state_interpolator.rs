@@ -0,0 +1,71 @@
+//! Dropout detection of the joint state interpolator: an arm standing still must
+//! not look like lost feedback, and repeated packets must not hide a dropout.
+
+use std::time::{Duration, Instant};
+
+use dh_arm_model::hardware::{ArmHardware, CommandMode, JointCommand, JointState};
+use dh_arm_model::state_interpolator::JointStateInterpolator;
+
+const PERIOD: Duration = Duration::from_millis(10);
+
+/// Driver returning the same reading on every call, as a stationary arm does.
+struct StillArm {
+    state: JointState<2>,
+}
+
+impl ArmHardware<2> for StillArm {
+    fn command_mode(&self) -> CommandMode {
+        CommandMode::Velocity
+    }
+
+    fn read_state(&mut self) -> Result<JointState<2>, String> {
+        Ok(self.state)
+    }
+
+    fn write_command(&mut self, _command: &JointCommand<2>) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+fn still_arm() -> StillArm {
+    StillArm { state: JointState { positions: [10.0, -20.0], velocities: [0.0; 2] } }
+}
+
+#[test]
+fn stationary_arm_is_not_stale() {
+    let mut arm = still_arm();
+    let mut interpolator = JointStateInterpolator::new(3 * PERIOD);
+    let start = Instant::now();
+    for cycle in 0..50 {
+        let now = start + cycle * PERIOD;
+        interpolator.poll(&mut arm, now).unwrap();
+        let estimate = interpolator.estimate(now + PERIOD / 2).unwrap();
+        assert!(!estimate.stale, "stale after {} cycles", cycle);
+        assert_eq!(estimate.state.positions, arm.state.positions);
+    }
+}
+
+#[test]
+fn stationary_arm_with_new_sequence_numbers_is_not_stale() {
+    let mut arm = still_arm();
+    let mut interpolator = JointStateInterpolator::new(3 * PERIOD);
+    let start = Instant::now();
+    for cycle in 0..50 {
+        let now = start + cycle * PERIOD;
+        interpolator.poll_stamped(&mut arm, u64::from(cycle), now).unwrap();
+        assert!(!interpolator.estimate(now + PERIOD / 2).unwrap().stale, "stale after {} cycles", cycle);
+    }
+}
+
+#[test]
+fn repeated_sequence_number_goes_stale() {
+    let mut arm = still_arm();
+    let mut interpolator = JointStateInterpolator::new(3 * PERIOD);
+    let start = Instant::now();
+    // The driver keeps returning packet 7 after the feedback stopped
+    for cycle in 0..10 {
+        interpolator.poll_stamped(&mut arm, 7, start + cycle * PERIOD).unwrap();
+    }
+    assert_eq!(interpolator.latest().unwrap().0, start);
+    assert!(interpolator.estimate(start + 10 * PERIOD).unwrap().stale);
+}
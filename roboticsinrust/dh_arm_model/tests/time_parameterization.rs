@@ -0,0 +1,105 @@
+//! Time-optimal timing of joint paths: the limits hold at every sample, the motion
+//! starts and ends at rest, a straight single-joint move takes the analytic
+//! bang-bang / trapezoid time, and degenerate paths are rejected.
+
+use dh_arm_model::time_parameterization::{JointMotionLimits, TimedPath};
+
+/// Slack on the limits for the finite-difference derivatives of the path.
+const LIMIT_TOLERANCE: f64 = 1e-6;
+
+fn limits() -> JointMotionLimits<3> {
+    JointMotionLimits { max_velocity: [90.0, 60.0, 120.0], max_acceleration: [180.0, 90.0, 360.0] }
+}
+
+/// A smooth curve through joint space, densely sampled.
+fn curved_path() -> Vec<[f64; 3]> {
+    (0..=200)
+        .map(|k| {
+            let t = k as f64 / 200.0;
+            [90.0 * t, 30.0 * (std::f64::consts::PI * t).sin(), -45.0 + 60.0 * t * t]
+        })
+        .collect()
+}
+
+/// A coarse polyline with sharp corners.
+fn corner_path() -> Vec<[f64; 3]> {
+    vec![[0.0, 0.0, 0.0], [40.0, 10.0, 0.0], [40.0, 50.0, -20.0], [0.0, 50.0, 30.0]]
+}
+
+fn straight_single_joint(length: f64, max_velocity: f64, max_acceleration: f64) -> TimedPath<1> {
+    let limits = JointMotionLimits { max_velocity: [max_velocity], max_acceleration: [max_acceleration] };
+    TimedPath::time_optimal(&[[0.0], [length]], &limits, length / 2000.0).unwrap()
+}
+
+#[test]
+fn every_sample_respects_the_limits() {
+    let limits = limits();
+    for (path, resolution) in [(curved_path(), 0.5), (corner_path(), 1.0)] {
+        let timed = TimedPath::time_optimal(&path, &limits, resolution).unwrap();
+        for sample in timed.samples() {
+            for i in 0..3 {
+                let (v, a) = (sample.velocity[i], sample.acceleration[i]);
+                assert!(v.abs() <= limits.max_velocity[i] * (1.0 + LIMIT_TOLERANCE), "joint {} at {:.3} s: velocity {}", i, sample.time, v);
+                assert!(a.abs() <= limits.max_acceleration[i] * (1.0 + LIMIT_TOLERANCE), "joint {} at {:.3} s: acceleration {}", i, sample.time, a);
+            }
+        }
+    }
+}
+
+#[test]
+fn starts_and_ends_at_rest() {
+    let timed = TimedPath::time_optimal(&curved_path(), &limits(), 0.5).unwrap();
+    let samples = timed.samples();
+    assert_eq!(samples[0].velocity, [0.0; 3]);
+    assert_eq!(samples[samples.len() - 1].velocity, [0.0; 3]);
+    assert_eq!(samples[0].position, curved_path()[0]);
+    assert_eq!(samples[samples.len() - 1].position, curved_path()[200]);
+    assert!(timed.duration() > 0.0);
+}
+
+#[test]
+fn straight_move_takes_the_trapezoid_time() {
+    // 90 deg at 60 deg/s and 120 deg/s²: 0.5 s ramps, 1 s cruise
+    let (length, v, a) = (90.0, 60.0, 120.0);
+    let expected = length / v + v / a;
+    let timed = straight_single_joint(length, v, a);
+    assert!((timed.duration() - expected).abs() < 1e-2 * expected, "{} s, expected {} s", timed.duration(), expected);
+
+    let cruise = timed.sample(0.5 * expected);
+    assert!((cruise.velocity[0] - v).abs() < 1e-6 * v, "cruising at {} deg/s", cruise.velocity[0]);
+}
+
+#[test]
+fn short_move_takes_the_bang_bang_time() {
+    // Too short to reach 60 deg/s at 120 deg/s²: accelerate, then brake at once
+    let (length, v, a): (f64, f64, f64) = (20.0, 60.0, 120.0);
+    let expected = 2.0 * (length / a).sqrt();
+    let timed = straight_single_joint(length, v, a);
+    assert!((timed.duration() - expected).abs() < 1e-2 * expected, "{} s, expected {} s", timed.duration(), expected);
+
+    let peak = timed.samples().iter().map(|s| s.velocity[0]).fold(0.0, f64::max);
+    assert!((peak - (a * length).sqrt()).abs() < 1e-2 * peak, "peak {} deg/s", peak);
+}
+
+#[test]
+fn degenerate_paths_are_rejected() {
+    let limits = limits();
+    let cases: [(&str, Vec<[f64; 3]>); 5] = [
+        ("empty", vec![]),
+        ("single point", vec![[10.0, 20.0, 30.0]]),
+        ("repeated points", vec![[10.0, 20.0, 30.0]; 4]),
+        ("NaN waypoint", vec![[0.0; 3], [f64::NAN, 0.0, 0.0], [10.0, 0.0, 0.0]]),
+        ("infinite waypoint", vec![[0.0; 3], [0.0, f64::INFINITY, 0.0]]),
+    ];
+    for (name, path) in cases {
+        assert!(TimedPath::time_optimal(&path, &limits, 1.0).is_err(), "{} path was accepted", name);
+    }
+}
+
+#[test]
+fn repeated_points_inside_a_path_are_passed_over() {
+    let path = [[0.0, 0.0, 0.0], [20.0, 0.0, 0.0], [20.0, 0.0, 0.0], [20.0, 30.0, 0.0]];
+    let timed = TimedPath::time_optimal(&path, &limits(), 1.0).unwrap();
+    assert_eq!(timed.samples().last().unwrap().position, path[3]);
+    assert!(timed.duration().is_finite());
+}
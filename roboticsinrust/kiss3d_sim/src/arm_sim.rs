@@ -1,111 +1,1371 @@
-use kiss3d::window::Window; 
+use kiss3d::window::Window;
 use kiss3d::camera::ArcBall;
 use kiss3d::scene::SceneNode;
 use kiss3d::text::Font;
-use kiss3d::nalgebra::{Translation3, Point2, Point3, Vector3, Matrix3, UnitQuaternion}; 
-use kiss3d::event::{Key, Action};
-use std::time::Instant;
+use kiss3d::nalgebra::{Translation3, Point2, Point3, Vector3, Matrix3};
+use kiss3d::event::{Key, Action, WindowEvent};
+use std::time::{Duration, Instant};
+use std::collections::BTreeMap;
 use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
 use dh_arm_model::dh_arm_model::DHArmModel;
-use dh_arm_model::dh::Pose;
+use dh_arm_model::dh::{FrameType, Pose};
+use dh_arm_model::manipulability::ManipulabilityEllipsoid;
+use dh_arm_model::cartesian_executor::TrajectoryExecutor;
+use dh_arm_model::motion_history::{JointSpaceMove, MotionHistory};
+use dh_arm_model::keyframe_script::{GripperAction, KeyframeScript, ScriptPlayer};
+use dh_arm_model::hardware::ToolIo;
+use dh_arm_model::elastic_joint::{JointElasticity, SeriesElasticJoints};
+use dh_arm_model::frequency_response::{FrequencyResponseTest, SweepAxis};
 use dh_arm_model::task_space_pid_controller::TaskSpacePidController;
+use dh_arm_model::velocity_filter::VelocityFilter;
+use dh_arm_model::travel_limiter::TravelLimiter;
+use dh_arm_model::pointing::{GazeController, GazePosition};
 use dh_arm_model::inverse_kinematics_solvers::IkSolver;
+use dh_arm_model::command_arbiter::{ArbiterState, CommandArbiter};
+use dh_arm_model::manifest::ExperimentManifest;
+use dh_arm_model::parameters::{Param, ParamSpec, ParamValue, Tunable};
+use dh_arm_model::settings::TuningSettings;
+use dh_arm_model::profiling::{Phase, StepProfiler};
+use dh_arm_model::telemetry::{Clock, ManualClock, SystemClock, TelemetryLog, TelemetryLogger, TelemetryRecord};
+use dh_arm_model::units::LengthUnit;
+use crate::remote::{RemoteClient, RemoteCommand, RemoteMessage};
+use crate::scene::{draw_frame_axes, Scene, SceneObjectId};
+use crate::trace::{draw_ellipsoid, EeTrail, TrajectoryPreview};
+use crate::capture::{CaptureFormat, FrameRecorder};
+use crate::sweep::ActiveSweep;
 
+/// Client name under which keyboard jogs claim an arm.
+const LOCAL_CLIENT: &str = "local-ui";
+/// The operator at the simulator outranks remote clients by default.
+const LOCAL_PRIORITY: u8 = 100;
+/// Operations each arm keeps for undo.
+const HISTORY_LENGTH: usize = 20;
+/// Speed of the fastest joint when undoing or redoing (deg/s or linear units/s).
+const HISTORY_JOINT_SPEED: f64 = 30.0;
+/// Deceleration joints brake with ahead of their limits (deg/s² or linear units/s²).
+const LIMIT_DECELERATION: f64 = 180.0;
+/// Rate (1/s) at which a gazing arm turns the tool towards its target.
+const GAZE_GAIN: f64 = 1.0;
+/// How often the averaged step breakdown is written to the log.
+const PROFILE_LOG_INTERVAL: Duration = Duration::from_secs(1);
+/// Axis length of goal markers; larger than the arm's frame axes so they stand out.
+const TARGET_FRAME_AXIS_LEN: f64 = 5.0;
+const TRAIL_COLOR: [f32; 3] = [1.0, 0.0, 1.0];
+const PREVIEW_COLOR: [f32; 3] = [0.0, 1.0, 1.0];
+/// Axis length of the frames sampled along a trajectory preview.
+const PREVIEW_AXIS_LEN: f32 = 1.0;
+const ELLIPSOID_COLOR: [f32; 3] = [1.0, 0.6, 0.0];
+const JOINT_COLOR: [f32; 3] = [1.0, 0.0, 0.0];
+/// Joint markers shade from this (no contribution) to [`JOINT_COLOR`] (largest).
+const IDLE_JOINT_COLOR: [f32; 3] = [0.2, 0.4, 1.0];
+/// Markers of fixed frames while joints are shaded.
+const FIXED_FRAME_COLOR: [f32; 3] = [0.6, 0.6, 0.6];
+/// Toggles the manipulability overlay.
+const MANIPULABILITY_KEY: Key = Key::E;
+/// Toggles between the configured [`SceneDisplay`] and its [`SceneDisplay::clean`] version.
+const CLEAN_VIEW_KEY: Key = Key::K;
+/// Shows or hides the scene objects.
+const PROPS_KEY: Key = Key::P;
+/// Saves the tuning settings of every arm that has a settings file.
+const SAVE_SETTINGS_KEY: Key = Key::O;
+/// Radius the joint marker spheres are created with; other sizes are drawn scaled.
+const JOINT_MARKER_RADIUS: f32 = 0.05;
 
-/// Simulation for task-space velocity control with continuous loop and non-blocking input.
-pub struct ArmSim<const F: usize, const J: usize, S: IkSolver<J>> {
+
+/// Frame in which keyboard jog commands are interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JogFrame {
+    /// Linear jogs along world axes, angular jogs about tool axes (controller default).
+    World,
+    /// Linear and angular jogs both along the current tool (end-effector) axes.
+    Tool,
+}
+
+/// Keyboard bindings that jog one arm.
+#[derive(Debug, Clone)]
+pub struct JogBindings {
+    /// (+, -) key pairs for [vx, vy, vz, ω_roll, ω_pitch, ω_yaw].
+    pub axes: [(Key, Key); 6],
+    /// Task velocity increment per frame for linear axes.
+    pub linear_step: f64,
+    /// Task velocity increment per frame for angular axes (deg/s).
+    pub angular_step: f64,
+    /// Holding a key ramps its axis up to at most this linear speed (units/s).
+    pub max_linear_speed: f64,
+    /// Holding a key ramps its axis up to at most this angular speed (deg/s).
+    pub max_angular_speed: f64,
+    /// Toggles between world and tool jog frames.
+    pub toggle_frame: Key,
+    /// Held together with a jog key for a fine jog.
+    pub fine_modifier: Key,
+    /// Toggles fine jogging on or off until pressed again.
+    pub toggle_fine: Key,
+    /// Undoes the last jog or nudge (see [`ArmSim::undo`]).
+    pub undo: Key,
+    /// Redoes the last undone one.
+    pub redo: Key,
+    /// Factor applied to the steps and speed limits above while fine jogging
+    /// (e.g. 0.1 for 10x finer, 0.01 for 100x).
+    pub fine_scale: f64,
+}
+
+impl JogBindings {
+    /// Ramp limit for task velocity axis `axis` (0-2 linear, 3-5 angular).
+    fn max_speed(&self, axis: usize) -> f64 {
+        if axis < 3 { self.max_linear_speed } else { self.max_angular_speed }
+    }
+}
+
+impl Default for JogBindings {
+    fn default() -> Self {
+        Self {
+            axes: [
+                (Key::Z, Key::X), (Key::C, Key::V), (Key::B, Key::N),
+                (Key::A, Key::S), (Key::D, Key::F), (Key::G, Key::H),
+            ],
+            linear_step: 1.0,
+            angular_step: 3.0,
+            max_linear_speed: 10.0,
+            max_angular_speed: 45.0,
+            toggle_frame: Key::T,
+            fine_modifier: Key::LShift,
+            toggle_fine: Key::M,
+            undo: Key::U,
+            redo: Key::Y,
+            fine_scale: 0.1,
+        }
+    }
+}
+
+/// How the manipulability overlay is drawn (see [`ArmSim::set_manipulability_display`]).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ManipulabilityDisplay {
+    /// World length drawn per unit of ellipsoid radius (linear units/s per rad/s).
+    pub scale: f64,
+    /// Shade each joint marker by the norm of its Jacobian column, from blue for the
+    /// joint moving the end-effector least to red for the one moving it most.
+    pub color_joints: bool,
+}
+
+impl Default for ManipulabilityDisplay {
+    fn default() -> Self {
+        Self { scale: 0.2, color_joints: true }
+    }
+}
+
+/// What the simulator draws besides the arms' links (see [`ArmSim::set_scene_display`]).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SceneDisplay {
+    /// Length of the world axes at the origin and of each arm's base axes; `None` hides them.
+    pub world_axes: Option<f32>,
+    /// Length of the axes drawn at every DH frame; `None` hides them.
+    pub frame_axes: Option<f32>,
+    /// Radius of the spheres marking every DH frame; `None` hides them.
+    pub joint_markers: Option<f32>,
+    /// Draw the scene objects (boards, spheres, target frames) that are themselves visible.
+    pub props: bool,
+    /// Draw the EE trail, the trajectory preview and the manipulability overlay when enabled.
+    pub overlays: bool,
+    /// Draw the status text.
+    pub hud: bool,
+}
+
+impl SceneDisplay {
+    /// This display with the axes, overlays and HUD hidden, keeping the joint markers
+    /// and props: a clean scene for recording demo footage.
+    pub fn clean(self) -> Self {
+        Self { world_axes: None, frame_axes: None, overlays: false, hud: false, ..self }
+    }
+}
+
+impl Default for SceneDisplay {
+    fn default() -> Self {
+        Self {
+            world_axes: Some(1.0),
+            frame_axes: Some(0.25),
+            joint_markers: Some(JOINT_MARKER_RADIUS),
+            props: true,
+            overlays: true,
+            hud: true,
+        }
+    }
+}
+
+/// What [`ArmSim::replay`] does with each logged step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayMode {
+    /// Sets every arm to the recorded joint states.
+    JointStates,
+    /// Starts from the first recorded state and re-runs each arm's controller on the
+    /// recorded commands and step lengths, reporting where it departs from the log.
+    Controller,
+}
+
+/// Progress through a loaded telemetry log.
+struct Replay {
+    records: Vec<TelemetryRecord>,
+    next: usize,
+    mode: ReplayMode,
+    /// Log time of the last applied step; what the rest of the sim sees as "now".
+    clock: ManualClock,
+    /// Wall time playback started (at the first rendered frame), so the log plays
+    /// back at its recorded speed.
+    started: Option<Instant>,
+    /// Arms whose state has been taken from the log (controller mode starts each from its first step).
+    seeded: Vec<bool>,
+    /// Largest joint position difference between the re-run and the log (controller mode).
+    max_divergence: f64,
+}
+
+/// A move an arm runs through on its own, driving the joints instead of the controller.
+enum PlannedMove<const J: usize> {
+    /// Relative Cartesian move (see [`ArmSim::nudge`]).
+    Nudge(TrajectoryExecutor<J>),
+    /// Retracing recorded configurations (see [`ArmSim::undo`] / [`ArmSim::redo`]).
+    History(JointSpaceMove<J>),
+    /// Playing a keyframe script (see [`ArmSim::play_script`]).
+    Script(ScriptPlayer<J>),
+}
+
+/// Stand-in for an arm's tool I/O: outputs are printed and shown in the HUD, inputs
+/// read what [`ArmSim::set_input`] set (off until then), events are printed.
+#[derive(Debug, Default)]
+struct SimToolIo {
+    outputs: BTreeMap<usize, bool>,
+    inputs: BTreeMap<usize, bool>,
+}
+
+impl ToolIo for SimToolIo {
+    fn set_output(&mut self, channel: usize, on: bool) -> Result<(), String> {
+        println!("Output {}: {}", channel, if on { "on" } else { "off" });
+        self.outputs.insert(channel, on);
+        Ok(())
+    }
+
+    fn read_input(&mut self, channel: usize) -> Result<bool, String> {
+        Ok(self.inputs.get(&channel).copied().unwrap_or(false))
+    }
+
+    fn publish_event(&mut self, event: &str) -> Result<(), String> {
+        println!("Event: {}", event);
+        Ok(())
+    }
+}
+
+/// One arm in the scene: model, controller, simulated joint state, where its base
+/// sits in the world, which keys (if any) drive it and which client controls it.
+pub struct SimArm<const F: usize, const J: usize, S: IkSolver<J>> {
     arm: DHArmModel<F, J, S>,
     controller: TaskSpacePidController,
+    /// Pose of the arm's base frame in the world.
+    base_pose: Pose,
+    bindings: Option<JogBindings>,
     task_vel: [f64; 6],   // [vx, vy, vz, ω_roll, ω_pitch, ω_yaw]
+    jog_frame: JogFrame,
+    /// Fine jogging toggled on (see [`JogBindings::fine_scale`]).
+    fine_jog: bool,
     joint_vel: [f64; J],
     joint_pos: [f64; J],
-    dt: f64,
+    arbiter: CommandArbiter,
+    trail: EeTrail,
+    /// Link-side joint states when the drives are modelled as elastic; the controller
+    /// still sees the motor side (`joint_pos`), the arm is drawn at the link side.
+    elastic: Option<SeriesElasticJoints<J>>,
+    planned: Option<PlannedMove<J>>,
+    history: MotionHistory<J>,
+    /// Joint positions and label of the operation moving the arm, recorded in the
+    /// history once the arm comes to rest.
+    operation: Option<([f64; J], String)>,
+    /// Last gripper action commanded by a script; the sim has no gripper model.
+    gripper: Option<GripperAction>,
+    /// Digital I/O and events of script triggers.
+    io: SimToolIo,
+    /// Smooths the controller's joint velocity commands; planned moves bypass it.
+    output_filter: Option<VelocityFilter<J>>,
+    /// Slows every command down ahead of the joint limits.
+    travel_limiter: Option<TravelLimiter<J>>,
+    /// Keeps the tool pointed at a target in place of the task-space controller;
+    /// linear jogs move the tool, which keeps turning to face it.
+    gaze: Option<GazeController>,
+    /// Where this arm's tuning settings are saved.
+    settings_file: Option<PathBuf>,
 }
 
-impl<const F: usize, const J: usize, S: IkSolver<J>> ArmSim<F, J, S> {
-    pub fn new(mut arm: DHArmModel<F, J, S>, controller: TaskSpacePidController, dt: f64) -> Self {
-        
+impl<const F: usize, const J: usize, S: IkSolver<J>> SimArm<F, J, S> {
+    fn new(
+        mut arm: DHArmModel<F, J, S>,
+        controller: TaskSpacePidController,
+        base_pose: Pose,
+        bindings: Option<JogBindings>,
+        trail_duration: Duration,
+    ) -> Self {
         arm.set_joint_positions(&[0.0f64; J]);
         arm.set_joint_velocities(&[0.0f64; J]);
+        // The scene's gravity is world -Z, whichever way this arm is mounted
+        arm.set_gravity_from_mounting(&base_pose.rotation);
+        let travel_limiter = Some(TravelLimiter::from_joints(arm.joints(), LIMIT_DECELERATION))
+            .filter(|limiter| limiter.has_limits());
 
         Self {
             arm,
             controller,
+            base_pose,
+            bindings,
             task_vel: [0.0; 6],
+            jog_frame: JogFrame::World,
+            fine_jog: false,
             joint_vel: [0.0; J],
             joint_pos: [0.0; J],
-            dt,
+            arbiter: CommandArbiter::default(),
+            trail: EeTrail::new(trail_duration),
+            elastic: None,
+            planned: None,
+            history: MotionHistory::new(HISTORY_LENGTH),
+            operation: None,
+            gripper: None,
+            io: SimToolIo::default(),
+            output_filter: None,
+            travel_limiter,
+            gaze: None,
+            settings_file: None,
         }
     }
 
-    /// Step simulation using task-space velocity (Jacobian inverse)
-    fn step(&mut self) -> Result<(), String> {
-        let theta_dot = self.controller.compute(&mut self.arm, &self.task_vel, &self.joint_pos, &self.joint_vel, self.dt);
+    pub fn arm(&self) -> &DHArmModel<F, J, S> {
+        &self.arm
+    }
+
+    pub fn base_pose(&self) -> &Pose {
+        &self.base_pose
+    }
+
+    fn toggle_jog_frame(&mut self) {
+        self.jog_frame = match self.jog_frame {
+            JogFrame::World => JogFrame::Tool,
+            JogFrame::Tool => JogFrame::World,
+        };
+        println!("Jog frame: {:?}", self.jog_frame);
+    }
+
+    fn toggle_fine_jog(&mut self) {
+        self.fine_jog = !self.fine_jog;
+        println!("Fine jog: {}", if self.fine_jog { "on" } else { "off" });
+    }
+
+    /// Scale of keyboard jogs right now: the fine scale while fine jogging is toggled
+    /// on or its modifier is held, else 1.
+    fn jog_scale(&self, window: &Window) -> f64 {
+        match &self.bindings {
+            Some(b) if self.fine_jog || window.get_key(b.fine_modifier) == Action::Press => b.fine_scale,
+            _ => 1.0,
+        }
+    }
+
+    /// Converts the jog command into the controller's convention
+    /// (linear in the arm's base frame, angular in end-effector frame).
+    fn controller_task_vel(&mut self) -> [f64; 6] {
+        let v = Vector3::new(self.task_vel[0], self.task_vel[1], self.task_vel[2]);
+        let v_base = match self.jog_frame {
+            // World jogs must be expressed in this arm's (possibly rotated) base frame
+            JogFrame::World => self.base_pose.rotation.transpose() * v,
+            JogFrame::Tool => {
+                // Use the rotation at the positions about to be commanded, not last step's.
+                self.arm.set_joint_positions(&self.joint_pos);
+                self.arm.end_effector_pose().rotation * v
+            }
+        };
+        [v_base.x, v_base.y, v_base.z, self.task_vel[3], self.task_vel[4], self.task_vel[5]]
+    }
+
+    /// Step simulation using task-space velocity (Jacobian inverse); `task_vel` is in
+    /// the controller's convention (see [`SimArm::controller_task_vel`]).
+    /// `joint_excitation` (joint, velocity) is added on top of the controller output.
+    fn step(
+        &mut self,
+        task_vel: &[f64; 6],
+        joint_excitation: Option<(usize, f64)>,
+        dt: f64,
+        profiler: &mut StepProfiler,
+    ) -> Result<(), String> {
+        profiler.merge(&self.arm.take_timings());
+
+        let start = Instant::now();
+        let planned = self.planned.is_some();
+        let mut theta_dot = match &mut self.planned {
+            Some(PlannedMove::Nudge(nudge)) => match nudge.step(&mut self.arm, &self.joint_pos, dt) {
+                Ok(command) => {
+                    if nudge.is_finished() {
+                        self.cancel_planned_move();
+                    }
+                    command
+                }
+                Err(e) => {
+                    eprintln!("Nudge aborted: {}", e);
+                    self.cancel_planned_move();
+                    [0.0; J]
+                }
+            },
+            Some(PlannedMove::History(path)) => {
+                let command = path.step(&self.joint_pos, dt);
+                if path.is_finished() {
+                    self.cancel_planned_move();
+                }
+                command
+            }
+            Some(PlannedMove::Script(player)) => match player.step(&mut self.arm, &mut self.io, &self.joint_pos, dt) {
+                Ok(command) => {
+                    if let Some(action) = command.gripper {
+                        println!("Gripper: {:?}", action);
+                        self.gripper = Some(action);
+                    }
+                    if player.is_finished() {
+                        println!("Script finished");
+                        self.cancel_planned_move();
+                    }
+                    command.joint_velocity
+                }
+                Err(e) => {
+                    eprintln!("Script aborted: {}", e);
+                    self.cancel_planned_move();
+                    [0.0; J]
+                }
+            },
+            None => {
+                let command = match &mut self.gaze {
+                    Some(gaze) => {
+                        gaze.position = GazePosition::Velocity(Vector3::new(task_vel[0], task_vel[1], task_vel[2]));
+                        gaze.compute(&mut self.arm, &self.joint_pos)
+                    }
+                    None => self.controller.compute(&mut self.arm, task_vel, &self.joint_pos, &self.joint_vel, dt),
+                };
+                match &mut self.output_filter {
+                    Some(filter) => filter.filter(&command, dt),
+                    None => command,
+                }
+            }
+        };
+        // Planned moves shape their own profiles; the filter picks up from where they leave off
+        if planned
+            && let Some(filter) = &mut self.output_filter
+        {
+            filter.reset(&theta_dot);
+        }
+        let elapsed = start.elapsed();
+        // FK/Jacobian run inside compute; charge them to their own phases
+        let kinematics = self.arm.take_timings();
+        profiler.merge(&kinematics);
+        profiler.record_excluding(Phase::Controller, elapsed, &kinematics);
         //println!("{:?} -> {:?}", self.task_vel, theta_dot);
+        if let Some((joint, velocity)) = joint_excitation {
+            theta_dot[joint] += velocity;
+        }
+        // Brake ahead of the limits rather than run into the clamp in `set_joint_positions`
+        if let Some(limiter) = &mut self.travel_limiter {
+            theta_dot = limiter.limit(&self.joint_pos, &theta_dot, dt);
+        }
         // Update internal joint state
         for i in 0..J {
             self.joint_vel[i] = theta_dot[i];
-            self.joint_pos[i] += self.joint_vel[i] * self.dt;
+            self.joint_pos[i] += self.joint_vel[i] * dt;
         }
+        if let Some(elastic) = &mut self.elastic {
+            elastic.step(&self.joint_pos, &self.joint_vel, dt);
+        }
+        self.record_operation();
 
         Ok(())
     }
 
-    pub fn reset(&mut self) {
+    fn reset(&mut self) {
+        // Reset is an operator override: whoever held the arm must claim it again
+        self.arbiter = CommandArbiter::new(self.arbiter.lease_timeout());
         self.task_vel = [0.0; 6];
+        self.planned = None;
+        // Keep the reset itself undoable, in case it was hit by mistake
+        self.record_operation();
+        self.history.record("reset", self.joint_pos, [0.0; J]);
         self.joint_vel = [0.0; J];
         self.joint_pos = [0.0; J];
+        if let Some(filter) = &mut self.output_filter {
+            filter.reset(&[0.0; J]);
+        }
+        self.trail.clear();
+        if let Some(elastic) = &mut self.elastic {
+            elastic.reset(&[0.0; J]);
+        }
         self.arm.set_joint_positions(&[0.0f64; J]);
         self.arm.set_joint_velocities(&[0.0f64; J]);
-        println!("Reset velocities and joint positions to zero.");
     }
 
-    // ----- Visualization Helpers -----
-    fn draw_frame_axes(window: &mut Window, pose: &Pose, length: f32) {
-        let pos = Point3::new(
-            pose.position.x as f32,
-            pose.position.y as f32,
-            pose.position.z as f32,
-        );
-        let rot_mat = pose.rotation.cast::<f32>();
-        let x_dir: Vector3<f32> = rot_mat.column(0).into_owned();
-        let y_dir: Vector3<f32> = rot_mat.column(1).into_owned();
-        let z_dir: Vector3<f32> = rot_mat.column(2).into_owned();
+    /// Gives the arm `gravity` (world frame, m/s²) in its base frame and length unit.
+    fn set_world_gravity(&mut self, gravity: &nalgebra::Vector3<f64>) {
+        let per_metre = self.arm.length_unit().per_metre();
+        self.arm.set_gravity(self.base_pose.rotation.transpose() * gravity * per_metre);
+    }
 
-        window.draw_line(&pos, &(pos + x_dir * length), &Point3::new(1.0, 0.0, 0.0)); //red
-        window.draw_line(&pos, &(pos + y_dir * length), &Point3::new(0.0, 1.0, 0.0)); //green
-        window.draw_line(&pos, &(pos + z_dir * length), &Point3::new(0.0, 0.0, 1.0)); //blue
+    /// Overwrites the simulated joint state, e.g. with a logged one.
+    fn set_state(&mut self, joint_pos: &[f64], joint_vel: &[f64]) {
+        self.joint_pos.copy_from_slice(joint_pos);
+        self.joint_vel.copy_from_slice(joint_vel);
+        if let Some(elastic) = &mut self.elastic {
+            elastic.reset(&self.joint_pos);
+        }
+        if let Some(filter) = &mut self.output_filter {
+            filter.reset(&self.joint_vel);
+        }
+        self.arm.set_joint_positions(&self.joint_pos);
+        self.arm.set_joint_velocities(&self.joint_vel);
     }
 
-    fn draw_board(window: &mut Window, height: f64, x_offset: f64, width: f64, depth: f64) {
-        let center_pos = Point3::new(x_offset as f32, 0.0, (height + depth / 2.0) as f32);
-        let rotation_quaternion = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), std::f32::consts::FRAC_PI_2);
-        let mut target_quad = window.add_quad(depth as f32, width as f32, 1, 1);
-        target_quad.set_color(1.0, 1.0, 0.0);
-        target_quad.set_local_rotation(rotation_quaternion);
-        target_quad.set_local_translation(Translation3::from(center_pos.coords));
+    /// Joint positions the arm is actually at: the link side if elastic, else the motor side.
+    fn link_positions(&self) -> [f64; J] {
+        self.elastic.as_ref().map_or(self.joint_pos, |e| *e.link_positions())
+    }
+
+    fn link_velocities(&self) -> [f64; J] {
+        self.elastic.as_ref().map_or(self.joint_vel, |e| *e.link_velocities())
+    }
+
+    /// End-effector pose in the arm's base frame at the link-side joint positions.
+    fn link_ee_pose(&mut self) -> Pose {
+        let link_pos = self.link_positions();
+        self.arm.set_joint_positions(&link_pos);
+        self.arm.end_effector_pose()
+    }
+
+    /// End-effector pose in world coordinates.
+    fn ee_world_pose(&self) -> Pose {
+        self.base_pose.compose(&self.arm.end_effector_pose())
+    }
+
+    /// Task velocity increments for every bound key held this frame, `None` if no key is held.
+    fn held_jog(&self, window: &Window) -> Option<[f64; 6]> {
+        let bindings = self.bindings.as_ref()?;
+        let scale = self.jog_scale(window);
+        let mut delta = [0.0; 6];
+        let mut held = false;
+
+        for (axis, &(plus, minus)) in bindings.axes.iter().enumerate() {
+            let step = scale * if axis < 3 { bindings.linear_step } else { bindings.angular_step };
+            if window.get_key(plus) == Action::Press { delta[axis] += step; held = true; }
+            if window.get_key(minus) == Action::Press { delta[axis] -= step; held = true; }
+        }
+        held.then_some(delta)
+    }
+
+    /// Applies a command from `client`, discarding the previous owner's velocity on handover.
+    /// Any command cancels a nudge, undo, redo or script in progress.
+    fn command(&mut self, client: &str, priority: u8, now: Instant, apply: impl FnOnce(&mut [f64; 6])) -> Result<(), String> {
+        if self.arbiter.claim(client, priority, now)? {
+            self.task_vel = [0.0; 6];
+        }
+        self.cancel_planned_move();
+        apply(&mut self.task_vel);
+        if self.task_vel.iter().any(|v| *v != 0.0) {
+            self.start_operation(&format!("jog ({})", client));
+        }
+        Ok(())
+    }
+
+    /// Starts a small relative move of the end-effector for `client` (see [`ArmSim::nudge`]),
+    /// at the jog speed limits, stopping any jog.
+    fn nudge(&mut self, client: &str, priority: u8, now: Instant, offset: &[f64; 6]) -> Result<(), String> {
+        self.command(client, priority, now, |v| *v = [0.0; 6])?;
+        let (linear_speed, angular_speed) = match &self.bindings {
+            Some(b) => (b.max_linear_speed, b.max_angular_speed),
+            None => (JogBindings::default().max_linear_speed, JogBindings::default().max_angular_speed),
+        };
+        // Offsets are in the world frame like jogs; the executor works in the base frame
+        let d = self.base_pose.rotation.transpose() * Vector3::new(offset[0], offset[1], offset[2]);
+        let base_offset = [d.x, d.y, d.z, offset[3], offset[4], offset[5]];
+        self.planned = Some(PlannedMove::Nudge(TrajectoryExecutor::nudge(
+            &mut self.arm,
+            &self.joint_pos,
+            &base_offset,
+            linear_speed,
+            angular_speed,
+        )));
+        self.start_operation(&format!("nudge ({})", client));
+        Ok(())
+    }
+
+    /// Undoes (`redo` false) or redoes the last `steps` operations for `client` (see
+    /// [`ArmSim::undo`]), stopping any jog. Fails without moving if the retraced path
+    /// would leave the joint limits or the controller's workspace box.
+    fn step_history(&mut self, client: &str, priority: u8, now: Instant, steps: usize, redo: bool) -> Result<(), String> {
+        self.command(client, priority, now, |v| *v = [0.0; 6])?;
+        // A jog still settling counts as done before stepping back over it
+        self.record_operation();
+        let workspace = self.controller.limits.workspace;
+        let plan = if redo {
+            self.history.plan_redo(steps, &mut self.arm, &self.joint_pos, HISTORY_JOINT_SPEED, workspace.as_ref())
+        } else {
+            self.history.plan_undo(steps, &mut self.arm, &self.joint_pos, HISTORY_JOINT_SPEED, workspace.as_ref())
+        };
+        self.arm.set_joint_positions(&self.joint_pos);
+        self.planned = Some(PlannedMove::History(plan?));
+        Ok(())
+    }
+
+    /// Plays `script` for `client` (see [`ArmSim::play_script`]), stopping any jog.
+    fn play_script(&mut self, client: &str, priority: u8, now: Instant, script: KeyframeScript<J>) -> Result<(), String> {
+        self.command(client, priority, now, |v| *v = [0.0; 6])?;
+        self.planned = Some(PlannedMove::Script(ScriptPlayer::new(script)));
+        self.start_operation(&format!("script ({})", client));
+        Ok(())
+    }
+
+    /// Ends a nudge, undo, redo or script in progress; the controller then holds the
+    /// pose the arm got to rather than the one it was holding before.
+    fn cancel_planned_move(&mut self) {
+        if self.planned.take().is_some() {
+            self.controller.reset();
+        }
+    }
+
+    /// Notes where the arm was when an operation started, unless one is already under way.
+    fn start_operation(&mut self, label: &str) {
+        if self.operation.is_none() {
+            self.operation = Some((self.joint_pos, label.to_string()));
+        }
+    }
+
+    /// Adds the operation under way to the history once the arm has come to rest.
+    fn record_operation(&mut self) {
+        let moving = self.task_vel.iter().any(|v| *v != 0.0) || matches!(self.planned, Some(PlannedMove::Nudge(_) | PlannedMove::Script(_)));
+        if !moving && let Some((before, label)) = self.operation.take() {
+            self.history.record(&label, before, self.joint_pos);
+        }
+    }
+
+    /// Keeps the local lease alive while its jog is still moving the arm, and stops
+    /// the arm once nobody holds the lease so no command outlives its owner.
+    fn update_lease(&mut self, now: Instant) {
+        let moving = self.task_vel.iter().any(|v| *v != 0.0);
+        if moving && self.arbiter.is_owner(LOCAL_CLIENT, now) {
+            let _ = self.arbiter.claim(LOCAL_CLIENT, LOCAL_PRIORITY, now);
+        }
+        // A nudge, undo or script is a single command, so it keeps its sender's lease until it is done
+        if self.planned.is_some()
+            && let Some((owner, priority)) = self.arbiter.lease(now).map(|l| (l.owner.clone(), l.priority))
+        {
+            let _ = self.arbiter.claim(&owner, priority, now);
+        }
+        if self.arbiter.lease(now).is_none() {
+            self.task_vel = [0.0; 6];
+            self.cancel_planned_move();
+        }
+    }
+}
+
+/// The arm model's parameters (IK damping) followed by the controller's.
+impl<const F: usize, const J: usize, S: IkSolver<J>> Tunable for SimArm<F, J, S> {
+    fn param_specs(&self) -> Vec<ParamSpec> {
+        let mut specs = self.arm.param_specs();
+        specs.extend(self.controller.param_specs());
+        specs
+    }
+
+    fn get_param(&self, name: &str) -> Result<ParamValue, String> {
+        match self.arm.param_spec(name) {
+            Ok(_) => self.arm.get_param(name),
+            Err(_) => self.controller.get_param(name),
+        }
+    }
+
+    fn set_param(&mut self, name: &str, value: ParamValue) -> Result<(), String> {
+        match self.arm.param_spec(name) {
+            Ok(_) => self.arm.set_param(name, value),
+            Err(_) => self.controller.set_param(name, value),
+        }
+    }
+}
+
+/// Simulation for task-space velocity control with continuous loop and non-blocking input.
+///
+/// Holds one or more arms sharing a single kiss3d scene; each arm has its own
+/// base transform, controller and key bindings.
+///
+/// The local keyboard and any [`RemoteClient`]s must hold an arm's lease (see
+/// [`CommandArbiter`]) to command it; parameter changes (see [`ArmSim::set_param`])
+/// need no lease.
+pub struct ArmSim<const F: usize, const J: usize, S: IkSolver<J>> {
+    arms: Vec<SimArm<F, J, S>>,
+    /// Length of the current step; live runs use the frame time.
+    dt: f64,
+    /// Nominal control period (s), as configured rather than measured; recorded in
+    /// manifests and tuning settings.
+    control_period: f64,
+    remote_tx: Sender<RemoteMessage>,
+    remote_rx: Receiver<RemoteMessage>,
+    arbiter_states: Arc<Mutex<Vec<ArbiterState>>>,
+    /// Tunable parameters of each arm, published to remote clients whenever they change.
+    arm_params: Arc<Mutex<Vec<Vec<Param>>>>,
+    profiler: StepProfiler,
+    scene: Scene,
+    /// How long the EE trail is kept; `None` disables it.
+    trail_duration: Option<Duration>,
+    trajectory_preview: Option<TrajectoryPreview>,
+    capture_format: CaptureFormat,
+    recorder: Option<FrameRecorder>,
+    /// Time source for live runs (leases, trails, step length).
+    clock: Box<dyn Clock>,
+    telemetry: Option<TelemetryLogger>,
+    /// Name of what is being run, recorded in the manifest of each telemetry log.
+    scenario: String,
+    replay: Option<Replay>,
+    sweep: Option<ActiveSweep>,
+    manipulability: ManipulabilityDisplay,
+    show_manipulability: bool,
+    display: SceneDisplay,
+    /// Drawing the [`SceneDisplay::clean`] version of `display`.
+    clean_view: bool,
+    /// World-frame gravity (m/s²) applied to every arm; `None` keeps each arm's own
+    /// magnitude along world -Z.
+    gravity: Option<nalgebra::Vector3<f64>>,
+}
+
+impl<const F: usize, const J: usize, S: IkSolver<J>> ArmSim<F, J, S> {
+    /// Creates a simulation with a single arm at the world origin using the default key bindings.
+    pub fn new(arm: DHArmModel<F, J, S>, controller: TaskSpacePidController, dt: f64) -> Self {
+        let mut sim = Self::empty(dt);
+        sim.add_arm(arm, controller, Pose::identity(), Some(JogBindings::default()));
+        sim
+    }
+
+    /// Creates a simulation with no arms; add them with [`ArmSim::add_arm`].
+    pub fn empty(dt: f64) -> Self {
+        let (remote_tx, remote_rx) = mpsc::channel();
+        Self {
+            arms: Vec::new(),
+            dt,
+            control_period: dt,
+            remote_tx,
+            remote_rx,
+            arbiter_states: Arc::new(Mutex::new(Vec::new())),
+            arm_params: Arc::new(Mutex::new(Vec::new())),
+            profiler: StepProfiler::default(),
+            scene: Scene::default(),
+            trail_duration: None,
+            trajectory_preview: None,
+            capture_format: CaptureFormat::PngSequence { dir: "capture".into() },
+            recorder: None,
+            clock: Box::new(SystemClock),
+            telemetry: None,
+            scenario: "interactive".to_string(),
+            replay: None,
+            sweep: None,
+            manipulability: ManipulabilityDisplay::default(),
+            show_manipulability: false,
+            display: SceneDisplay::default(),
+            clean_view: false,
+            gravity: None,
+        }
+    }
+
+    /// Adds an arm whose base sits at `base_pose` in the world, returning its index.
+    ///
+    /// Arms without `bindings` are not keyboard controlled. The arm's gravity vector
+    /// is re-aimed for the base orientation (see `DHArmModel::set_gravity_from_mounting`).
+    pub fn add_arm(
+        &mut self,
+        arm: DHArmModel<F, J, S>,
+        controller: TaskSpacePidController,
+        base_pose: Pose,
+        bindings: Option<JogBindings>,
+    ) -> usize {
+        let trail_duration = self.trail_duration.unwrap_or_default();
+        let mut sim_arm = SimArm::new(arm, controller, base_pose, bindings, trail_duration);
+        if let Some(gravity) = self.gravity {
+            sim_arm.set_world_gravity(&gravity);
+        }
+        self.arms.push(sim_arm);
+        self.publish_params();
+        self.arms.len() - 1
+    }
+
+    pub fn arms(&self) -> &[SimArm<F, J, S>] {
+        &self.arms
+    }
+
+    /// Sets the scene's gravity (world frame, m/s²) for every arm, including arms
+    /// added later, e.g. zero for a weightless scene. Each arm receives it in its
+    /// base frame and length unit.
+    pub fn set_gravity(&mut self, gravity: nalgebra::Vector3<f64>) {
+        self.gravity = Some(gravity);
+        for sim_arm in &mut self.arms {
+            sim_arm.set_world_gravity(&gravity);
+        }
+    }
+
+    /// Chooses which helpers (axes, joint markers, props, overlays, HUD) are drawn.
+    /// At runtime, `K` switches to its [`SceneDisplay::clean`] version and back, and `P`
+    /// toggles the props.
+    pub fn set_scene_display(&mut self, display: SceneDisplay) {
+        self.display = display;
+    }
+
+    /// Draws the [`SceneDisplay::clean`] version of the scene display, or the display itself.
+    pub fn set_clean_view(&mut self, clean: bool) {
+        self.clean_view = clean;
+    }
+
+    /// What is being drawn right now.
+    pub fn scene_display(&self) -> SceneDisplay {
+        if self.clean_view { self.display.clean() } else { self.display }
+    }
+
+    /// Smooths the joint velocity commands the controller of arm `arm_index` sends
+    /// (see [`VelocityFilter`]), so jog starts and stops ramp instead of stepping; `None`
+    /// removes the filter. Nudges, undo/redo and scripts plan smooth moves themselves
+    /// and are not filtered.
+    pub fn set_output_filter(&mut self, arm_index: usize, filter: Option<VelocityFilter<J>>) -> Result<(), String> {
+        let sim_arm = self.arms.get_mut(arm_index).ok_or_else(|| format!("No arm with index {}", arm_index))?;
+        sim_arm.output_filter = filter.map(|mut f| {
+            f.reset(&sim_arm.joint_vel);
+            f
+        });
+        Ok(())
     }
 
+    /// Sets the frame arm `arm_index`'s jog commands are interpreted in.
+    pub fn set_jog_frame(&mut self, arm_index: usize, frame: JogFrame) -> Result<(), String> {
+        let sim_arm = self.arms.get_mut(arm_index).ok_or_else(|| format!("No arm with index {}", arm_index))?;
+        sim_arm.jog_frame = frame;
+        Ok(())
+    }
+
+    /// Switches arm `arm_index` between world and tool frame jogging, as the T key does.
+    pub fn toggle_jog_frame(&mut self, arm_index: usize) -> Result<(), String> {
+        let sim_arm = self.arms.get_mut(arm_index).ok_or_else(|| format!("No arm with index {}", arm_index))?;
+        sim_arm.toggle_jog_frame();
+        Ok(())
+    }
+
+    /// Replaces how arm `arm_index` slows down ahead of its joint limits (see
+    /// [`TravelLimiter`]); `None` leaves the limits to the clamp in the model. Arms
+    /// with joint limits start with a limiter braking at 180 deg/s².
+    pub fn set_travel_limiter(&mut self, arm_index: usize, limiter: Option<TravelLimiter<J>>) -> Result<(), String> {
+        let sim_arm = self.arms.get_mut(arm_index).ok_or_else(|| format!("No arm with index {}", arm_index))?;
+        sim_arm.travel_limiter = limiter;
+        Ok(())
+    }
+
+    /// Makes arm `arm_index` keep its tool's Z axis pointed at `target` (world frame),
+    /// e.g. to inspect a part with a wrist camera (see [`GazeController`]); `None`
+    /// hands the arm back to its task-space controller where it stands. While gazing,
+    /// linear jogs move the tool and angular jogs are ignored.
+    pub fn set_gaze_target(&mut self, arm_index: usize, target: Option<Vector3<f64>>) -> Result<(), String> {
+        let sim_arm = self.arms.get_mut(arm_index).ok_or_else(|| format!("No arm with index {}", arm_index))?;
+        let world_to_base = sim_arm.base_pose.inverse();
+        sim_arm.gaze = target.map(|t| GazeController::new(world_to_base.rotation * t + world_to_base.position, GAZE_GAIN));
+        if sim_arm.gaze.is_none() {
+            // Hold where the gaze left the arm, not the pose held before it
+            sim_arm.controller.reset();
+        }
+        Ok(())
+    }
+
+    /// Models the drives of arm `arm_index` as series-elastic (see
+    /// [`SeriesElasticJoints`]), or makes them rigid again with `None`.
+    ///
+    /// The controller keeps commanding and reading the motor side, as with servo
+    /// encoders; the arm is drawn at the link side, so tracking oscillations show up
+    /// on screen and in the end-effector trail.
+    pub fn set_joint_elasticity(&mut self, arm_index: usize, elasticity: Option<[JointElasticity; J]>) -> Result<(), String> {
+        let sim_arm = self.arms.get_mut(arm_index).ok_or_else(|| format!("No arm with index {}", arm_index))?;
+        sim_arm.elastic = elasticity.map(|e| SeriesElasticJoints::new(e, &sim_arm.joint_pos));
+        Ok(())
+    }
+
+    /// Runs a frequency-response test on arm `arm_index`, replacing any test in progress.
+    ///
+    /// The excitation is added to the operator's command on the test's axis, and the
+    /// link-side response is recorded (joint velocity, or end-effector velocity for a
+    /// task axis). When the excitation ends, the Bode estimate is written to
+    /// `<output>.csv` and `<output>.svg`.
+    pub fn start_frequency_response<P: AsRef<Path>>(
+        &mut self,
+        arm_index: usize,
+        test: FrequencyResponseTest,
+        output: P,
+    ) -> Result<(), String> {
+        if arm_index >= self.arms.len() {
+            return Err(format!("No arm with index {}", arm_index));
+        }
+        match test.axis {
+            SweepAxis::Joint(j) if j >= J => return Err(format!("No joint with index {}", j)),
+            SweepAxis::Task(k) if k >= 6 => return Err(format!("No task axis with index {}", k)),
+            _ => {}
+        }
+        self.sweep = Some(ActiveSweep::new(arm_index, test, PathBuf::from(output.as_ref())));
+        Ok(())
+    }
+
+    /// Draws the path of every arm's end-effector over the last `duration`, or disables it with `None`.
+    pub fn set_ee_trail(&mut self, duration: Option<Duration>) {
+        self.trail_duration = duration;
+        for sim_arm in &mut self.arms {
+            sim_arm.trail = EeTrail::new(duration.unwrap_or_default());
+        }
+    }
+
+    /// Draws each arm's linear velocity manipulability ellipsoid at its end-effector,
+    /// with the weakest (nearest singular) direction in red, and optionally shades the
+    /// joints by how much they move the end-effector; `None` hides it. Toggled with `E`.
+    pub fn set_manipulability_display(&mut self, display: Option<ManipulabilityDisplay>) {
+        self.show_manipulability = display.is_some();
+        if let Some(display) = display {
+            self.manipulability = display;
+        }
+    }
+
+    /// Shows a planned trajectory (world-frame EE poses) before executing it,
+    /// with frame axes at every `frame_stride`-th pose. Replaces any previous preview.
+    pub fn preview_trajectory(&mut self, poses: Vec<Pose>, frame_stride: usize) {
+        self.trajectory_preview = Some(TrajectoryPreview::new(poses, frame_stride));
+    }
+
+    pub fn clear_trajectory_preview(&mut self) {
+        self.trajectory_preview = None;
+    }
+
+    /// Sets where the next recording goes (default: PNG sequence in `./capture`).
+    pub fn set_capture_format(&mut self, format: CaptureFormat) {
+        self.capture_format = format;
+    }
+
+    /// Starts dumping every rendered frame; also toggled with the R key.
+    pub fn start_recording(&mut self) -> Result<(), String> {
+        if self.recorder.is_some() {
+            return Err("Already recording".into());
+        }
+        self.recorder = Some(FrameRecorder::start(self.capture_format.clone())?);
+        println!("Recording to {:?}", self.capture_format);
+        Ok(())
+    }
+
+    /// Stops recording, returning the number of frames written.
+    pub fn stop_recording(&mut self) -> Result<usize, String> {
+        let recorder = self.recorder.take().ok_or("Not recording")?;
+        let frames = recorder.finish()?;
+        println!("Recording stopped after {} frames", frames);
+        Ok(frames)
+    }
+
+    fn toggle_recording(&mut self) {
+        let result = if self.recorder.is_some() {
+            self.stop_recording().map(|_| ())
+        } else {
+            self.start_recording()
+        };
+        if let Err(e) = result {
+            eprintln!("Recording error: {}", e);
+        }
+    }
+
+    /// Replaces the wall clock the simulation reads once per frame.
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Logs every controller step (time, command, joint state) to `path` for later
+    /// [`ArmSim::replay`].
+    ///
+    /// An [`ExperimentManifest`] is written alongside (`<log>.manifest`): the scenario
+    /// name, simulator and library versions, the step length, and each arm's
+    /// [`DHArmModel::config_hash`] and parameters as logging starts.
+    pub fn start_telemetry<P: AsRef<Path>>(&mut self, path: P) -> Result<(), String> {
+        if self.telemetry.is_some() {
+            return Err("Telemetry is already being logged".into());
+        }
+        let path = path.as_ref();
+        let mut manifest = ExperimentManifest::new(&self.scenario, concat!("kiss3d_sim ", env!("CARGO_PKG_VERSION")), self.control_period);
+        manifest.data_files.push(path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().into_owned()));
+        for sim_arm in &self.arms {
+            manifest.add_arm(sim_arm.arm.config_hash(), &sim_arm.params());
+        }
+        // One units line covers every arm in the log
+        let length_unit = self.arms.first().map_or_else(LengthUnit::default, |a| a.arm.length_unit());
+        if self.arms.iter().any(|a| a.arm.length_unit() != length_unit) {
+            return Err("Telemetry needs every arm in the same length unit".into());
+        }
+        self.telemetry = Some(TelemetryLogger::create(path, J, length_unit, self.clock.now())?);
+        manifest.save(ExperimentManifest::path_for(path))
+    }
+
+    /// Names the scenario being run (default "interactive") for telemetry manifests.
+    pub fn set_scenario(&mut self, name: &str) {
+        self.scenario = name.to_string();
+    }
+
+    /// Closes the telemetry log, returning the number of steps written.
+    pub fn stop_telemetry(&mut self) -> Result<usize, String> {
+        self.telemetry.take().ok_or("Telemetry is not being logged")?.finish()
+    }
+
+    /// Plays back a telemetry log (from this simulator or from hardware) in the visualizer
+    /// at its recorded speed, either re-driving the logged joint states or re-running the
+    /// controllers on the logged commands. Keyboard and remote commands are ignored until
+    /// playback ends.
+    ///
+    /// Controller replays start every controller from scratch, so they reproduce a
+    /// session exactly only if it was logged from the start.
+    pub fn replay<P: AsRef<Path>>(&mut self, log_file: P, mode: ReplayMode) -> Result<(), String> {
+        let log = TelemetryLog::load(log_file)?;
+        if log.num_joints != J {
+            return Err(format!("Log has {} joints, the simulated arms have {}", log.num_joints, J));
+        }
+        if let Some(record) = log.records.iter().find(|r| r.arm >= self.arms.len()) {
+            return Err(format!("Log refers to arm {}, but only {} are loaded", record.arm, self.arms.len()));
+        }
+
+        let records = log.records.iter()
+            .map(|r| {
+                let arm = &self.arms[r.arm].arm;
+                r.to_controller_units(&log.units, arm.joints(), arm.length_unit())
+            })
+            .collect();
+
+        for sim_arm in &mut self.arms {
+            sim_arm.reset();
+            sim_arm.controller.reset();
+        }
+        println!("Replaying {} steps ({:.1} s) in {:?} mode", log.records.len(), log.duration(), mode);
+        self.replay = Some(Replay {
+            records,
+            next: 0,
+            mode,
+            clock: ManualClock::new(),
+            started: None,
+            seeded: vec![false; self.arms.len()],
+            max_divergence: 0.0,
+        });
+        self.run();
+        Ok(())
+    }
+
+    /// Applies every logged step that is due by now, returning false once the log is exhausted.
+    fn advance_replay(&mut self) -> bool {
+        let Some(replay) = &mut self.replay else { return false };
+        let playback_time = replay.started.get_or_insert_with(Instant::now).elapsed().as_secs_f64();
+
+        while let Some(record) = replay.records.get(replay.next) {
+            if record.time > playback_time {
+                return true;
+            }
+            replay.clock.set_elapsed(Duration::from_secs_f64(record.time));
+            let sim_arm = &mut self.arms[record.arm];
+            sim_arm.task_vel = record.task_vel;
+            match replay.mode {
+                ReplayMode::JointStates => sim_arm.set_state(&record.joint_pos, &record.joint_vel),
+                ReplayMode::Controller => {
+                    if !replay.seeded[record.arm] {
+                        sim_arm.set_state(&record.joint_pos, &record.joint_vel);
+                        replay.seeded[record.arm] = true;
+                    } else {
+                        let divergence = sim_arm.joint_pos.iter()
+                            .zip(&record.joint_pos)
+                            .map(|(a, b)| (a - b).abs())
+                            .fold(0.0, f64::max);
+                        replay.max_divergence = replay.max_divergence.max(divergence);
+                    }
+                    let _ = sim_arm.step(&record.task_vel, None, record.dt, &mut self.profiler);
+                }
+            }
+            replay.next += 1;
+        }
+
+        match replay.mode {
+            ReplayMode::JointStates => println!("Replay finished ({} steps)", replay.records.len()),
+            ReplayMode::Controller => println!(
+                "Replay finished ({} steps), max joint divergence from log: {:.3e}",
+                replay.records.len(), replay.max_divergence
+            ),
+        }
+        false
+    }
+
+    /// Current time: the log time during a replay, otherwise the configured clock.
+    fn now(&self) -> Instant {
+        match &self.replay {
+            Some(replay) => replay.clock.now(),
+            None => self.clock.now(),
+        }
+    }
+
+    /// Task environment drawn with the arms; see also the `add_*` shortcuts below.
+    pub fn scene(&self) -> &Scene {
+        &self.scene
+    }
+
+    pub fn scene_mut(&mut self) -> &mut Scene {
+        &mut self.scene
+    }
+
+    /// Adds a box of full edge lengths `size` centered at `pose`.
+    pub fn add_box(&mut self, pose: Pose, size: [f64; 3]) -> SceneObjectId {
+        self.scene.add_box(pose, size)
+    }
+
+    pub fn add_sphere(&mut self, center: nalgebra::Vector3<f64>, radius: f64) -> SceneObjectId {
+        self.scene.add_sphere(center, radius)
+    }
+
+    /// Marks a goal or reference pose with coordinate axes.
+    pub fn add_target_frame(&mut self, pose: Pose) -> SceneObjectId {
+        self.scene.add_target_frame(pose, TARGET_FRAME_AXIS_LEN)
+    }
+
+    pub fn remove_object(&mut self, id: SceneObjectId) -> Result<(), String> {
+        self.scene.remove(id).map(|_| ())
+    }
+
+    /// Creates a handle for a client other than the local UI.
+    pub fn remote_client(&self, name: &str, priority: u8) -> RemoteClient {
+        RemoteClient::new(
+            name,
+            priority,
+            self.remote_tx.clone(),
+            Arc::clone(&self.arbiter_states),
+            Arc::clone(&self.arm_params),
+        )
+    }
+
+    /// Applies queued remote commands that pass arbitration.
+    fn process_remote_commands(&mut self, now: Instant) {
+        while let Ok(msg) = self.remote_rx.try_recv() {
+            let result = match msg.command {
+                RemoteCommand::TaskVelocity { arm_index, task_vel } => match self.arms.get_mut(arm_index) {
+                    Some(sim_arm) => sim_arm.command(&msg.client, msg.priority, now, |v| *v = task_vel),
+                    None => Err(format!("No arm with index {}", arm_index)),
+                },
+                RemoteCommand::Nudge { arm_index, offset } => match self.arms.get_mut(arm_index) {
+                    Some(sim_arm) => sim_arm.nudge(&msg.client, msg.priority, now, &offset),
+                    None => Err(format!("No arm with index {}", arm_index)),
+                },
+                RemoteCommand::Undo { arm_index, steps } => match self.arms.get_mut(arm_index) {
+                    Some(sim_arm) => sim_arm.step_history(&msg.client, msg.priority, now, steps, false),
+                    None => Err(format!("No arm with index {}", arm_index)),
+                },
+                RemoteCommand::Redo { arm_index, steps } => match self.arms.get_mut(arm_index) {
+                    Some(sim_arm) => sim_arm.step_history(&msg.client, msg.priority, now, steps, true),
+                    None => Err(format!("No arm with index {}", arm_index)),
+                },
+                RemoteCommand::Release { arm_index } => match self.arms.get_mut(arm_index) {
+                    Some(sim_arm) => sim_arm.arbiter.release(&msg.client, now),
+                    None => Err(format!("No arm with index {}", arm_index)),
+                },
+                RemoteCommand::SaveSettings { arm_index } => self.save_settings(arm_index).map(|path| {
+                    println!("'{}' saved arm {} settings to {}", msg.client, arm_index, path.display());
+                }),
+                RemoteCommand::SetParam { arm_index, name, value } => {
+                    let result = self.set_param(arm_index, &name, value);
+                    if result.is_ok() {
+                        println!("'{}' set arm {} {} = {}", msg.client, arm_index, name, value);
+                    }
+                    result
+                }
+            };
+            if let Err(e) = result {
+                eprintln!("Rejected command from '{}': {}", msg.client, e);
+            }
+        }
+    }
+
+    /// Moves an arm's end-effector by a small relative offset from where it is now, as
+    /// the local operator: `[dx, dy, dz]` along the world axes and `[droll, dpitch, dyaw]`
+    /// degrees about the tool axes, the same frames as the default jog.
+    ///
+    /// The move runs over the following steps at the jog speed limits, detouring through
+    /// joint space near singularities (`TrajectoryExecutor`); any other command to the
+    /// arm cancels it. Remote clients nudge through [`RemoteClient::send_nudge`].
+    pub fn nudge(&mut self, arm_index: usize, offset: [f64; 6]) -> Result<(), String> {
+        let now = self.now();
+        let sim_arm = self.arms.get_mut(arm_index).ok_or_else(|| format!("No arm with index {}", arm_index))?;
+        sim_arm.nudge(LOCAL_CLIENT, LOCAL_PRIORITY, now, &offset)
+    }
+
+    /// Undoes the last `steps` jogs and nudges of an arm as the local operator.
+    ///
+    /// Each operation is recorded with the configuration before it once the arm comes
+    /// to rest (the last `HISTORY_LENGTH` per arm). Undoing plans a joint-space move back
+    /// through those configurations, most recent first, at a moderate joint speed; the
+    /// path is checked against the joint limits and the controller's workspace box
+    /// beforehand and refused if it leaves them. Any other command cancels the move.
+    pub fn undo(&mut self, arm_index: usize, steps: usize) -> Result<(), String> {
+        let now = self.now();
+        let sim_arm = self.arms.get_mut(arm_index).ok_or_else(|| format!("No arm with index {}", arm_index))?;
+        sim_arm.step_history(LOCAL_CLIENT, LOCAL_PRIORITY, now, steps, false)
+    }
+
+    /// Redoes the last `steps` undone operations of an arm (see [`ArmSim::undo`]).
+    pub fn redo(&mut self, arm_index: usize, steps: usize) -> Result<(), String> {
+        let now = self.now();
+        let sim_arm = self.arms.get_mut(arm_index).ok_or_else(|| format!("No arm with index {}", arm_index))?;
+        sim_arm.step_history(LOCAL_CLIENT, LOCAL_PRIORITY, now, steps, true)
+    }
+
+    /// Plays a keyframe script on an arm as the local operator, e.g. a demo routine
+    /// loaded with [`KeyframeScript::load`].
+    ///
+    /// Each keyframe's move is planned from wherever the arm is when it starts and
+    /// checked against the joint limits; pose keyframes follow a straight line through
+    /// the path IK. Gripper actions and trigger outputs are printed and shown in the
+    /// HUD, events are printed, and triggers waiting for an input hold the arm until
+    /// [`ArmSim::set_input`] turns it. Any other command to the arm stops the script,
+    /// and a keyframe that cannot be planned or a trigger that times out aborts it.
+    pub fn play_script(&mut self, arm_index: usize, script: KeyframeScript<J>) -> Result<(), String> {
+        let now = self.now();
+        let sim_arm = self.arms.get_mut(arm_index).ok_or_else(|| format!("No arm with index {}", arm_index))?;
+        sim_arm.play_script(LOCAL_CLIENT, LOCAL_PRIORITY, now, script)
+    }
+
+    /// Sets a simulated digital input of arm `arm_index`, as read by script triggers
+    /// waiting on it, e.g. a part-present sensor.
+    pub fn set_input(&mut self, arm_index: usize, channel: usize, on: bool) -> Result<(), String> {
+        let sim_arm = self.arms.get_mut(arm_index).ok_or_else(|| format!("No arm with index {}", arm_index))?;
+        sim_arm.io.inputs.insert(channel, on);
+        Ok(())
+    }
+
+    /// Tunable parameters of an arm: the pseudo-inverse damping, the controller's PID
+    /// gains per task axis and its speed limits, with types, ranges and values.
+    pub fn params(&self, arm_index: usize) -> Option<Vec<Param>> {
+        self.arms.get(arm_index).map(|a| a.params())
+    }
+
+    /// Changes a tunable parameter of an arm (see [`ArmSim::params`]) and publishes the
+    /// new values to remote clients. Takes effect from the next step; the arm keeps
+    /// whoever controls it.
+    pub fn set_param(&mut self, arm_index: usize, name: &str, value: ParamValue) -> Result<(), String> {
+        let sim_arm = self.arms.get_mut(arm_index).ok_or_else(|| format!("No arm with index {}", arm_index))?;
+        sim_arm.set_param(name, value)?;
+        self.publish_params();
+        Ok(())
+    }
+
+    /// Makes `path` the tuning settings file of arm `arm_index`, which
+    /// [`ArmSim::save_settings`] (or the save key) writes the arm's parameters to.
+    pub fn set_settings_file<P: AsRef<Path>>(&mut self, arm_index: usize, path: P) -> Result<(), String> {
+        let sim_arm = self.arms.get_mut(arm_index).ok_or_else(|| format!("No arm with index {}", arm_index))?;
+        sim_arm.settings_file = Some(path.as_ref().to_path_buf());
+        Ok(())
+    }
+
+    /// Applies the settings saved in arm `arm_index`'s settings file (see
+    /// [`TuningSettings`]), including the control period.
+    ///
+    /// The live simulation steps with the measured frame time; the saved control period
+    /// is the nominal one, e.g. for a hardware loop running the same gains.
+    pub fn load_settings(&mut self, arm_index: usize) -> Result<(), String> {
+        let sim_arm = self.arms.get_mut(arm_index).ok_or_else(|| format!("No arm with index {}", arm_index))?;
+        let path = sim_arm.settings_file.as_ref().ok_or_else(|| format!("Arm {} has no settings file", arm_index))?;
+        let settings = TuningSettings::load(path)?;
+        settings.apply(sim_arm)?;
+        self.control_period = settings.dt;
+        self.publish_params();
+        Ok(())
+    }
+
+    /// Saves the parameters of arm `arm_index` and the control period to its settings
+    /// file, returning the file's path.
+    pub fn save_settings(&self, arm_index: usize) -> Result<PathBuf, String> {
+        let sim_arm = self.arms.get(arm_index).ok_or_else(|| format!("No arm with index {}", arm_index))?;
+        let path = sim_arm.settings_file.clone().ok_or_else(|| format!("Arm {} has no settings file", arm_index))?;
+        TuningSettings::capture(sim_arm, self.control_period).save(&path)?;
+        Ok(path)
+    }
+
+    fn publish_params(&self) {
+        if let Ok(mut params) = self.arm_params.lock() {
+            *params = self.arms.iter().map(|a| a.params()).collect();
+        }
+    }
+
+    /// Recorded operations of an arm, for listing what an undo would revert.
+    pub fn history(&self, arm_index: usize) -> Option<&MotionHistory<J>> {
+        self.arms.get(arm_index).map(|a| &a.history)
+    }
+
+    /// Publishes who controls each arm to all clients.
+    fn publish_arbiter_states(&self, now: Instant) {
+        if let Ok(mut states) = self.arbiter_states.lock() {
+            *states = self.arms.iter().map(|a| a.arbiter.state(now)).collect();
+        }
+    }
+
+    /// Step every arm in the scene, logging each step if telemetry is on
+    fn step(&mut self, now: Instant) -> Result<(), String> {
+        for (i, sim_arm) in self.arms.iter_mut().enumerate() {
+            let mut task_vel = sim_arm.controller_task_vel();
+            let mut sweep = self.sweep.as_mut().filter(|s| s.arm == i);
+            let mut joint_excitation = None;
+            if let Some(sweep) = &mut sweep {
+                let input = sweep.excitation(now);
+                match sweep.test.axis {
+                    SweepAxis::Joint(j) => joint_excitation = Some((j, input)),
+                    SweepAxis::Task(k) => task_vel[k] += input,
+                }
+            }
+            if let Some(logger) = &mut self.telemetry
+                && let Err(e) = logger.record(now, i, self.dt, &task_vel, &sim_arm.joint_pos, &sim_arm.joint_vel)
+            {
+                eprintln!("Telemetry error: {}", e);
+                self.telemetry = None;
+            }
+            sim_arm.step(&task_vel, joint_excitation, self.dt, &mut self.profiler)?;
+            if let Some(sweep) = sweep {
+                let ee = sim_arm.link_ee_pose();
+                sweep.record(self.dt, &sim_arm.link_velocities(), ee);
+            }
+        }
+
+        if let Some(sweep) = self.sweep.take_if(|s| s.is_finished(now)) {
+            let samples = sweep.test.num_samples();
+            let points = sweep.finish()?;
+            println!("Frequency response done: {} samples, {} frequencies", samples, points.len());
+            for p in &points {
+                println!("  {:8.3} Hz  {:7.2} dB  {:8.1} deg", p.frequency, p.gain_db(), p.phase);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn reset(&mut self) {
+        for sim_arm in &mut self.arms {
+            sim_arm.reset();
+        }
+        println!("Reset velocities and joint positions to zero.");
+    }
+
+    // ----- Visualization Helpers -----
     fn draw_dh_arm(
         window: &mut Window,
         arm: &DHArmModel<F, J, S>,
         joint_nodes: &mut [SceneNode],
-        world_pose: &Pose,
-        world_axis_len: f32,
-        frame_axis_len: f32,
+        base_pose: &Pose,
+        display: &SceneDisplay,
     ) {
         let poses = arm.frame_poses();
 
-        // Draw world frame
-        Self::draw_frame_axes(window, world_pose, world_axis_len);
+        // Draw the arm's base frame
+        if let Some(length) = display.world_axes {
+            draw_frame_axes(window, base_pose, length);
+        }
 
         let mut prev_pos = Point3::new(
-            world_pose.position.x as f32,
-            world_pose.position.y as f32,
-            world_pose.position.z as f32,
+            base_pose.position.x as f32,
+            base_pose.position.y as f32,
+            base_pose.position.z as f32,
         );
 
         for (i, pose) in poses.iter().enumerate() {
+            // Frame poses are relative to the arm's base; place them in the world
+            let pose = base_pose.compose(pose);
             let current_pos = Point3::new(
                 pose.position.x as f32,
                 pose.position.y as f32,
@@ -114,50 +1374,141 @@ impl<const F: usize, const J: usize, S: IkSolver<J>> ArmSim<F, J, S> {
 
             // Update joint marker
             joint_nodes[i].set_local_translation(Translation3::from(current_pos));
+            joint_nodes[i].set_visible(display.joint_markers.is_some());
+            if let Some(radius) = display.joint_markers {
+                let scale = radius / JOINT_MARKER_RADIUS;
+                joint_nodes[i].set_local_scale(scale, scale, scale);
+            }
 
             // Draw link
             window.draw_line(&prev_pos, &current_pos, &Point3::new(0.0, 0.0, 1.0));
 
             // Draw frame axes
-            Self::draw_frame_axes(window, pose, frame_axis_len);
+            if let Some(length) = display.frame_axes {
+                draw_frame_axes(window, &pose, length);
+            }
 
             prev_pos = current_pos;
         }
     }
 
-    
 
-    fn get_keyboard_input(&mut self, window: &Window) {
+
+    /// Draws an arm's manipulability ellipsoid at its end-effector and, if enabled,
+    /// shades its joint markers by contribution; returns the ellipsoid for the HUD.
+    fn draw_manipulability(
+        window: &mut Window,
+        sim_arm: &mut SimArm<F, J, S>,
+        joint_nodes: &mut [SceneNode],
+        display: &ManipulabilityDisplay,
+    ) -> ManipulabilityEllipsoid {
+        let ellipsoid = sim_arm.arm.manipulability_ellipsoid();
+        let at_ee = Pose::new(sim_arm.ee_world_pose().position, sim_arm.base_pose.rotation);
+        draw_ellipsoid(window, &ellipsoid, &at_ee, display.scale, &Point3::from(ELLIPSOID_COLOR));
+
+        if display.color_joints {
+            let contributions = sim_arm.arm.joint_contributions();
+            let max = contributions.iter().cloned().fold(0.0, f64::max);
+            // Joint markers sit at the frame of the joint's DH row
+            for (node, row) in joint_nodes.iter_mut().zip(sim_arm.arm.dh_table().rows()) {
+                let c: [f32; 3] = match row.frame_type() {
+                    Some(FrameType::Joint(j)) => {
+                        let t = if max > 0.0 { (contributions[j] / max) as f32 } else { 0.0 };
+                        core::array::from_fn(|k| IDLE_JOINT_COLOR[k] + t * (JOINT_COLOR[k] - IDLE_JOINT_COLOR[k]))
+                    }
+                    _ => FIXED_FRAME_COLOR,
+                };
+                node.set_color(c[0], c[1], c[2]);
+            }
+        }
+        ellipsoid
+    }
+
+    fn get_keyboard_input(&mut self, window: &Window, now: Instant) {
         // Placeholder for future keyboard input handling if needed
         if window.get_key(Key::Space) == Action::Press { self.reset(); }
 
-        // Linear velocities
-        if window.get_key(Key::Z) == Action::Press { self.task_vel[0] += 1.0; }
-        if window.get_key(Key::X) == Action::Press { self.task_vel[0] -= 1.0; }
-        if window.get_key(Key::C) == Action::Press { self.task_vel[1] += 1.0; }
-        if window.get_key(Key::V) == Action::Press { self.task_vel[1] -= 1.0; }
-        if window.get_key(Key::B) == Action::Press { self.task_vel[2] += 1.0; }
-        if window.get_key(Key::N) == Action::Press { self.task_vel[2] -= 1.0; }
+        // Toggles react once per key press rather than every frame the key is held
+        for event in window.events().iter() {
+            if let WindowEvent::Key(key, Action::Press, _) = event.value {
+                if key == Key::R {
+                    self.toggle_recording();
+                }
+                if key == MANIPULABILITY_KEY {
+                    self.show_manipulability = !self.show_manipulability;
+                }
+                if key == CLEAN_VIEW_KEY {
+                    self.clean_view = !self.clean_view;
+                }
+                if key == PROPS_KEY {
+                    self.display.props = !self.display.props;
+                }
+                if key == SAVE_SETTINGS_KEY {
+                    for i in (0..self.arms.len()).filter(|&i| self.arms[i].settings_file.is_some()) {
+                        match self.save_settings(i) {
+                            Ok(path) => println!("Saved arm {} settings to {}", i, path.display()),
+                            Err(e) => eprintln!("Saving arm {} settings failed: {}", i, e),
+                        }
+                    }
+                }
+                for sim_arm in &mut self.arms {
+                    if sim_arm.bindings.as_ref().is_some_and(|b| b.toggle_frame == key) {
+                        sim_arm.toggle_jog_frame();
+                    }
+                    if sim_arm.bindings.as_ref().is_some_and(|b| b.toggle_fine == key) {
+                        sim_arm.toggle_fine_jog();
+                    }
+                    let undo = sim_arm.bindings.as_ref().is_some_and(|b| b.undo == key);
+                    let redo = sim_arm.bindings.as_ref().is_some_and(|b| b.redo == key);
+                    if (undo || redo)
+                        && let Err(e) = sim_arm.step_history(LOCAL_CLIENT, LOCAL_PRIORITY, now, 1, redo)
+                    {
+                        println!("{} rejected: {}", if redo { "Redo" } else { "Undo" }, e);
+                    }
+                }
+            }
+        }
 
-        // Angular velocities
-        if window.get_key(Key::A) == Action::Press { self.task_vel[3] += 3.0; }
-        if window.get_key(Key::S) == Action::Press { self.task_vel[3] -= 3.0; }
-        if window.get_key(Key::D) == Action::Press { self.task_vel[4] += 3.0; }
-        if window.get_key(Key::F) == Action::Press { self.task_vel[4] -= 3.0; }
-        if window.get_key(Key::G) == Action::Press { self.task_vel[5] += 3.0; }
-        if window.get_key(Key::H) == Action::Press { self.task_vel[5] -= 3.0; }
+        // Held jog keys are ignored while another client controls the arm
+        for sim_arm in &mut self.arms {
+            if let Some(delta) = sim_arm.held_jog(window) {
+                // Fine jogs are capped at the fine fraction of the full speed too
+                let scale = sim_arm.jog_scale(window);
+                let max: [f64; 6] = core::array::from_fn(|axis| {
+                    sim_arm.bindings.as_ref().map_or(f64::INFINITY, |b| scale * b.max_speed(axis))
+                });
+                let _ = sim_arm.command(LOCAL_CLIENT, LOCAL_PRIORITY, now, |v| {
+                    for ((v, d), max) in v.iter_mut().zip(delta).zip(max) { *v = (*v + d).clamp(-max, max); }
+                });
+            }
+        }
     }
 
 
     pub fn run(&mut self) {
         println!("=== Continuous Arm Simulation (Kiss3d) ===");
         println!("Controls:");
-        println!("z/x, c/v, b/n  -> linear X/Y/Z +/-");
-        println!("a/s, d/f, g/h  -> angular X/Y/Z +/-");
-        println!("space          -> reset");
-        println!("q              -> quit\n");
+        for (i, sim_arm) in self.arms.iter().enumerate() {
+            let Some(b) = &sim_arm.bindings else { continue };
+            println!("Arm {}:", i);
+            println!("  {:?}/{:?}, {:?}/{:?}, {:?}/{:?}  -> linear X/Y/Z +/-",
+                b.axes[0].0, b.axes[0].1, b.axes[1].0, b.axes[1].1, b.axes[2].0, b.axes[2].1);
+            println!("  {:?}/{:?}, {:?}/{:?}, {:?}/{:?}  -> angular X/Y/Z +/-",
+                b.axes[3].0, b.axes[3].1, b.axes[4].0, b.axes[4].1, b.axes[5].0, b.axes[5].1);
+            println!("  {:?}              -> toggle world/tool jog frame", b.toggle_frame);
+            println!("  {:?} (hold) / {:?} -> fine jog ({}x finer)", b.fine_modifier, b.toggle_fine, 1.0 / b.fine_scale);
+            println!("  {:?}/{:?}            -> undo/redo last jog or nudge", b.undo, b.redo);
+        }
+        println!("r              -> start/stop recording frames");
+        println!("e              -> show/hide manipulability ellipsoids");
+        println!("k              -> clean view (hide axes, overlays and text) for recording");
+        println!("p              -> show/hide props");
+        println!("o              -> save tuning settings (arms with a settings file)");
+        println!("space          -> reset (also releases every arm's control lease)");
+        println!("q              -> quit");
+        println!("Keyboard jogs take control of an arm; remote commands are rejected while it is held.\n");
 
-        let mut last_time = Instant::now();
+        let mut last_time = self.now();
 
         let target = Point3::new(0.0f32, 0.0f32, 30.0f32);
         let eye = Point3::new(40.0f32, -80.0f32, 50.0f32);
@@ -169,51 +1520,166 @@ impl<const F: usize, const J: usize, S: IkSolver<J>> ArmSim<F, J, S> {
         window.set_framerate_limit(None);
         let font = Font::default();
 
-        let mut joint_nodes: Vec<SceneNode> = Vec::new();
-        for _ in 0..F {
-            let mut s = window.add_sphere(0.05);
-            s.set_color(1.0, 0.0, 0.0);
-            joint_nodes.push(s);
+        let mut joint_nodes: Vec<Vec<SceneNode>> = Vec::new();
+        for _ in &self.arms {
+            let mut nodes = Vec::new();
+            for _ in 0..F {
+                let mut s = window.add_sphere(JOINT_MARKER_RADIUS);
+                s.set_color(JOINT_COLOR[0], JOINT_COLOR[1], JOINT_COLOR[2]);
+                nodes.push(s);
+            }
+            joint_nodes.push(nodes);
         }
 
         //let dt_duration = Duration::from_secs_f64(self.dt);
-        let world_axis_len = 1.0;
-        let frame_axis_len = 0.25;
         let world_pose = Pose::new(Vector3::new(0.0, 0.0, 0.0), Matrix3::identity());
 
-        Self::draw_board(&mut window, -5.0, 35.0, 90.0, 60.0);
-
+        let mut render_start = Instant::now();
+        let mut last_profile_log = Instant::now();
         while window.render_with_camera(&mut camera) {
-            let delta_secs = last_time.elapsed().as_secs_f64();
-            last_time = Instant::now();
-            self.dt = delta_secs; // Update dt based on actual frame time for more accurate simulation
+            // Drawing of the previous frame plus the render that just finished closes its step
+            self.profiler.record(Phase::Render, render_start.elapsed());
+            self.profiler.end_step();
+
+            // Capture the frame that was just rendered
+            if let Some(recorder) = &mut self.recorder
+                && let Err(e) = recorder.capture(&window)
+            {
+                eprintln!("Recording error: {}", e);
+                self.toggle_recording();
+            }
+            if last_profile_log.elapsed() >= PROFILE_LOG_INTERVAL {
+                println!("step profile (avg): {}", self.profiler.average());
+                last_profile_log = Instant::now();
+            }
 
             if window.get_key(Key::Q) == Action::Press { break; }
 
-            self.get_keyboard_input(&window);
+            if self.replay.is_some() {
+                // Playback owns the arms; live input and stepping resume once it ends
+                if !self.advance_replay() {
+                    self.replay = None;
+                }
+                last_time = self.now();
+            } else {
+                let now = self.now();
+                self.dt = now.saturating_duration_since(last_time).as_secs_f64(); // Update dt based on actual frame time for more accurate simulation
+                last_time = now;
 
-            let _ = self.step();
-            println!("joint_vel: {:?}, joint_pos: {:?}", &self.joint_vel, &self.joint_pos);
+                self.process_remote_commands(now);
+                self.get_keyboard_input(&window, now);
+                for sim_arm in &mut self.arms {
+                    sim_arm.update_lease(now);
+                }
+                self.publish_arbiter_states(now);
 
-            Self::draw_dh_arm(
-                &mut window,
-                &self.arm,
-                &mut joint_nodes,
-                &world_pose,
-                world_axis_len,
-                frame_axis_len,
-            );
+                // A clock that has not moved gives no step to integrate
+                if self.dt > 0.0 {
+                    let _ = self.step(now);
+                }
+            }
+            let now = self.now();
+
+            render_start = Instant::now();
+            let display = self.scene_display();
+            // Draw world frame
+            if let Some(length) = display.world_axes {
+                draw_frame_axes(&mut window, &world_pose, length);
+            }
+            self.scene.sync(&mut window, display.props);
+            if display.overlays
+                && let Some(preview) = &self.trajectory_preview
+            {
+                preview.draw(&mut window, &Point3::from(PREVIEW_COLOR), PREVIEW_AXIS_LEN);
+            }
 
             let mut vel_text = String::new();
-            write!(&mut vel_text,
-                "Vx: {:.2}, Vy: {:.2}, Vz: {:.2}\nWx: {:.2}, Wy: {:.2}, Wz: {:.2}",
-                self.task_vel[0], self.task_vel[1], self.task_vel[2],
-                self.task_vel[3], self.task_vel[4], self.task_vel[5]
-            ).unwrap();
-            window.draw_text(&vel_text, &Point2::new(10.0, 10.0), 60.0, &font, &Point3::new(1.0, 1.0, 1.0));
-            
+            for (i, (sim_arm, nodes)) in self.arms.iter_mut().zip(joint_nodes.iter_mut()).enumerate() {
+                println!("arm {}: joint_vel: {:?}, joint_pos: {:?}", i, &sim_arm.joint_vel, &sim_arm.joint_pos);
+
+                // Draw (and trace) where the links are, which lags the motors when elastic
+                let link_pos = sim_arm.link_positions();
+                sim_arm.arm.set_joint_positions(&link_pos);
+
+                Self::draw_dh_arm(
+                    &mut window,
+                    &sim_arm.arm,
+                    nodes,
+                    &sim_arm.base_pose,
+                    &display,
+                );
+                let manipulability = (self.show_manipulability && display.overlays).then(|| {
+                    Self::draw_manipulability(&mut window, sim_arm, nodes, &self.manipulability)
+                });
+                if manipulability.is_none() || !self.manipulability.color_joints {
+                    for node in nodes.iter_mut() {
+                        node.set_color(JOINT_COLOR[0], JOINT_COLOR[1], JOINT_COLOR[2]);
+                    }
+                }
+
+                if self.trail_duration.is_some() {
+                    // Keep tracing while hidden so the trail is whole when shown again
+                    let ee_pose = sim_arm.ee_world_pose();
+                    sim_arm.trail.push(now, &ee_pose);
+                    if display.overlays {
+                        sim_arm.trail.draw(&mut window, &Point3::from(TRAIL_COLOR));
+                    }
+                }
+
+                let v = &sim_arm.task_vel;
+                writeln!(&mut vel_text,
+                    "Arm {}: Vx: {:.2}, Vy: {:.2}, Vz: {:.2} | Wx: {:.2}, Wy: {:.2}, Wz: {:.2} | Jog frame: {:?}{} | Undo: {} / redo: {} | Control: {}",
+                    i, v[0], v[1], v[2], v[3], v[4], v[5], sim_arm.jog_frame,
+                    if sim_arm.fine_jog { " (fine)" } else { "" },
+                    sim_arm.history.undo_entries().count(), sim_arm.history.redo_entries().count(),
+                    sim_arm.arbiter.state(now)
+                ).unwrap();
+                if let Some(PlannedMove::Script(player)) = &sim_arm.planned {
+                    writeln!(&mut vel_text,
+                        "  Script: keyframe {} / {}{} | Gripper: {:?}",
+                        player.keyframe() + 1, player.script().keyframes.len(),
+                        if player.script().repeat { " (looping)" } else { "" }, sim_arm.gripper
+                    ).unwrap();
+                    if !sim_arm.io.outputs.is_empty() {
+                        let outputs: Vec<String> = sim_arm.io.outputs.iter()
+                            .map(|(channel, on)| format!("{}: {}", channel, if *on { "on" } else { "off" }))
+                            .collect();
+                        writeln!(&mut vel_text, "  Outputs: {}", outputs.join(", ")).unwrap();
+                    }
+                }
+                if let Some(ellipsoid) = manipulability {
+                    writeln!(&mut vel_text,
+                        "  Manipulability: {:.0} | isotropy {:.3} | radii {:.1} / {:.1} / {:.1}",
+                        ellipsoid.measure(), ellipsoid.isotropy(), ellipsoid.radii[0], ellipsoid.radii[1], ellipsoid.radii[2]
+                    ).unwrap();
+                }
+            }
+            writeln!(&mut vel_text, "Step: {}", self.profiler.average()).unwrap();
+            if let Some(recorder) = &self.recorder {
+                writeln!(&mut vel_text, "REC {} frames", recorder.frames()).unwrap();
+            }
+            if let Some(replay) = &self.replay {
+                let end = replay.records.last().map_or(0.0, |r| r.time);
+                writeln!(&mut vel_text, "REPLAY {:.1} / {:.1} s", replay.clock.elapsed().as_secs_f64(), end).unwrap();
+            }
+            if let Some(sweep) = &self.sweep {
+                writeln!(&mut vel_text, "SWEEP {:.1} / {:.1} s", sweep.elapsed(now), sweep.test.duration()).unwrap();
+            }
+            if display.hud {
+                window.draw_text(&vel_text, &Point2::new(10.0, 10.0), 60.0, &font, &Point3::new(1.0, 1.0, 1.0));
+            }
+
 
             //std::thread::sleep(dt_duration);
         }
+
+        if self.recorder.is_some() {
+            self.toggle_recording();
+        }
+        if self.telemetry.is_some()
+            && let Err(e) = self.stop_telemetry()
+        {
+            eprintln!("Telemetry error: {}", e);
+        }
     }
 }
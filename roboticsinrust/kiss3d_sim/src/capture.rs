@@ -0,0 +1,96 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+use kiss3d::window::Window;
+
+/// Where recorded frames go.
+#[derive(Debug, Clone)]
+pub enum CaptureFormat {
+    /// `frame_000000.png`, `frame_000001.png`, ... in `dir`.
+    PngSequence { dir: PathBuf },
+    /// Video encoded by piping raw frames to `ffmpeg` (must be on `PATH`).
+    Video { path: PathBuf, fps: u32 },
+}
+
+/// Dumps rendered frames of a running simulation to disk.
+pub struct FrameRecorder {
+    format: CaptureFormat,
+    frames: usize,
+    /// Frame size fixed by the first captured frame.
+    size: Option<(u32, u32)>,
+    encoder: Option<(Child, ChildStdin)>,
+}
+
+impl FrameRecorder {
+    pub fn start(format: CaptureFormat) -> Result<Self, String> {
+        if let CaptureFormat::PngSequence { dir } = &format {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create capture directory {}: {}", dir.display(), e))?;
+        }
+        Ok(Self { format, frames: 0, size: None, encoder: None })
+    }
+
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Grabs the frame the window just rendered.
+    pub fn capture(&mut self, window: &Window) -> Result<(), String> {
+        let image = window.snap_image();
+        let size = image.dimensions();
+        match self.size {
+            None => self.size = Some(size),
+            // Encoders need a constant frame size; skip frames taken while resizing
+            Some(expected) if expected != size => return Ok(()),
+            Some(_) => {}
+        }
+
+        match &self.format {
+            CaptureFormat::PngSequence { dir } => {
+                let path = dir.join(format!("frame_{:06}.png", self.frames));
+                image.save(&path)
+                    .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            }
+            CaptureFormat::Video { path, fps } => {
+                if self.encoder.is_none() {
+                    self.encoder = Some(spawn_ffmpeg(path, *fps, size)?);
+                }
+                let (_, stdin) = self.encoder.as_mut().unwrap();
+                stdin.write_all(&image.into_raw())
+                    .map_err(|e| format!("Failed to send frame to ffmpeg: {}", e))?;
+            }
+        }
+
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Flushes the recording and returns the number of frames written.
+    pub fn finish(mut self) -> Result<usize, String> {
+        if let Some((mut child, stdin)) = self.encoder.take() {
+            // Closing stdin tells ffmpeg the stream has ended
+            drop(stdin);
+            let status = child.wait().map_err(|e| format!("Failed to wait for ffmpeg: {}", e))?;
+            if !status.success() {
+                return Err(format!("ffmpeg exited with {}", status));
+            }
+        }
+        Ok(self.frames)
+    }
+}
+
+fn spawn_ffmpeg(path: &Path, fps: u32, (width, height): (u32, u32)) -> Result<(Child, ChildStdin), String> {
+    let mut child = Command::new("ffmpeg")
+        .args(["-y", "-loglevel", "error", "-f", "rawvideo", "-pix_fmt", "rgb24"])
+        .args(["-s", &format!("{}x{}", width, height), "-r", &fps.to_string(), "-i", "-"])
+        // yuv420p (for player compatibility) needs even dimensions
+        .args(["-vf", "pad=ceil(iw/2)*2:ceil(ih/2)*2", "-pix_fmt", "yuv420p"])
+        .arg(path)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start ffmpeg: {}", e))?;
+    let stdin = child.stdin.take().ok_or("ffmpeg stdin unavailable")?;
+    Ok((child, stdin))
+}
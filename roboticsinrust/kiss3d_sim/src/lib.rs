@@ -0,0 +1,12 @@
+//! Kiss3D visualization of DH arm models: task-space jogging of one or more arms
+//! (`arm_sim`), arbitrated remote command clients (`remote`), the static task
+//! environment drawn around them (`scene`), end-effector trails and planned
+//! path previews (`trace`), frame/video recording (`capture`) and
+//! frequency-response sweeps (`sweep`).
+
+pub mod arm_sim;
+pub mod capture;
+pub mod remote;
+pub mod scene;
+pub mod sweep;
+pub mod trace;
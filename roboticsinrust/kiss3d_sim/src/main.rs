@@ -1,61 +1,29 @@
-mod arm_sim;
-
+use dh_arm_model::task_space_limits::{TaskSpaceLimits, WorkspaceBox};
 use dh_arm_model::task_space_pid_controller::TaskSpacePidController;
-use dh_arm_model::joint::{Joint, JointType};
-use dh_arm_model::dh::{DHTable, DHRow};
-use dh_arm_model::dh_arm_model::DHArmModel;
-use arm_sim::ArmSim;
-use nalgebra::SVector;
-use dh_arm_model::inverse_kinematics_solvers::UrtIkSolver;
-
-const NUM_FRAMES: usize = 7;
-const NUM_JOINTS: usize = 6;
+use dh_arm_model::dh::Pose;
+use dh_arm_model::frequency_response::{Excitation, FrequencyResponseTest, SweepAxis};
+use dh_arm_model::keyframe_script::KeyframeScript;
+use dh_arm_model::settings::TuningSettings;
+use dh_arm_model::urt;
+use dh_arm_model::velocity_filter::VelocityFilter;
+use kiss3d_sim::arm_sim::{ArmSim, ReplayMode};
+use kiss3d_sim::remote;
+use nalgebra::{Matrix3, SVector, Vector3};
+use std::path::Path;
+use std::time::Duration;
 
 fn main() {
-    // URT robot 6 DOF arm
-    let table = DHTable::<NUM_FRAMES, NUM_JOINTS>::new([
-        // Insert DH rows with joint_index
-        DHRow::new(0.0, 0.0, 9.0, 0.0, false, Some(0)),   // joint 1
-        DHRow::new(0.0, -90.0, 0.0, -90.0, false, Some(1)), // joint 2
-        DHRow::new(24.0, 0.0, 0.0, 90.0, false, Some(2)),  // joint 3
-        DHRow::new(0.0, 90.0, 22.0, 0.0, false, Some(3)),  // joint 4
-        DHRow::new(0.0, -90.0, 0.0, 0.0, false, Some(4)),  // joint 5
-        DHRow::new(0.0, 90.0, 15.0, 0.0, false, Some(5)),  // joint 6
-        // Add end-effector fixed frame (no joint)
-        DHRow::new(0.0, 0.0, 15.0, 0.0, true, None)
-    ]);
+    let args: Vec<String> = std::env::args().collect();
+    let arg_value = |flag: &str| args.iter().position(|a| a == flag).and_then(|i| args.get(i + 1));
 
-    // Create joints
-    let joints = [
-        Joint::new(JointType::Revolute, None, None), // joint 1
-        Joint::new(JointType::Revolute, None, None), // joint 2
-        Joint::new(JointType::Revolute, None, None), // joint 3
-        Joint::new(JointType::Revolute, None, None), // joint 4
-        Joint::new(JointType::Revolute, None, None), // joint 5
-        Joint::new(JointType::Revolute, None, None), // joint 6
-    ];
-
-    let urt_ik_link_parameters = vec![
-        9.0,  // l1
-        34.0, // l2
-        0.0,  // l3
-        32.0, // l4
-        15.0, // l5
-    ];
-
-    // Create Arm with default damping
-    let arm = DHArmModel::<NUM_FRAMES, NUM_JOINTS, UrtIkSolver>::new(
-        table,
-        joints,
-        None, // Use default damping
-        UrtIkSolver,
-        urt_ik_link_parameters,
-    );
+    // URT robot 6 DOF arm with default damping (uses its closed-form Jacobian)
+    let mut arm = urt::urt_arm(None);
+    arm.dh_table().print_diagnostics();
 
     // Choose dt for simulation (seconds)
-    let dt = 0.05; // 50 ms per step
+    let mut dt = 0.05; // 50 ms per step
 
-    let controller = TaskSpacePidController::new(
+    let mut controller = TaskSpacePidController::new(
         // Proportional Gains (Kp) - [x, y, z, roll, pitch, yaw]
         SVector::<f64, 6>::from([1.0, 1.0, 1.0, 0.0, 0.0, 0.0]), 
         
@@ -66,6 +34,103 @@ fn main() {
         SVector::<f64, 6>::from([0.0, 0.0, 0.0, 0.0, 0.0, 0.0]), 
     );
 
+    // Keep the end-effector on the arm's side of the board and cap its speed
+    controller.limits = TaskSpaceLimits {
+        workspace: Some(WorkspaceBox::new(Vector3::new(-60.0, -60.0, 0.0), Vector3::new(34.0, 60.0, 90.0))),
+        max_linear_speed: Some(15.0),
+        max_angular_speed: Some(60.0),
+    };
+
+    // --settings <file>: start from the gains, damping and dt saved there (O key or
+    // `param save 0` writes them back)
+    let settings_file = arg_value("--settings");
+    if let Some(file) = settings_file
+        && Path::new(file).exists()
+    {
+        let settings = TuningSettings::load(file).expect("Failed to load settings");
+        let mut tuned = (arm, controller);
+        settings.apply(&mut tuned).expect("Failed to apply settings");
+        (arm, controller) = tuned;
+        dt = settings.dt;
+    }
+
+    // Catch gains the linearised loop already shows to diverge at this step size
+    if let Some(analysis) = controller.analyze_gains(&mut arm, dt)
+        && analysis.is_unstable()
+    {
+        eprintln!(
+            "Warning: controller gains are unstable at dt = {} s (spectral radius {:.3})",
+            dt, analysis.spectral_radius
+        );
+    }
+
     let mut sim = ArmSim::new(arm, controller,  dt);
+
+    // Yellow board in front of the arm
+    let board = sim.add_box(Pose::new(Vector3::new(35.0, 0.0, 25.0), Matrix3::identity()), [0.2, 90.0, 60.0]);
+    sim.scene_mut().set_color(board, [1.0, 1.0, 0.0]).unwrap();
+
+    // Show where the end-effector has been over the last few seconds
+    sim.set_ee_trail(Some(Duration::from_secs(5)));
+
+    // Second command source alongside the keyboard, arbitrated against it
+    remote::spawn_stdin_client(sim.remote_client("stdin", 50));
+
+    // --log <file>: record telemetry, with a <file>.manifest describing the run
+    // (--scenario <name> names it); --replay <file> [--rerun]: play a log back
+    // (re-driving joint states, or re-running the controller with --rerun);
+    // --bode <stem>: sweep the x velocity and write <stem>.csv / <stem>.svg;
+    // --script <file>: play a keyframe script on the arm;
+    // --clean: start without axes, overlays and text; --no-board: hide the board;
+    // --smooth: ramp jog starts and stops instead of stepping the joint velocities;
+    // --gaze <x,y,z>: keep the tool pointed at that world point while jogging it
+    if let Some(file) = settings_file {
+        sim.set_settings_file(0, file).unwrap();
+    }
+
+    if let Some(log_file) = arg_value("--replay") {
+        let mode = if args.iter().any(|a| a == "--rerun") { ReplayMode::Controller } else { ReplayMode::JointStates };
+        if let Err(e) = sim.replay(log_file, mode) {
+            eprintln!("Replay failed: {}", e);
+        }
+        return;
+    }
+    if let Some(scenario) = arg_value("--scenario") {
+        sim.set_scenario(scenario);
+    } else if let Some(script_file) = arg_value("--script") {
+        sim.set_scenario(&format!("script {}", script_file));
+    }
+    if let Some(log_file) = arg_value("--log") {
+        sim.start_telemetry(log_file).expect("Failed to start telemetry log");
+    }
+    if let Some(stem) = arg_value("--bode") {
+        // 0.1-4 Hz stays well below the 10 Hz Nyquist rate of the 50 ms loop
+        let excitation = Excitation::Chirp { start_hz: 0.1, end_hz: 4.0, duration: 60.0 };
+        let test = FrequencyResponseTest::new(SweepAxis::Task(0), 3.0, excitation);
+        sim.start_frequency_response(0, test, stem).expect("Failed to start frequency response");
+    }
+    if args.iter().any(|a| a == "--clean") {
+        sim.set_clean_view(true);
+    }
+    if args.iter().any(|a| a == "--no-board") {
+        sim.scene_mut().set_visible(board, false).unwrap();
+    }
+    if args.iter().any(|a| a == "--smooth") {
+        // 90 deg/s² with 360 deg/s³ reaches a 30 deg/s jog in about 0.6 s
+        let filter = VelocityFilter::slew_rate(90.0, Some(360.0)).unwrap();
+        sim.set_output_filter(0, Some(filter)).unwrap();
+    }
+    if let Some(point) = arg_value("--gaze") {
+        let coords: Vec<f64> = point.split(',').map(|c| c.trim().parse().expect("Invalid --gaze point")).collect();
+        let [x, y, z] = coords[..] else { panic!("--gaze expects x,y,z") };
+        let target = Vector3::new(x, y, z);
+        sim.add_sphere(target, 1.0);
+        sim.set_gaze_target(0, Some(target)).unwrap();
+    }
+    if let Some(script_file) = arg_value("--script") {
+        let script = KeyframeScript::load(script_file).expect("Failed to load keyframe script");
+        sim.play_script(0, script).expect("Failed to start keyframe script");
+    }
+
     sim.run();
 }
@@ -0,0 +1,220 @@
+use std::io::BufRead;
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use dh_arm_model::command_arbiter::ArbiterState;
+use dh_arm_model::parameters::{Param, ParamSpec, ParamValue};
+
+/// Command sent to the simulator by a client other than the local UI.
+#[derive(Debug, Clone)]
+pub enum RemoteCommand {
+    /// Sets the jog velocity of an arm ([vx, vy, vz] world, [wx, wy, wz] deg/s).
+    TaskVelocity { arm_index: usize, task_vel: [f64; 6] },
+    /// Moves the end-effector by a small offset ([dx, dy, dz] world, [droll, dpitch, dyaw] deg).
+    Nudge { arm_index: usize, offset: [f64; 6] },
+    /// Undoes the last `steps` jogs and nudges of an arm.
+    Undo { arm_index: usize, steps: usize },
+    /// Redoes the last `steps` undone operations of an arm.
+    Redo { arm_index: usize, steps: usize },
+    /// Gives up control of an arm.
+    Release { arm_index: usize },
+    /// Changes a tunable parameter of an arm (see [`RemoteClient::set_param`]).
+    SetParam { arm_index: usize, name: String, value: ParamValue },
+    /// Saves an arm's parameters to its settings file.
+    SaveSettings { arm_index: usize },
+}
+
+#[derive(Debug, Clone)]
+pub struct RemoteMessage {
+    pub client: String,
+    pub priority: u8,
+    pub command: RemoteCommand,
+}
+
+/// Handle through which a remote client commands `ArmSim`.
+///
+/// Commands go through the same lease arbitration as the local UI; the
+/// arbitration state of every arm is published back to all clients, as are the
+/// arms' tunable parameters.
+#[derive(Clone)]
+pub struct RemoteClient {
+    name: String,
+    priority: u8,
+    tx: Sender<RemoteMessage>,
+    states: Arc<Mutex<Vec<ArbiterState>>>,
+    params: Arc<Mutex<Vec<Vec<Param>>>>,
+}
+
+impl RemoteClient {
+    pub(crate) fn new(
+        name: &str,
+        priority: u8,
+        tx: Sender<RemoteMessage>,
+        states: Arc<Mutex<Vec<ArbiterState>>>,
+        params: Arc<Mutex<Vec<Vec<Param>>>>,
+    ) -> Self {
+        Self { name: name.to_string(), priority, tx, states, params }
+    }
+
+    fn send(&self, command: RemoteCommand) -> Result<(), String> {
+        self.tx
+            .send(RemoteMessage { client: self.name.clone(), priority: self.priority, command })
+            .map_err(|_| "Simulator is no longer running".to_string())
+    }
+
+    pub fn send_task_velocity(&self, arm_index: usize, task_vel: [f64; 6]) -> Result<(), String> {
+        self.send(RemoteCommand::TaskVelocity { arm_index, task_vel })
+    }
+
+    pub fn send_nudge(&self, arm_index: usize, offset: [f64; 6]) -> Result<(), String> {
+        self.send(RemoteCommand::Nudge { arm_index, offset })
+    }
+
+    pub fn undo(&self, arm_index: usize, steps: usize) -> Result<(), String> {
+        self.send(RemoteCommand::Undo { arm_index, steps })
+    }
+
+    pub fn redo(&self, arm_index: usize, steps: usize) -> Result<(), String> {
+        self.send(RemoteCommand::Redo { arm_index, steps })
+    }
+
+    pub fn release(&self, arm_index: usize) -> Result<(), String> {
+        self.send(RemoteCommand::Release { arm_index })
+    }
+
+    /// Who controls each arm, as of the last simulated frame.
+    pub fn arbitration_state(&self) -> Vec<ArbiterState> {
+        self.states.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    /// Tunable parameters of an arm (controller gains and limits, IK damping) with
+    /// their types, ranges and values, as of the last change.
+    pub fn list_params(&self, arm_index: usize) -> Result<Vec<Param>, String> {
+        self.params
+            .lock()
+            .map_err(|_| "Simulator is no longer running".to_string())?
+            .get(arm_index)
+            .cloned()
+            .ok_or_else(|| format!("No arm with index {}", arm_index))
+    }
+
+    pub fn get_param(&self, arm_index: usize, name: &str) -> Result<Param, String> {
+        self.list_params(arm_index)?
+            .into_iter()
+            .find(|p| p.spec.name == name)
+            .ok_or_else(|| format!("Unknown parameter '{}'", name))
+    }
+
+    /// Changes a tunable parameter of an arm. The value is checked against the
+    /// parameter's type and range before it is sent.
+    ///
+    /// Parameter changes do not move the arm, so they bypass the lease: a tuning
+    /// client can adjust gains while another client jogs.
+    pub fn set_param(&self, arm_index: usize, name: &str, value: ParamValue) -> Result<(), String> {
+        let value = self.get_param(arm_index, name)?.spec.check(value)?;
+        self.send(RemoteCommand::SetParam { arm_index, name: name.to_string(), value })
+    }
+
+    /// Saves an arm's current parameters to its settings file (see `ArmSim::set_settings_file`).
+    pub fn save_settings(&self, arm_index: usize) -> Result<(), String> {
+        self.send(RemoteCommand::SaveSettings { arm_index })
+    }
+
+    fn param_spec(&self, arm_index: usize, name: &str) -> Result<ParamSpec, String> {
+        self.get_param(arm_index, name).map(|p| p.spec)
+    }
+}
+
+/// Runs a remote client fed from stdin on a background thread.
+///
+/// Commands, one per line:
+///   `vel <arm> vx vy vz wx wy wz`  -> set task velocity
+///   `nudge <arm> dx dy dz dr dp dy` -> small relative end-effector move
+///   `undo <arm> [n]`               -> undo the last n (default 1) jogs/nudges
+///   `redo <arm> [n]`               -> redo the last n undone ones
+///   `release <arm>`                -> give up control
+///   `state`                        -> print who controls each arm
+///   `param list <arm>`             -> print each parameter's type, range and value
+///   `param get <arm> <name>`       -> print a parameter's value
+///   `param set <arm> <name> <v>`   -> change a parameter (`inf` lifts a speed limit)
+///   `param save <arm>`             -> save the parameters to the arm's settings file
+pub fn spawn_stdin_client(client: RemoteClient) {
+    thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if let Err(e) = handle_line(&client, &line) {
+                eprintln!("Remote command rejected: {}", e);
+            }
+        }
+    });
+}
+
+fn handle_line(client: &RemoteClient, line: &str) -> Result<(), String> {
+    let mut parts = line.split_whitespace();
+    let parse_arm = |s: Option<&str>| -> Result<usize, String> {
+        s.ok_or("Missing arm index")?
+            .parse()
+            .map_err(|e| format!("Invalid arm index: {}", e))
+    };
+
+    let parse_six = |parts: std::str::SplitWhitespace, what: &str| -> Result<[f64; 6], String> {
+        let values = parts
+            .map(|v| v.parse::<f64>().map_err(|e| format!("Invalid {} '{}': {}", what, v, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+        values
+            .try_into()
+            .map_err(|v: Vec<f64>| format!("Expected 6 {} components, got {}", what, v.len()))
+    };
+
+    match parts.next() {
+        Some("vel") => {
+            let arm_index = parse_arm(parts.next())?;
+            client.send_task_velocity(arm_index, parse_six(parts, "velocity")?)
+        }
+        Some("nudge") => {
+            let arm_index = parse_arm(parts.next())?;
+            client.send_nudge(arm_index, parse_six(parts, "offset")?)
+        }
+        Some(command @ ("undo" | "redo")) => {
+            let arm_index = parse_arm(parts.next())?;
+            let steps = match parts.next() {
+                Some(n) => n.parse().map_err(|e| format!("Invalid step count '{}': {}", n, e))?,
+                None => 1,
+            };
+            if command == "undo" { client.undo(arm_index, steps) } else { client.redo(arm_index, steps) }
+        }
+        Some("release") => client.release(parse_arm(parts.next())?),
+        Some("state") => {
+            for (i, state) in client.arbitration_state().iter().enumerate() {
+                println!("arm {}: {}", i, state);
+            }
+            Ok(())
+        }
+        Some("param") => match parts.next() {
+            Some("list") => {
+                for param in client.list_params(parse_arm(parts.next())?)? {
+                    println!("{}", param);
+                }
+                Ok(())
+            }
+            Some("get") => {
+                let arm_index = parse_arm(parts.next())?;
+                let param = client.get_param(arm_index, parts.next().ok_or("Missing parameter name")?)?;
+                println!("{} = {}", param.spec.name, param.value);
+                Ok(())
+            }
+            Some("set") => {
+                let arm_index = parse_arm(parts.next())?;
+                let name = parts.next().ok_or("Missing parameter name")?;
+                let value = client.param_spec(arm_index, name)?.parse(parts.next().ok_or("Missing value")?)?;
+                client.set_param(arm_index, name, value)
+            }
+            Some("save") => client.save_settings(parse_arm(parts.next())?),
+            Some(other) => Err(format!("Unknown param command '{}'", other)),
+            None => Err("Expected 'param list', 'param get', 'param set' or 'param save'".to_string()),
+        },
+        Some(other) => Err(format!("Unknown command '{}'", other)),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+
+use kiss3d::window::Window;
+use kiss3d::scene::SceneNode;
+use kiss3d::nalgebra::{Translation3, Point3, Vector3, Matrix3, UnitQuaternion};
+use dh_arm_model::dh::Pose;
+
+/// Handle to an object added to the scene, used to update or remove it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SceneObjectId(usize);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SceneShape {
+    /// Box with full edge lengths along the x/y/z axes of its pose.
+    Box { size: [f64; 3] },
+    Sphere { radius: f64 },
+    /// Coordinate axes (red/green/blue = x/y/z) marking a goal or reference frame.
+    Frame { axis_length: f64 },
+}
+
+#[derive(Debug, Clone)]
+pub struct SceneObject {
+    pub shape: SceneShape,
+    /// Pose in world coordinates.
+    pub pose: Pose,
+    /// RGB in 0..=1; ignored for frames, which use axis colors.
+    pub color: [f32; 3],
+    /// Hidden objects stay in the scene and can be shown again.
+    pub visible: bool,
+}
+
+/// Static task environment (fixtures, obstacles, goal markers) drawn alongside the arms.
+///
+/// Objects are plain descriptions and can be edited at any time; `sync` brings
+/// the kiss3d scene graph in line with them once per frame.
+#[derive(Default)]
+pub struct Scene {
+    objects: BTreeMap<SceneObjectId, SceneObject>,
+    next_id: usize,
+    nodes: BTreeMap<SceneObjectId, SceneNode>,
+}
+
+impl Scene {
+    pub const DEFAULT_COLOR: [f32; 3] = [0.6, 0.6, 0.6];
+
+    pub fn add(&mut self, object: SceneObject) -> SceneObjectId {
+        let id = SceneObjectId(self.next_id);
+        self.next_id += 1;
+        self.objects.insert(id, object);
+        id
+    }
+
+    pub fn add_box(&mut self, pose: Pose, size: [f64; 3]) -> SceneObjectId {
+        self.add(SceneObject { shape: SceneShape::Box { size }, pose, color: Self::DEFAULT_COLOR, visible: true })
+    }
+
+    pub fn add_sphere(&mut self, center: nalgebra::Vector3<f64>, radius: f64) -> SceneObjectId {
+        let pose = Pose::new(center, nalgebra::Matrix3::identity());
+        self.add(SceneObject { shape: SceneShape::Sphere { radius }, pose, color: Self::DEFAULT_COLOR, visible: true })
+    }
+
+    pub fn add_target_frame(&mut self, pose: Pose, axis_length: f64) -> SceneObjectId {
+        self.add(SceneObject { shape: SceneShape::Frame { axis_length }, pose, color: Self::DEFAULT_COLOR, visible: true })
+    }
+
+    pub fn get(&self, id: SceneObjectId) -> Option<&SceneObject> {
+        self.objects.get(&id)
+    }
+
+    fn get_mut(&mut self, id: SceneObjectId) -> Result<&mut SceneObject, String> {
+        self.objects.get_mut(&id).ok_or_else(|| format!("No scene object {:?}", id))
+    }
+
+    pub fn set_pose(&mut self, id: SceneObjectId, pose: Pose) -> Result<(), String> {
+        self.get_mut(id)?.pose = pose;
+        Ok(())
+    }
+
+    pub fn set_color(&mut self, id: SceneObjectId, color: [f32; 3]) -> Result<(), String> {
+        self.get_mut(id)?.color = color;
+        Ok(())
+    }
+
+    pub fn set_visible(&mut self, id: SceneObjectId, visible: bool) -> Result<(), String> {
+        self.get_mut(id)?.visible = visible;
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: SceneObjectId) -> Result<SceneObject, String> {
+        self.objects.remove(&id).ok_or_else(|| format!("No scene object {:?}", id))
+    }
+
+    pub fn objects(&self) -> impl Iterator<Item = (SceneObjectId, &SceneObject)> {
+        self.objects.iter().map(|(id, obj)| (*id, obj))
+    }
+
+    /// Creates, updates and removes scene nodes to match the object list, and
+    /// draws frame markers (which are immediate-mode lines, not nodes). With
+    /// `show` false every object is hidden, whatever its own visibility.
+    pub(crate) fn sync(&mut self, window: &mut Window, show: bool) {
+        let objects = &self.objects;
+        self.nodes.retain(|id, node| {
+            let keep = objects.contains_key(id);
+            if !keep {
+                window.remove_node(node);
+            }
+            keep
+        });
+
+        for (id, object) in &self.objects {
+            let visible = show && object.visible;
+            let node = match object.shape {
+                SceneShape::Frame { axis_length } => {
+                    if visible {
+                        draw_frame_axes(window, &object.pose, axis_length as f32);
+                    }
+                    continue;
+                }
+                SceneShape::Box { size } => self.nodes.entry(*id).or_insert_with(|| {
+                    window.add_cube(size[0] as f32, size[1] as f32, size[2] as f32)
+                }),
+                SceneShape::Sphere { radius } => self.nodes.entry(*id).or_insert_with(|| {
+                    window.add_sphere(radius as f32)
+                }),
+            };
+
+            node.set_visible(visible);
+            let [r, g, b] = object.color;
+            node.set_color(r, g, b);
+            node.set_local_translation(Translation3::new(
+                object.pose.position.x as f32,
+                object.pose.position.y as f32,
+                object.pose.position.z as f32,
+            ));
+            let rotation = Matrix3::from_fn(|i, j| object.pose.rotation[(i, j)] as f32);
+            node.set_local_rotation(UnitQuaternion::from_matrix(&rotation));
+        }
+    }
+}
+
+/// Draws x/y/z axes of `pose` as red/green/blue lines of `length`.
+pub fn draw_frame_axes(window: &mut Window, pose: &Pose, length: f32) {
+    let pos = Point3::new(
+        pose.position.x as f32,
+        pose.position.y as f32,
+        pose.position.z as f32,
+    );
+    let rot_mat = pose.rotation.cast::<f32>();
+    let x_dir: Vector3<f32> = rot_mat.column(0).into_owned();
+    let y_dir: Vector3<f32> = rot_mat.column(1).into_owned();
+    let z_dir: Vector3<f32> = rot_mat.column(2).into_owned();
+
+    window.draw_line(&pos, &(pos + x_dir * length), &Point3::new(1.0, 0.0, 0.0)); //red
+    window.draw_line(&pos, &(pos + y_dir * length), &Point3::new(0.0, 1.0, 0.0)); //green
+    window.draw_line(&pos, &(pos + z_dir * length), &Point3::new(0.0, 0.0, 1.0)); //blue
+}
@@ -0,0 +1,81 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use dh_arm_model::dh::Pose;
+use dh_arm_model::frequency_response::{render_bode_svg, write_bode_csv, BodePoint, FrequencyResponseTest, SweepAxis};
+use dh_arm_model::units::AngularVelocityUnit;
+
+/// Number of frequencies a chirp response is evaluated at.
+const CHIRP_POINTS: usize = 40;
+
+/// A frequency-response test running on one arm of the simulator.
+pub struct ActiveSweep {
+    pub arm: usize,
+    pub test: FrequencyResponseTest,
+    /// Results go to `<output>.csv` and `<output>.svg`.
+    output: PathBuf,
+    started: Option<Instant>,
+    /// Test time and excitation of the step in progress.
+    pending: (f64, f64),
+    /// Link-side end-effector pose after the previous step, for task-axis velocities.
+    prev_ee: Option<Pose>,
+}
+
+impl ActiveSweep {
+    pub fn new(arm: usize, test: FrequencyResponseTest, output: PathBuf) -> Self {
+        Self { arm, test, output, started: None, pending: (0.0, 0.0), prev_ee: None }
+    }
+
+    /// Seconds into the test; the clock starts with the first step.
+    pub fn elapsed(&self, now: Instant) -> f64 {
+        self.started.map_or(0.0, |s| now.saturating_duration_since(s).as_secs_f64())
+    }
+
+    pub fn is_finished(&self, now: Instant) -> bool {
+        self.started.is_some() && self.test.is_finished(self.elapsed(now))
+    }
+
+    /// Excitation to add to the swept axis for the step starting at `now`.
+    pub fn excitation(&mut self, now: Instant) -> f64 {
+        self.started.get_or_insert(now);
+        let time = self.elapsed(now);
+        let input = self.test.excitation(time);
+        self.pending = (time, input);
+        input
+    }
+
+    /// Records how the arm responded over the step of length `dt` just taken, from
+    /// its link-side joint velocities and end-effector pose (base frame) after it.
+    pub fn record(&mut self, dt: f64, link_vel: &[f64], ee: Pose) {
+        let output = match self.test.axis {
+            SweepAxis::Joint(j) => Some(link_vel[j]),
+            SweepAxis::Task(k) => self.prev_ee.as_ref().map(|prev| task_velocity(prev, &ee, dt)[k]),
+        };
+        self.prev_ee = Some(ee);
+        if let Some(output) = output {
+            self.test.record(self.pending.0, self.pending.1, output);
+        }
+    }
+
+    /// Analyses the recorded response and writes it as CSV and an SVG Bode plot.
+    pub fn finish(self) -> Result<Vec<BodePoint>, String> {
+        let points = self.test.bode(CHIRP_POINTS);
+        write_bode_csv(&points, self.output.with_extension("csv"))?;
+        let svg_path = self.output.with_extension("svg");
+        fs::write(&svg_path, render_bode_svg(&points))
+            .map_err(|e| format!("Failed to write {}: {}", svg_path.display(), e))?;
+        Ok(points)
+    }
+}
+
+/// End-effector velocity between two poses `dt` apart, in the controller's
+/// convention: linear in the base frame, angular in the end-effector frame (deg/s).
+fn task_velocity(prev: &Pose, now: &Pose, dt: f64) -> [f64; 6] {
+    let v = (now.position - prev.position) / dt;
+    // Small-angle rotation vector of the step, from the skew part of the relative rotation
+    let r = prev.rotation.transpose() * now.rotation;
+    let w = [r[(2, 1)] - r[(1, 2)], r[(0, 2)] - r[(2, 0)], r[(1, 0)] - r[(0, 1)]]
+        .map(|c| AngularVelocityUnit::DegreesPerSecond.from_radians_per_second(0.5 * c / dt));
+    [v.x, v.y, v.z, w[0], w[1], w[2]]
+}
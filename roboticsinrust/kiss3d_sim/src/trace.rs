@@ -0,0 +1,117 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use kiss3d::window::Window;
+use kiss3d::nalgebra::Point3;
+use dh_arm_model::dh::Pose;
+use dh_arm_model::manipulability::ManipulabilityEllipsoid;
+
+use crate::scene::draw_frame_axes;
+
+fn to_point(pose: &Pose) -> Point3<f32> {
+    Point3::new(
+        pose.position.x as f32,
+        pose.position.y as f32,
+        pose.position.z as f32,
+    )
+}
+
+/// Recent end-effector positions, drawn as a polyline to show the
+/// path the controller actually tracked.
+#[derive(Debug, Clone)]
+pub struct EeTrail {
+    duration: Duration,
+    points: VecDeque<(Instant, Point3<f32>)>,
+}
+
+impl EeTrail {
+    pub fn new(duration: Duration) -> Self {
+        Self { duration, points: VecDeque::new() }
+    }
+
+    /// Appends the current EE pose and drops samples older than the trail duration.
+    pub fn push(&mut self, now: Instant, ee_pose: &Pose) {
+        self.points.push_back((now, to_point(ee_pose)));
+        while self.points.front().is_some_and(|(t, _)| now.duration_since(*t) > self.duration) {
+            self.points.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+
+    pub fn draw(&self, window: &mut Window, color: &Point3<f32>) {
+        for ((_, a), (_, b)) in self.points.iter().zip(self.points.iter().skip(1)) {
+            window.draw_line(a, b, color);
+        }
+    }
+}
+
+/// A planned Cartesian path shown before it is executed: a polyline through the
+/// waypoint positions plus the full frame at every `frame_stride`-th waypoint.
+#[derive(Debug, Clone)]
+pub struct TrajectoryPreview {
+    pub poses: Vec<Pose>,
+    /// Draw frame axes at every n-th pose (the last pose is always drawn); 0 draws none.
+    pub frame_stride: usize,
+}
+
+impl TrajectoryPreview {
+    pub fn new(poses: Vec<Pose>, frame_stride: usize) -> Self {
+        Self { poses, frame_stride }
+    }
+
+    pub fn draw(&self, window: &mut Window, color: &Point3<f32>, axis_length: f32) {
+        for (a, b) in self.poses.iter().zip(self.poses.iter().skip(1)) {
+            window.draw_line(&to_point(a), &to_point(b), color);
+        }
+
+        if self.frame_stride == 0 {
+            return;
+        }
+        let last = self.poses.len().saturating_sub(1);
+        for (i, pose) in self.poses.iter().enumerate() {
+            if i.is_multiple_of(self.frame_stride) || i == last {
+                draw_frame_axes(window, pose, axis_length);
+            }
+        }
+    }
+}
+
+/// Segments per principal ellipse of a drawn ellipsoid.
+const ELLIPSE_SEGMENTS: usize = 48;
+/// Color of the ellipsoid's weakest (closest to singular) axis.
+const WEAKEST_AXIS_COLOR: [f32; 3] = [1.0, 0.0, 0.0];
+
+/// Draws a manipulability ellipsoid as its three principal ellipses plus its axes,
+/// the weakest one in red. `frame` places the ellipsoid's base-frame axes in the world
+/// and `scale` turns radii into world lengths.
+pub fn draw_ellipsoid(
+    window: &mut Window,
+    ellipsoid: &ManipulabilityEllipsoid,
+    frame: &Pose,
+    scale: f64,
+    color: &Point3<f32>,
+) {
+    let center = frame.position;
+    let axes: [_; 3] = core::array::from_fn(|i| frame.rotation * ellipsoid.axes.column(i) * ellipsoid.radii[i] * scale);
+    let point = |v: nalgebra::Vector3<f64>| {
+        let p = center + v;
+        Point3::new(p.x as f32, p.y as f32, p.z as f32)
+    };
+
+    for (a, b) in [(0, 1), (1, 2), (2, 0)] {
+        let on_ellipse = |k: usize| {
+            let angle = k as f64 / ELLIPSE_SEGMENTS as f64 * std::f64::consts::TAU;
+            point(axes[a] * angle.cos() + axes[b] * angle.sin())
+        };
+        for k in 0..ELLIPSE_SEGMENTS {
+            window.draw_line(&on_ellipse(k), &on_ellipse(k + 1), color);
+        }
+    }
+    for (i, axis) in axes.iter().enumerate() {
+        let axis_color = if i == 2 { Point3::from(WEAKEST_AXIS_COLOR) } else { *color };
+        window.draw_line(&point(-axis), &point(*axis), &axis_color);
+    }
+}
@@ -0,0 +1,233 @@
+//! Python bindings (`import roboticsinrust`) for the URT arm's kinematics and control core.
+//!
+//! Build and install into the active virtualenv with `maturin develop -m roboticsinrust_py/Cargo.toml`.
+//! Units follow the Rust API: joint positions are set in degrees and read back in
+//! radians, lengths are in the DH table's unit (cm for the URT arm).
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use nalgebra::{Matrix3, SVector, Vector3};
+
+use dh_arm_model::approach_retreat::{ApproachRetreat, MotionTarget, PickPlace, Waypoint};
+use dh_arm_model::dh::Pose;
+use dh_arm_model::task_space_pid_controller::TaskSpacePidController;
+use dh_arm_model::urt::{self, NUM_FRAMES, NUM_JOINTS, UrtArm};
+
+/// A frame pose: position [x, y, z] and 3x3 rotation matrix (row-major nested lists).
+#[pyclass(name = "Pose")]
+#[derive(Clone)]
+struct PyPose {
+    inner: Pose,
+}
+
+#[pymethods]
+impl PyPose {
+    #[new]
+    fn new(position: [f64; 3], rotation: [[f64; 3]; 3]) -> Self {
+        let r = Matrix3::from_fn(|i, j| rotation[i][j]);
+        Self { inner: Pose::new(Vector3::from(position), r) }
+    }
+
+    /// Builds a pose from position and yaw/pitch/roll (radians, Z*Y*X order).
+    #[staticmethod]
+    fn from_components(x: f64, y: f64, z: f64, yaw: f64, pitch: f64, roll: f64) -> Self {
+        Self { inner: Pose::from_components(x, y, z, yaw, pitch, roll) }
+    }
+
+    #[getter]
+    fn position(&self) -> [f64; 3] {
+        self.inner.position.into()
+    }
+
+    #[getter]
+    fn rotation(&self) -> [[f64; 3]; 3] {
+        let r = &self.inner.rotation;
+        core::array::from_fn(|i| core::array::from_fn(|j| r[(i, j)]))
+    }
+
+    /// 4x4 homogeneous transform as nested lists.
+    fn to_homogeneous(&self) -> [[f64; 4]; 4] {
+        let m = self.inner.to_homogeneous();
+        core::array::from_fn(|i| core::array::from_fn(|j| m[(i, j)]))
+    }
+
+    fn __repr__(&self) -> String {
+        let p = &self.inner.position;
+        format!("Pose(position=[{:.4}, {:.4}, {:.4}])", p.x, p.y, p.z)
+    }
+}
+
+impl From<Pose> for PyPose {
+    fn from(inner: Pose) -> Self {
+        Self { inner }
+    }
+}
+
+/// The URT 6-DOF arm: forward kinematics, Jacobians and closed-form IK.
+#[pyclass(name = "UrtArm")]
+struct PyUrtArm {
+    inner: UrtArm,
+}
+
+#[pymethods]
+impl PyUrtArm {
+    #[new]
+    #[pyo3(signature = (damping=None))]
+    fn new(damping: Option<f64>) -> Self {
+        Self { inner: urt::urt_arm(damping) }
+    }
+
+    #[getter]
+    fn num_joints(&self) -> usize {
+        NUM_JOINTS
+    }
+
+    #[getter]
+    fn num_frames(&self) -> usize {
+        NUM_FRAMES
+    }
+
+    /// Sets joint positions in degrees (clamped to joint limits).
+    fn set_joint_positions(&mut self, positions: [f64; NUM_JOINTS]) {
+        self.inner.set_joint_positions(&positions);
+    }
+
+    /// Sets joint velocities in degrees/s.
+    fn set_joint_velocities(&mut self, velocities: [f64; NUM_JOINTS]) {
+        self.inner.set_joint_velocities(&velocities);
+    }
+
+    /// Current joint positions in radians.
+    fn joint_positions(&self) -> Vec<f64> {
+        self.inner.joint_positions().iter().copied().collect()
+    }
+
+    /// Current joint velocities in radians/s.
+    fn joint_velocities(&self) -> Vec<f64> {
+        self.inner.joint_velocities().iter().copied().collect()
+    }
+
+    /// Pose of frame `frame_index` (0 is the base frame).
+    fn frame_pose(&self, frame_index: usize) -> PyResult<PyPose> {
+        if frame_index >= NUM_FRAMES {
+            return Err(PyValueError::new_err(format!(
+                "frame_index {} out of range (0..{})", frame_index, NUM_FRAMES
+            )));
+        }
+        Ok(self.inner.frame_pose(frame_index).into())
+    }
+
+    /// Poses of every frame after each DH row, ending with the end-effector.
+    fn frame_poses(&self) -> Vec<PyPose> {
+        self.inner.frame_poses().into_iter().map(PyPose::from).collect()
+    }
+
+    /// Geometric Jacobian (6 x J, nested lists).
+    fn jacobian(&mut self) -> Vec<Vec<f64>> {
+        let j = self.inner.jacobian();
+        j.row_iter().map(|row| row.iter().copied().collect()).collect()
+    }
+
+    /// Damped pseudo-inverse of the Jacobian (J x 6, nested lists).
+    fn inv_jacobian(&mut self) -> Vec<Vec<f64>> {
+        let j = self.inner.inv_jacobian();
+        j.row_iter().map(|row| row.iter().copied().collect()).collect()
+    }
+
+    /// Solves IK for an end-effector pose, returning joint angles in radians.
+    fn solve_ik(&self, target: &PyPose) -> PyResult<Vec<f64>> {
+        self.inner
+            .solve_ik_from_pose(&target.inner)
+            .map(|q| q.to_vec())
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    /// Solves IK from position and yaw/pitch/roll (radians), returning joint angles in radians.
+    fn solve_ik_from_components(
+        &self, x: f64, y: f64, z: f64, yaw: f64, pitch: f64, roll: f64,
+    ) -> PyResult<Vec<f64>> {
+        self.inner
+            .solve_ik_from_components(x, y, z, yaw, pitch, roll)
+            .map(|q| q.to_vec())
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+}
+
+/// Task-space PID controller producing joint velocity commands in degrees/s.
+#[pyclass(name = "TaskSpacePidController")]
+struct PyTaskSpacePidController {
+    inner: TaskSpacePidController,
+}
+
+#[pymethods]
+impl PyTaskSpacePidController {
+    #[new]
+    fn new(kp: [f64; 6], ki: [f64; 6], kd: [f64; 6]) -> Self {
+        Self {
+            inner: TaskSpacePidController::new(
+                SVector::<f64, 6>::from(kp),
+                SVector::<f64, 6>::from(ki),
+                SVector::<f64, 6>::from(kd),
+            ),
+        }
+    }
+
+    /// One control step. See `TaskSpacePidController::compute` for units.
+    fn compute(
+        &mut self,
+        arm: &mut PyUrtArm,
+        task_velocity: [f64; 6],
+        motor_positions: [f64; NUM_JOINTS],
+        motor_velocities: [f64; NUM_JOINTS],
+        dt: f64,
+    ) -> Vec<f64> {
+        self.inner
+            .compute(&mut arm.inner, &task_velocity, &motor_positions, &motor_velocities, dt)
+            .to_vec()
+    }
+}
+
+fn waypoints_to_py(waypoints: Vec<Waypoint>) -> Vec<(String, PyPose)> {
+    waypoints
+        .into_iter()
+        .map(|wp| (format!("{:?}", wp.kind).to_lowercase(), wp.pose.into()))
+        .collect()
+}
+
+/// Approach / target / retreat waypoints for a target, as (kind, Pose) tuples.
+#[pyfunction]
+#[pyo3(signature = (target, approach_distance, retreat_distance=None))]
+fn approach_retreat_waypoints(
+    target: &PyPose,
+    approach_distance: f64,
+    retreat_distance: Option<f64>,
+) -> Vec<(String, PyPose)> {
+    let ar = ApproachRetreat::new(approach_distance, retreat_distance.unwrap_or(approach_distance));
+    waypoints_to_py(MotionTarget::with_approach_retreat(target.inner.clone(), ar).waypoints())
+}
+
+/// Full pick-and-place waypoint sequence, as (kind, Pose) tuples.
+#[pyfunction]
+fn pick_place_waypoints(pick: &PyPose, place: &PyPose, standoff: f64) -> Vec<(String, PyPose)> {
+    let action = PickPlace::new(pick.inner.clone(), place.inner.clone(), ApproachRetreat::symmetric(standoff));
+    waypoints_to_py(action.waypoints())
+}
+
+/// Rotation matrix from yaw (Z), pitch (Y), roll (X) in radians.
+#[pyfunction]
+fn orientation_mat(yaw: f64, pitch: f64, roll: f64) -> [[f64; 3]; 3] {
+    let r = Pose::orientation_mat(yaw, pitch, roll);
+    core::array::from_fn(|i| core::array::from_fn(|j| r[(i, j)]))
+}
+
+#[pymodule]
+fn roboticsinrust(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyPose>()?;
+    m.add_class::<PyUrtArm>()?;
+    m.add_class::<PyTaskSpacePidController>()?;
+    m.add_function(wrap_pyfunction!(approach_retreat_waypoints, m)?)?;
+    m.add_function(wrap_pyfunction!(pick_place_waypoints, m)?)?;
+    m.add_function(wrap_pyfunction!(orientation_mat, m)?)?;
+    Ok(())
+}
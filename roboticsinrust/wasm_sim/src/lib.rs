@@ -0,0 +1,136 @@
+//! WebAssembly build of the URT arm model for in-browser preview and jogging.
+//!
+//! Build with `wasm-pack build wasm_sim --target web --out-dir web/pkg` and serve
+//! `wasm_sim/web/` with any static file server; `index.html` renders the arm with
+//! three.js. The jog loop mirrors `kiss3d_sim`'s `ArmSim::step`.
+
+use wasm_bindgen::prelude::*;
+
+use nalgebra::SVector;
+
+use dh_arm_model::task_space_pid_controller::TaskSpacePidController;
+use dh_arm_model::urt::{self, NUM_FRAMES, NUM_JOINTS, UrtArm};
+
+/// End-effector speed caps for jogging (cm/s, deg/s).
+const MAX_LINEAR_SPEED: f64 = 15.0;
+const MAX_ANGULAR_SPEED: f64 = 60.0;
+
+/// The URT arm plus a task-space controller, driven from JavaScript.
+#[wasm_bindgen]
+pub struct WasmArm {
+    arm: UrtArm,
+    controller: TaskSpacePidController,
+    joint_pos: [f64; NUM_JOINTS], // degrees
+    joint_vel: [f64; NUM_JOINTS], // degrees/s
+}
+
+#[wasm_bindgen]
+impl WasmArm {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WasmArm {
+        let mut sim = WasmArm {
+            arm: urt::urt_arm(None),
+            controller: Self::fresh_controller(),
+            joint_pos: [0.0; NUM_JOINTS],
+            joint_vel: [0.0; NUM_JOINTS],
+        };
+        sim.reset();
+        sim
+    }
+
+    pub fn num_joints(&self) -> usize {
+        NUM_JOINTS
+    }
+
+    pub fn num_frames(&self) -> usize {
+        NUM_FRAMES
+    }
+
+    /// Sets all joint positions in degrees.
+    ///
+    /// The controller is restarted so it holds the new pose instead of pulling
+    /// the arm back to the reference it held before.
+    pub fn set_joint_positions(&mut self, positions_deg: &[f64]) -> Result<(), JsValue> {
+        if positions_deg.len() != NUM_JOINTS {
+            return Err(JsValue::from_str(&format!(
+                "expected {} joint positions, got {}", NUM_JOINTS, positions_deg.len()
+            )));
+        }
+        self.joint_pos.copy_from_slice(positions_deg);
+        self.joint_vel = [0.0; NUM_JOINTS];
+        self.arm.set_joint_positions(&self.joint_pos);
+        self.controller = Self::fresh_controller();
+        Ok(())
+    }
+
+    /// Current joint positions in degrees.
+    pub fn joint_positions(&self) -> Vec<f64> {
+        self.joint_pos.to_vec()
+    }
+
+    /// Frame origins flattened as [x0, y0, z0, x1, ...], starting with the base origin.
+    pub fn frame_positions(&self) -> Vec<f64> {
+        let mut out = Vec::with_capacity(3 * (NUM_FRAMES + 1));
+        out.extend_from_slice(&[0.0, 0.0, 0.0]);
+        for pose in self.arm.frame_poses().iter() {
+            out.extend_from_slice(pose.position.as_slice());
+        }
+        out
+    }
+
+    /// Frame rotations flattened, 9 values per frame in column-major order
+    /// (x-axis, y-axis, z-axis), matching `frame_positions` without the base.
+    pub fn frame_rotations(&self) -> Vec<f64> {
+        let mut out = Vec::with_capacity(9 * NUM_FRAMES);
+        for pose in self.arm.frame_poses().iter() {
+            out.extend_from_slice(pose.rotation.as_slice());
+        }
+        out
+    }
+
+    /// Advances the simulation by `dt` seconds with the given task velocity
+    /// ([vx, vy, vz] world, [wx, wy, wz] end-effector in deg/s), returning joint positions.
+    pub fn jog(&mut self, task_vel: &[f64], dt: f64) -> Result<Vec<f64>, JsValue> {
+        let task_vel: [f64; 6] = task_vel
+            .try_into()
+            .map_err(|_| JsValue::from_str("task velocity must have 6 components"))?;
+
+        let theta_dot = self.controller.compute(&mut self.arm, &task_vel, &self.joint_pos, &self.joint_vel, dt);
+        self.joint_vel = theta_dot;
+        for (pos, vel) in self.joint_pos.iter_mut().zip(self.joint_vel) {
+            *pos += vel * dt;
+        }
+        self.arm.set_joint_positions(&self.joint_pos);
+
+        Ok(self.joint_positions())
+    }
+
+    /// Returns all joints to zero.
+    pub fn reset(&mut self) {
+        self.joint_pos = [0.0; NUM_JOINTS];
+        self.joint_vel = [0.0; NUM_JOINTS];
+        self.arm.set_joint_positions(&self.joint_pos);
+        self.arm.set_joint_velocities(&self.joint_vel);
+        self.controller = Self::fresh_controller();
+    }
+}
+
+impl WasmArm {
+    fn fresh_controller() -> TaskSpacePidController {
+        let mut controller = TaskSpacePidController::new(
+            SVector::<f64, 6>::from([1.0, 1.0, 1.0, 0.0, 0.0, 0.0]),
+            SVector::<f64, 6>::zeros(),
+            SVector::<f64, 6>::zeros(),
+        );
+        // Cap the end-effector speed whatever the page asks for
+        controller.limits.max_linear_speed = Some(MAX_LINEAR_SPEED);
+        controller.limits.max_angular_speed = Some(MAX_ANGULAR_SPEED);
+        controller
+    }
+}
+
+impl Default for WasmArm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
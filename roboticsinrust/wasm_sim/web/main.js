@@ -0,0 +1,149 @@
+// Browser front-end for the wasm_sim crate: renders the URT arm as a stick figure
+// with frame axes (same colours as kiss3d_sim) and jogs it from the keyboard or sliders.
+import * as THREE from 'three';
+import { OrbitControls } from 'three/addons/controls/OrbitControls.js';
+import init, { WasmArm } from './pkg/wasm_sim.js';
+
+// Key -> [task velocity index, increment]; linear in cm/s, angular in deg/s
+const JOG_KEYS = {
+  z: [0, 10], x: [0, -10], c: [1, 10], v: [1, -10], b: [2, 10], n: [2, -10],
+  a: [3, 30], s: [3, -30], d: [4, 30], f: [4, -30], g: [5, 30], h: [5, -30],
+};
+// Jog increments are scaled by this while Shift is held or fine jog is toggled with M
+const FINE_SCALE = 0.1;
+const FRAME_AXIS_LEN = 3.0;
+
+await init();
+const arm = new WasmArm();
+
+// ----- Scene -----
+const renderer = new THREE.WebGLRenderer({ antialias: true });
+renderer.setSize(window.innerWidth, window.innerHeight);
+document.body.appendChild(renderer.domElement);
+
+const scene = new THREE.Scene();
+const camera = new THREE.PerspectiveCamera(45, window.innerWidth / window.innerHeight, 0.1, 1000);
+camera.up.set(0, 0, 1); // DH model is Z-up
+camera.position.set(40, -80, 50);
+const controls = new OrbitControls(camera, renderer.domElement);
+controls.target.set(0, 0, 30);
+controls.update();
+
+scene.add(new THREE.AxesHelper(5));
+const grid = new THREE.GridHelper(100, 20, 0x444444, 0x222222);
+grid.rotation.x = Math.PI / 2;
+scene.add(grid);
+
+const linkMaterial = new THREE.LineBasicMaterial({ color: 0x3399ff });
+const linkGeometry = new THREE.BufferGeometry();
+linkGeometry.setAttribute('position', new THREE.Float32BufferAttribute(new Float32Array(3 * (arm.num_frames() + 1)), 3));
+scene.add(new THREE.Line(linkGeometry, linkMaterial));
+
+const jointMaterial = new THREE.MeshBasicMaterial({ color: 0xff0000 });
+const jointSpheres = [];
+const frameAxes = [];
+for (let i = 0; i < arm.num_frames(); i++) {
+  const sphere = new THREE.Mesh(new THREE.SphereGeometry(0.6), jointMaterial);
+  scene.add(sphere);
+  jointSpheres.push(sphere);
+  const axes = new THREE.AxesHelper(FRAME_AXIS_LEN);
+  axes.matrixAutoUpdate = false;
+  scene.add(axes);
+  frameAxes.push(axes);
+}
+
+function updateArm() {
+  const p = arm.frame_positions();
+  const r = arm.frame_rotations();
+  linkGeometry.attributes.position.array.set(p);
+  linkGeometry.attributes.position.needsUpdate = true;
+  for (let i = 0; i < jointSpheres.length; i++) {
+    const [x, y, z] = [p[3 * (i + 1)], p[3 * (i + 1) + 1], p[3 * (i + 1) + 2]];
+    jointSpheres[i].position.set(x, y, z);
+    const c = r.slice(9 * i, 9 * i + 9); // column-major 3x3
+    frameAxes[i].matrix.set(
+      c[0], c[3], c[6], x,
+      c[1], c[4], c[7], y,
+      c[2], c[5], c[8], z,
+      0, 0, 0, 1,
+    );
+  }
+}
+
+// ----- Joint sliders -----
+const sliders = [];
+const sliderBox = document.getElementById('sliders');
+for (let i = 0; i < arm.num_joints(); i++) {
+  const label = document.createElement('label');
+  const input = Object.assign(document.createElement('input'), { type: 'range', min: -180, max: 180, step: 0.5, value: 0 });
+  input.addEventListener('input', () => {
+    arm.set_joint_positions(Float64Array.from(sliders, s => Number(s.value)));
+    updateArm();
+  });
+  label.append(`J${i + 1} `, input);
+  sliderBox.appendChild(label);
+  sliders.push(input);
+}
+
+function syncSliders(q) {
+  sliders.forEach((s, i) => { s.value = q[i]; });
+}
+
+// ----- Keyboard jog -----
+const held = new Set();
+let fineToggled = false;
+let shiftHeld = false;
+// Shift changes e.key to upper case, so jog keys are matched in lower case
+window.addEventListener('keydown', e => {
+  const key = e.key.toLowerCase();
+  shiftHeld = e.shiftKey;
+  if (key === ' ') {
+    arm.reset();
+    syncSliders(arm.joint_positions());
+    updateArm();
+  } else if (key === 'm' && !e.repeat) {
+    fineToggled = !fineToggled;
+  } else if (key in JOG_KEYS) {
+    held.add(key);
+  }
+});
+window.addEventListener('keyup', e => {
+  shiftHeld = e.shiftKey;
+  held.delete(e.key.toLowerCase());
+});
+
+window.addEventListener('resize', () => {
+  camera.aspect = window.innerWidth / window.innerHeight;
+  camera.updateProjectionMatrix();
+  renderer.setSize(window.innerWidth, window.innerHeight);
+});
+
+// ----- Main loop -----
+const status = document.getElementById('status');
+let last = performance.now();
+function frame(now) {
+  const dt = Math.min((now - last) / 1000, 0.1);
+  last = now;
+
+  // Step every frame (zero velocity = hold), as the kiss3d simulator does
+  const taskVel = new Float64Array(6);
+  const fine = fineToggled || shiftHeld;
+  for (const key of held) {
+    const [idx, inc] = JOG_KEYS[key];
+    taskVel[idx] += fine ? inc * FINE_SCALE : inc;
+  }
+  const q = arm.jog(taskVel, dt);
+  if (held.size > 0) {
+    syncSliders(q);
+  }
+  updateArm();
+
+  status.textContent = 'q [deg]: ' + Array.from(q, v => v.toFixed(1)).join(', ') + (fine ? ' (fine jog)' : '');
+
+  controls.update();
+  renderer.render(scene, camera);
+  requestAnimationFrame(frame);
+}
+
+updateArm();
+requestAnimationFrame(frame);